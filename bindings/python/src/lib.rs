@@ -148,7 +148,7 @@ impl Tokenizer {
         path: &str,
     ) -> PyResult<()> {
         py.detach(|| {
-            save_base64_span_map_path(self.inner.vocab().span_vocab().span_map(), path)
+            save_base64_span_map_path(&self.inner.vocab().span_vocab().to_span_map(), path)
                 .map_err(to_pyerr)
         })
     }
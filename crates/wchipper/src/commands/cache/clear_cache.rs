@@ -0,0 +1,17 @@
+use crate::disk_cache::DiskCacheArgs;
+
+/// Args for the cache clearing command.
+#[derive(clap::Args, Debug)]
+pub struct ClearCacheArgs {
+    #[command(flatten)]
+    disk_cache: DiskCacheArgs,
+}
+
+impl ClearCacheArgs {
+    /// Run the cache clearing command.
+    pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let disk_cache = self.disk_cache.init_disk_cache()?;
+        disk_cache.clear()?;
+        Ok(())
+    }
+}
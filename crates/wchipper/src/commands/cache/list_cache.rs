@@ -0,0 +1,28 @@
+use crate::disk_cache::DiskCacheArgs;
+
+/// Args for the cache listing command.
+#[derive(clap::Args, Debug)]
+pub struct ListCacheArgs {
+    #[command(flatten)]
+    disk_cache: DiskCacheArgs,
+}
+
+impl ListCacheArgs {
+    /// Run the cache listing command.
+    pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let disk_cache = self.disk_cache.init_disk_cache()?;
+        let entries = disk_cache.list_entries()?;
+
+        println!("{}", disk_cache.cache_dir().display());
+        for entry in &entries {
+            println!("  {:>12}  {}", entry.size, entry.resource_id());
+        }
+        println!(
+            "{} files, {} bytes",
+            entries.len(),
+            entries.iter().map(|e| e.size).sum::<u64>()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use clear_cache::ClearCacheArgs;
+use list_cache::ListCacheArgs;
+use prefetch::PrefetchArgs;
+use remove_entry::RemoveEntryArgs;
+use verify_cache::VerifyCacheArgs;
+
+mod clear_cache;
+mod list_cache;
+mod prefetch;
+mod remove_entry;
+mod verify_cache;
+
+/// Subcommands for the cache command.
+#[derive(clap::Subcommand, Debug)]
+pub enum CacheCommand {
+    /// List cached files.
+    #[clap(visible_alias = "ls")]
+    List(ListCacheArgs),
+
+    /// Verify cached files against their known hashes.
+    Verify(VerifyCacheArgs),
+
+    /// Remove a single cached file.
+    #[clap(visible_alias = "rm")]
+    Remove(RemoveEntryArgs),
+
+    /// Remove all cached files.
+    Clear(ClearCacheArgs),
+
+    /// Download the files for a list of models ahead of time.
+    Prefetch(PrefetchArgs),
+}
+
+/// Args for the cache command.
+#[derive(clap::Args, Debug)]
+pub struct CacheArgs {
+    #[clap(subcommand)]
+    pub command: CacheCommand,
+}
+
+impl CacheArgs {
+    /// Run the cache command.
+    pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.command {
+            CacheCommand::List(cmd) => cmd.run(),
+            CacheCommand::Verify(cmd) => cmd.run(),
+            CacheCommand::Remove(cmd) => cmd.run(),
+            CacheCommand::Clear(cmd) => cmd.run(),
+            CacheCommand::Prefetch(cmd) => cmd.run(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::disk_cache::DiskCacheArgs;
+
+/// Args for the prefetch command.
+#[derive(clap::Args, Debug)]
+pub struct PrefetchArgs {
+    #[command(flatten)]
+    disk_cache: DiskCacheArgs,
+
+    /// Models to prefetch; defaults to all publicly downloadable models.
+    models: Vec<String>,
+}
+
+impl PrefetchArgs {
+    /// Run the prefetch command.
+    pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut disk_cache = self.disk_cache.init_disk_cache()?;
+
+        let models = if self.models.is_empty() {
+            wordchipper::pretrained::list_downloadable_models()
+        } else {
+            self.models.clone()
+        };
+
+        for resource in wordchipper::pretrained::prefetch_vocabs(&models, &mut disk_cache)? {
+            println!("{}", resource.resource_id().unwrap_or_default());
+        }
+        Ok(())
+    }
+}
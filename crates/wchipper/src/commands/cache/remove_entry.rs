@@ -0,0 +1,26 @@
+use crate::disk_cache::DiskCacheArgs;
+
+/// Args for the cache entry removal command.
+#[derive(clap::Args, Debug)]
+pub struct RemoveEntryArgs {
+    #[command(flatten)]
+    disk_cache: DiskCacheArgs,
+
+    /// The entry to remove, as listed by `cache list` (e.g. "openai/gpt2/vocab.bpe").
+    resource_id: String,
+}
+
+impl RemoveEntryArgs {
+    /// Run the cache entry removal command.
+    pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let disk_cache = self.disk_cache.init_disk_cache()?;
+
+        let mut parts: Vec<&str> = self.resource_id.split('/').collect();
+        let file_name = parts.pop().unwrap_or_default();
+
+        if !disk_cache.remove_entry(&parts, file_name)? {
+            return Err(format!("no cached file: {}", self.resource_id).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use wordchipper::disk_cache::CacheEntryStatus;
+
+use crate::disk_cache::DiskCacheArgs;
+
+/// Args for the cache verification command.
+#[derive(clap::Args, Debug)]
+pub struct VerifyCacheArgs {
+    #[command(flatten)]
+    disk_cache: DiskCacheArgs,
+
+    /// Remove files which fail verification.
+    #[arg(long)]
+    remove_invalid: bool,
+}
+
+impl VerifyCacheArgs {
+    /// Run the cache verification command.
+    pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let disk_cache = self.disk_cache.init_disk_cache()?;
+
+        let mut invalid = 0;
+        for (entry, status) in wordchipper::pretrained::verify_disk_cache(&disk_cache)? {
+            match &status {
+                CacheEntryStatus::Verified => println!("ok          {}", entry.resource_id()),
+                CacheEntryStatus::Unverified => println!("unverified  {}", entry.resource_id()),
+                CacheEntryStatus::Mismatch { expected, actual } => {
+                    invalid += 1;
+                    println!("MISMATCH    {}", entry.resource_id());
+                    println!("    expected: {expected}");
+                    println!("    actual:   {actual}");
+
+                    if self.remove_invalid {
+                        disk_cache.remove_entry(&entry.context, &entry.file_name)?;
+                        println!("    removed");
+                    }
+                }
+            }
+        }
+
+        if invalid > 0 && !self.remove_invalid {
+            return Err(format!("{invalid} cached files failed verification").into());
+        }
+        Ok(())
+    }
+}
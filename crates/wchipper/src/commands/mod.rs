@@ -1,3 +1,4 @@
+mod cache;
 mod cat;
 mod models;
 mod train;
@@ -5,6 +6,9 @@ mod train;
 /// Subcommands for wchipper
 #[derive(clap::Subcommand, Debug)]
 pub enum Commands {
+    /// Disk cache sub-menu.
+    Cache(cache::CacheArgs),
+
     /// Act as a streaming tokenizer.
     Cat(cat::CatArgs),
 
@@ -19,6 +23,7 @@ impl Commands {
     /// Run the subcommand.
     pub fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
         match self {
+            Commands::Cache(cmd) => cmd.run(),
             Commands::Cat(cmd) => cmd.run(),
             Commands::Models(cmd) => cmd.run(),
             Commands::Train(cmd) => cmd.run(),
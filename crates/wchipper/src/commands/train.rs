@@ -80,7 +80,7 @@ impl TrainArgs {
             log::info!("output: {}", path);
         }
         let mut writer = self.output.open_writer()?;
-        write_base64_span_map(vocab.span_vocab().span_map(), &mut writer)?;
+        write_base64_span_map(&vocab.span_vocab().to_span_map(), &mut writer)?;
 
         Ok(())
     }
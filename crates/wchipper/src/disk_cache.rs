@@ -1,4 +1,16 @@
-use wordchipper::disk_cache::{WordchipperDiskCache, WordchipperDiskCacheOptions};
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use wordchipper::disk_cache::{
+    DEFAULT_DOWNLOAD_RETRIES,
+    DownloadReporter,
+    MirrorFailure,
+    WordchipperDiskCache,
+    WordchipperDiskCacheOptions,
+};
 
 /// Disk cache argument group.
 #[derive(clap::Args, Debug)]
@@ -6,17 +18,108 @@ pub struct DiskCacheArgs {
     /// Cache directory.
     #[arg(long, default_value = None)]
     cache_dir: Option<String>,
+
+    /// Retry rounds when every download mirror fails.
+    #[arg(long, default_value_t = DEFAULT_DOWNLOAD_RETRIES)]
+    download_retries: u32,
+
+    /// Download request timeout, in seconds.
+    #[arg(long, default_value = None)]
+    download_timeout: Option<u64>,
+
+    /// Do not report download progress.
+    #[arg(long)]
+    quiet_downloads: bool,
 }
 
 impl DiskCacheArgs {
     /// Initialize the disk cache.
     pub fn init_disk_cache(&self) -> Result<WordchipperDiskCache, Box<dyn std::error::Error>> {
-        let mut options = WordchipperDiskCacheOptions::default();
+        let mut options = WordchipperDiskCacheOptions::default()
+            .with_retries(self.download_retries)
+            .with_timeout(self.download_timeout.map(Duration::from_secs));
 
         if let Some(cache_dir) = &self.cache_dir {
             options = options.with_cache_dir(Some(cache_dir.clone()));
         }
 
+        if !self.quiet_downloads {
+            options = options.with_reporter(Some(Arc::new(StderrReporter::default())));
+        }
+
         WordchipperDiskCache::new(options)
     }
 }
+
+/// Reports download progress on stderr.
+#[derive(Default)]
+struct StderrReporter {
+    /// The last reported percentage (or MiB, when the size is unknown).
+    last: Mutex<Option<u64>>,
+}
+
+impl DownloadReporter for StderrReporter {
+    fn on_start(
+        &self,
+        file: &str,
+        url: &str,
+        total: Option<u64>,
+    ) {
+        *self.last.lock().unwrap() = None;
+        match total {
+            Some(total) => eprintln!("downloading {file} ({total} bytes) from {url}"),
+            None => eprintln!("downloading {file} from {url}"),
+        }
+    }
+
+    fn on_progress(
+        &self,
+        file: &str,
+        current: u64,
+        total: Option<u64>,
+    ) {
+        let step = match total {
+            Some(total) if total > 0 => current * 100 / total,
+            _ => current >> 20,
+        };
+
+        let mut last = self.last.lock().unwrap();
+        if *last == Some(step) {
+            return;
+        }
+        *last = Some(step);
+
+        match total {
+            Some(_) => eprint!("\r  {file}: {step:>3}%"),
+            None => eprint!("\r  {file}: {step} MiB"),
+        }
+        let _ = std::io::stderr().flush();
+    }
+
+    fn on_failure(
+        &self,
+        failure: &MirrorFailure,
+    ) {
+        eprintln!(
+            "\n  {}: {} failed: {}",
+            failure.file, failure.url, failure.reason
+        );
+    }
+
+    fn on_retry(
+        &self,
+        file: &str,
+        round: u32,
+        delay: Duration,
+    ) {
+        eprintln!("  {file}: retry {round} in {delay:?}");
+    }
+
+    fn on_finish(
+        &self,
+        file: &str,
+        bytes: u64,
+    ) {
+        eprintln!("\n  {file}: done ({bytes} bytes)");
+    }
+}
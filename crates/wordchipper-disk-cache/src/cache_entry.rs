@@ -0,0 +1,54 @@
+//! # Cache Entries
+
+use std::path::PathBuf;
+
+/// A file held in the disk cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    /// The context (key) directories, relative to the cache directory.
+    pub context: Vec<String>,
+
+    /// The file name.
+    pub file_name: String,
+
+    /// The full path to the file.
+    pub path: PathBuf,
+
+    /// The file size, in bytes.
+    pub size: u64,
+}
+
+impl CacheEntry {
+    /// The ``"{context}/.../{file_name}"`` identifier of this entry.
+    pub fn resource_id(&self) -> String {
+        let mut parts: Vec<&str> = self.context.iter().map(|s| s.as_str()).collect();
+        parts.push(&self.file_name);
+        parts.join("/")
+    }
+}
+
+/// The result of verifying a [`CacheEntry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheEntryStatus {
+    /// The file matches its expected hash.
+    Verified,
+
+    /// The file does not match its expected hash.
+    Mismatch {
+        /// The expected hex SHA-256 digest.
+        expected: String,
+
+        /// The actual hex SHA-256 digest.
+        actual: String,
+    },
+
+    /// There is no known hash for the file.
+    Unverified,
+}
+
+impl CacheEntryStatus {
+    /// Is the entry known to be bad?
+    pub fn is_mismatch(&self) -> bool {
+        matches!(self, Self::Mismatch { .. })
+    }
+}
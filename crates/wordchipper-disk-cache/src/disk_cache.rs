@@ -1,16 +1,36 @@
 //! # Wordchipper Disk Cache
 
 use std::{
+    fmt,
     fs,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
 use downloader::{Download, Downloader};
 
-use crate::{WORDCHIPPER_CACHE_CONFIG, path_utils};
+use crate::{
+    CacheEntry,
+    CacheEntryStatus,
+    DownloadError,
+    DownloadReporter,
+    MirrorFailure,
+    WORDCHIPPER_CACHE_CONFIG,
+    WORDCHIPPER_OFFLINE,
+    download_reporter::ReporterAdapter,
+    hashing::{file_sha256, verify_file_sha256},
+    path_utils,
+};
+
+/// Default number of retry rounds after every mirror has failed.
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 2;
+
+/// Default delay before the first retry round; doubled on each further round.
+pub const DEFAULT_DOWNLOAD_BACKOFF: Duration = Duration::from_millis(500);
 
 /// Options for [`WordchipperDiskCache`].
-#[derive(Clone, Default, Debug)]
+#[derive(Clone)]
 pub struct WordchipperDiskCacheOptions {
     /// Optional path to the cache directory.
     pub cache_dir: Option<PathBuf>,
@@ -20,6 +40,60 @@ pub struct WordchipperDiskCacheOptions {
 
     /// Optional [`Downloader`] builder.
     pub downloader: Option<fn() -> Downloader>,
+
+    /// Never download; only serve files already in the cache.
+    ///
+    /// The [`WORDCHIPPER_OFFLINE`] environment variable also enables this.
+    pub offline: bool,
+
+    /// Retry rounds after every mirror has failed.
+    ///
+    /// Each round tries every mirror once, in order.
+    pub retries: u32,
+
+    /// Delay before the first retry round; doubled on each further round.
+    pub retry_backoff: Duration,
+
+    /// Optional request timeout for the default [`Downloader`].
+    ///
+    /// Ignored when a `downloader` builder is provided.
+    pub timeout: Option<Duration>,
+
+    /// Optional progress and failure reporter.
+    pub reporter: Option<Arc<dyn DownloadReporter>>,
+}
+
+impl Default for WordchipperDiskCacheOptions {
+    fn default() -> Self {
+        Self {
+            cache_dir: None,
+            data_dir: None,
+            downloader: None,
+            offline: false,
+            retries: DEFAULT_DOWNLOAD_RETRIES,
+            retry_backoff: DEFAULT_DOWNLOAD_BACKOFF,
+            timeout: None,
+            reporter: None,
+        }
+    }
+}
+
+impl fmt::Debug for WordchipperDiskCacheOptions {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("WordchipperDiskCacheOptions")
+            .field("cache_dir", &self.cache_dir)
+            .field("data_dir", &self.data_dir)
+            .field("downloader", &self.downloader)
+            .field("offline", &self.offline)
+            .field("retries", &self.retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("timeout", &self.timeout)
+            .field("reporter", &self.reporter.is_some())
+            .finish()
+    }
 }
 
 impl WordchipperDiskCacheOptions {
@@ -49,6 +123,58 @@ impl WordchipperDiskCacheOptions {
         self.downloader = downloader;
         self
     }
+
+    /// Set offline mode.
+    pub fn with_offline(
+        mut self,
+        offline: bool,
+    ) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Set the number of retry rounds.
+    pub fn with_retries(
+        mut self,
+        retries: u32,
+    ) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the initial retry backoff.
+    pub fn with_retry_backoff(
+        mut self,
+        retry_backoff: Duration,
+    ) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// Set the request timeout.
+    pub fn with_timeout(
+        mut self,
+        timeout: Option<Duration>,
+    ) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the progress reporter.
+    pub fn with_reporter(
+        mut self,
+        reporter: Option<Arc<dyn DownloadReporter>>,
+    ) -> Self {
+        self.reporter = reporter;
+        self
+    }
+}
+
+/// Is offline mode forced by the [`WORDCHIPPER_OFFLINE`] environment variable?
+fn offline_from_env() -> bool {
+    std::env::var(WORDCHIPPER_OFFLINE)
+        .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "" | "0" | "false"))
+        .unwrap_or(false)
 }
 
 /// Disk cache for downloaded files.
@@ -65,6 +191,18 @@ pub struct WordchipperDiskCache {
 
     /// Connection pool for downloading files.
     downloader: Downloader,
+
+    /// Never download.
+    offline: bool,
+
+    /// Retry rounds after every mirror has failed.
+    retries: u32,
+
+    /// Initial retry delay.
+    retry_backoff: Duration,
+
+    /// Progress and failure reporter.
+    reporter: Option<Arc<dyn DownloadReporter>>,
 }
 
 impl Default for WordchipperDiskCache {
@@ -86,13 +224,25 @@ impl WordchipperDiskCache {
 
         let downloader = match options.downloader {
             Some(builder) => builder(),
-            None => Downloader::builder().build()?,
+            None => {
+                let mut builder = Downloader::builder();
+                // Retries and mirror failover are handled by the cache.
+                builder.retries(1);
+                if let Some(timeout) = options.timeout {
+                    builder.timeout(timeout);
+                }
+                builder.build()?
+            }
         };
 
         Ok(Self {
             cache_dir,
             data_dir,
             downloader,
+            offline: options.offline || offline_from_env(),
+            retries: options.retries,
+            retry_backoff: options.retry_backoff,
+            reporter: options.reporter,
         })
     }
 
@@ -111,6 +261,26 @@ impl WordchipperDiskCache {
         &self.downloader
     }
 
+    /// Get the progress reporter.
+    pub fn reporter(&self) -> Option<&Arc<dyn DownloadReporter>> {
+        self.reporter.as_ref()
+    }
+
+    /// Set the progress reporter.
+    pub fn set_reporter(
+        &mut self,
+        reporter: Option<Arc<dyn DownloadReporter>>,
+    ) {
+        self.reporter = reporter;
+    }
+
+    /// Is this cache in offline mode?
+    ///
+    /// An offline cache never downloads, regardless of the `download` flag.
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
     /// Get the cache path for the given key.
     ///
     /// * Does not check that the path exists.
@@ -133,13 +303,17 @@ impl WordchipperDiskCache {
 
     /// Loads a cached file from a specified path or downloads it if it does not exist.
     ///
+    /// See [`load_cached_path_verified`](Self::load_cached_path_verified); this
+    /// does not check the file contents.
+    ///
     /// # Arguments
     /// * `context`: A slice of `C` containing path-related context used in determining the
     ///   cache location. These paths are combined to build the cached file's location.
     /// * `urls`: A slice of string references specifying the URLs to download the file from
     ///   if it is not already cached.
     /// * `download`: A boolean flag indicating whether to attempt downloading the file
-    ///   from the provided URLs if it does not already exist in the cache.
+    ///   from the provided URLs if it does not already exist in the cache;
+    ///   ignored (treated as `false`) in [`offline`](Self::offline) mode.
     ///
     /// # Returns
     /// * Returns a [`PathBuf`] pointing to the cached file if it exists or is successfully downloaded.
@@ -154,31 +328,266 @@ impl WordchipperDiskCache {
         context: &[C],
         urls: &[S],
         download: bool,
-        /* TODO: hash: Option<&str>, */
+    ) -> Result<PathBuf, Box<dyn std::error::Error>>
+    where
+        C: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        self.load_cached_path_verified(context, urls, download, None)
+    }
+
+    /// Loads a cached file, verified against a SHA-256 digest, or downloads it.
+    ///
+    /// Downloads are written to a temporary `*.part` file next to the target,
+    /// verified, and then atomically renamed into place; so an interrupted
+    /// download never leaves a truncated file at the cached path.
+    ///
+    /// Mirrors are tried in order; if every mirror fails, the whole list is
+    /// retried with exponential backoff. Progress and each failed attempt are
+    /// sent to the [`reporter`](Self::reporter); and if every attempt fails,
+    /// the returned error is a [`DownloadError`] listing them.
+    ///
+    /// An advisory lock on a sibling `*.lock` file serializes downloads of the
+    /// same file, so parallel processes on one host share a single download.
+    ///
+    /// # Arguments
+    /// * `context`: A slice of `C` containing path-related context used in determining the
+    ///   cache location. These paths are combined to build the cached file's location.
+    /// * `urls`: A slice of string references specifying the URLs to download the file from
+    ///   if it is not already cached.
+    /// * `download`: A boolean flag indicating whether to attempt downloading the file
+    ///   from the provided URLs if it does not already exist in the cache;
+    ///   ignored (treated as `false`) in [`offline`](Self::offline) mode.
+    /// * `hash`: An optional hex SHA-256 digest the file must match.
+    ///
+    /// # Returns
+    /// * Returns a [`PathBuf`] pointing to the cached file if it exists or is successfully downloaded.
+    /// * Returns an error if the file is not found in the cache and downloading is not allowed
+    ///   or fails.
+    ///
+    /// # Errors
+    /// * Returns an error if the cached file does not exist (or fails verification)
+    ///   and `download` is `false`.
+    /// * Returns an error if the downloading process fails.
+    /// * Returns an error if the downloaded file does not match `hash`.
+    pub fn load_cached_path_verified<C, S>(
+        &mut self,
+        context: &[C],
+        urls: &[S],
+        download: bool,
+        hash: Option<&str>,
     ) -> Result<PathBuf, Box<dyn std::error::Error>>
     where
         C: AsRef<Path>,
         S: AsRef<str>,
     {
         let urls: Vec<_> = urls.iter().map(|s| s.as_ref()).collect();
-        let mut dl = Download::new_mirrored(&urls);
-        let file_name = dl.file_name.clone();
+        let file_name = Download::new_mirrored(&urls).file_name;
         let path = self.cache_path(context, &file_name);
-        dl.file_name = path.clone();
 
-        if path.exists() {
+        if is_valid_cached_file(&path, hash)? {
             return Ok(path);
         }
 
-        if !download {
+        if !download || self.offline {
+            if path.exists() {
+                return Err(format!("cached file failed verification: {}", path.display()).into());
+            }
             return Err(format!("cached file not found: {}", path.display()).into());
         }
 
         fs::create_dir_all(path.parent().unwrap())?;
 
-        self.downloader.download(&[dl])?;
+        // Held until the end of the function; released on drop.
+        let lock_file = fs::File::create(sibling_path(&path, "lock"))?;
+        lock_file.lock()?;
+
+        // Another process may have completed the download while we waited.
+        if is_valid_cached_file(&path, hash)? {
+            return Ok(path);
+        }
+
+        let part_path = sibling_path(&path, &format!("{}.part", std::process::id()));
+        let file = file_name.to_string_lossy().into_owned();
+
+        let mut failures = Vec::new();
+        for round in 0..=self.retries {
+            if round > 0 {
+                let delay = self
+                    .retry_backoff
+                    .saturating_mul(2u32.saturating_pow(round - 1));
+                if let Some(reporter) = &self.reporter {
+                    reporter.on_retry(&file, round, delay);
+                }
+                std::thread::sleep(delay);
+            }
+
+            for url in &urls {
+                if part_path.exists() {
+                    fs::remove_file(&part_path)?;
+                }
+
+                match self.download_part(&file, url, &part_path, hash) {
+                    Ok(bytes) => {
+                        fs::rename(&part_path, &path)?;
+                        if let Some(reporter) = &self.reporter {
+                            reporter.on_finish(&file, bytes);
+                        }
+                        return Ok(path);
+                    }
+                    Err(reason) => {
+                        if part_path.exists() {
+                            fs::remove_file(&part_path)?;
+                        }
+                        let failure = MirrorFailure {
+                            file: file.clone(),
+                            url: url.to_string(),
+                            round,
+                            reason,
+                        };
+                        if let Some(reporter) = &self.reporter {
+                            reporter.on_failure(&failure);
+                        }
+                        failures.push(failure);
+                    }
+                }
+            }
+        }
+
+        Err(Box::new(DownloadError { file, failures }))
+    }
+
+    /// Download and verify a single temporary file from one mirror.
+    ///
+    /// # Returns
+    /// The size of the file, or the reason the attempt failed.
+    fn download_part(
+        &mut self,
+        file: &str,
+        url: &str,
+        part_path: &Path,
+        hash: Option<&str>,
+    ) -> Result<u64, String> {
+        let mut dl = Download::new(url).file_name(part_path);
+        if let Some(reporter) = &self.reporter {
+            dl = dl.progress(Arc::new(ReporterAdapter::new(reporter.clone(), file, url)));
+        }
+
+        let summaries = self.downloader.download(&[dl]).map_err(|e| e.to_string())?;
+        for summary in summaries {
+            match summary {
+                Ok(_) => (),
+                Err(downloader::Error::Download(summary)) => {
+                    return Err(match summary.status.last() {
+                        Some((_, status)) => format!("HTTP status {status}"),
+                        None => "request failed".to_string(),
+                    });
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+
+        if !part_path.is_file() {
+            return Err("download produced no file".to_string());
+        }
 
-        Ok(path)
+        if let Some(hash) = hash
+            && !verify_file_sha256(part_path, hash).map_err(|e| e.to_string())?
+        {
+            return Err(format!("hash mismatch (expected {hash})"));
+        }
+
+        fs::metadata(part_path)
+            .map(|m| m.len())
+            .map_err(|e| e.to_string())
+    }
+
+    /// List the files held in the cache.
+    ///
+    /// Lock files and partial downloads are skipped.
+    ///
+    /// # Returns
+    /// The entries, sorted by [`CacheEntry::resource_id`];
+    /// empty if the cache directory does not exist.
+    pub fn list_entries(&self) -> std::io::Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        if self.cache_dir.is_dir() {
+            collect_entries(&self.cache_dir, &mut Vec::new(), &mut entries)?;
+        }
+        entries.sort_by_key(|e| e.resource_id());
+        Ok(entries)
+    }
+
+    /// Verify a cache entry against an expected hash.
+    ///
+    /// # Arguments
+    /// * `entry` - the entry to check.
+    /// * `hash` - the expected hex SHA-256 digest, if known.
+    pub fn verify_entry(
+        &self,
+        entry: &CacheEntry,
+        hash: Option<&str>,
+    ) -> std::io::Result<CacheEntryStatus> {
+        let Some(hash) = hash else {
+            return Ok(CacheEntryStatus::Unverified);
+        };
+        let actual = file_sha256(&entry.path)?;
+        if actual.eq_ignore_ascii_case(hash) {
+            Ok(CacheEntryStatus::Verified)
+        } else {
+            Ok(CacheEntryStatus::Mismatch {
+                expected: hash.to_ascii_lowercase(),
+                actual,
+            })
+        }
+    }
+
+    /// Remove a single cached file.
+    ///
+    /// Takes the download lock first; so this waits for an in-progress
+    /// download of the same file. The sibling `*.lock` file is left in place:
+    /// unlinking a lock another process may hold would let a third process
+    /// lock a fresh file, and download concurrently.
+    ///
+    /// # Arguments
+    /// * `context` - prefix dirs, inserted between `self.cache_dir` and `file`.
+    /// * `file` - the final file name.
+    ///
+    /// # Returns
+    /// `true` if the file existed and was removed.
+    pub fn remove_entry<C, F>(
+        &self,
+        context: &[C],
+        file: F,
+    ) -> std::io::Result<bool>
+    where
+        C: AsRef<Path>,
+        F: AsRef<Path>,
+    {
+        let path = self.cache_path(context, file);
+        if !path.is_file() {
+            return Ok(false);
+        }
+
+        // Held until the end of the function; released on drop.
+        let lock_file = fs::File::create(sibling_path(&path, "lock"))?;
+        lock_file.lock()?;
+
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remove everything in the cache directory.
+    ///
+    /// The data directory is untouched.
+    pub fn clear(&self) -> std::io::Result<()> {
+        if self.cache_dir.exists() {
+            fs::remove_dir_all(&self.cache_dir)?;
+        }
+        Ok(())
     }
 
     /// Get the data path for the given key.
@@ -202,17 +611,88 @@ impl WordchipperDiskCache {
     }
 }
 
+/// Build a path alongside `path`, with `suffix` appended to the file name.
+fn sibling_path(
+    path: &Path,
+    suffix: &str,
+) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Is this file name a download lock or a partial download?
+fn is_bookkeeping_file(name: &str) -> bool {
+    name.ends_with(".lock") || name.ends_with(".part")
+}
+
+/// Recursively collect [`CacheEntry`]s under `dir`.
+fn collect_entries(
+    dir: &Path,
+    context: &mut Vec<String>,
+    entries: &mut Vec<CacheEntry>,
+) -> std::io::Result<()> {
+    for item in fs::read_dir(dir)? {
+        let item = item?;
+        let name = item.file_name().to_string_lossy().into_owned();
+        let file_type = item.file_type()?;
+
+        if file_type.is_dir() {
+            context.push(name);
+            collect_entries(&item.path(), context, entries)?;
+            context.pop();
+        } else if file_type.is_file() && !is_bookkeeping_file(&name) {
+            entries.push(CacheEntry {
+                context: context.clone(),
+                file_name: name,
+                path: item.path(),
+                size: item.metadata()?.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check whether a cached file exists and is trustworthy.
+///
+/// With a `hash`, the file must match it; without one, the file must be non-empty.
+fn is_valid_cached_file(
+    path: &Path,
+    hash: Option<&str>,
+) -> std::io::Result<bool> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+    match hash {
+        Some(hash) => verify_file_sha256(path, hash),
+        None => Ok(fs::metadata(path)?.len() > 0),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{env, path::PathBuf};
+    use std::{
+        env,
+        fs,
+        io::{Read, Write},
+        net::TcpListener,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
 
     use serial_test::serial;
 
     use crate::{
+        CacheEntryStatus,
+        DownloadError,
+        DownloadReporter,
+        MirrorFailure,
         WORDCHIPPER_CACHE_CONFIG,
         WORDCHIPPER_CACHE_DIR,
         WORDCHIPPER_DATA_DIR,
-        disk_cache::{WordchipperDiskCache, WordchipperDiskCacheOptions},
+        disk_cache::{WordchipperDiskCache, WordchipperDiskCacheOptions, sibling_path},
     };
 
     #[test]
@@ -293,4 +773,271 @@ mod tests {
         let path = cache.cache_path(&["prefix"], "file.txt");
         assert_eq!(path, cache.cache_dir.join("prefix").join("file.txt"));
     }
+
+    #[test]
+    fn test_load_cached_path_verification() {
+        let dir = tempdir::TempDir::new("disk_cache").unwrap();
+        let mut cache = WordchipperDiskCache::new(
+            WordchipperDiskCacheOptions::default().with_cache_dir(Some(dir.path())),
+        )
+        .unwrap();
+
+        let urls = ["https://example.invalid/vocab/abc.txt"];
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        // Missing.
+        assert!(
+            cache
+                .load_cached_path_verified(&["ctx"], &urls, false, Some(hash))
+                .is_err()
+        );
+
+        let path = cache.cache_path(&["ctx"], "abc.txt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        // Empty files are never trusted.
+        fs::write(&path, "").unwrap();
+        assert!(cache.load_cached_path(&["ctx"], &urls, false).is_err());
+
+        // Hash mismatch.
+        fs::write(&path, "ab").unwrap();
+        assert!(
+            cache
+                .load_cached_path_verified(&["ctx"], &urls, false, Some(hash))
+                .is_err()
+        );
+        assert_eq!(
+            cache.load_cached_path(&["ctx"], &urls, false).unwrap(),
+            path
+        );
+
+        // Hash match.
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            cache
+                .load_cached_path_verified(&["ctx"], &urls, false, Some(hash))
+                .unwrap(),
+            path
+        );
+    }
+
+    #[test]
+    fn test_offline() {
+        let dir = tempdir::TempDir::new("disk_cache").unwrap();
+        let mut cache = WordchipperDiskCache::new(
+            WordchipperDiskCacheOptions::default()
+                .with_cache_dir(Some(dir.path()))
+                .with_offline(true),
+        )
+        .unwrap();
+        assert!(cache.offline());
+
+        let urls = ["https://example.invalid/vocab/abc.txt"];
+        let err = cache.load_cached_path(&["ctx"], &urls, true).unwrap_err();
+        assert!(err.to_string().contains("cached file not found"));
+        assert!(!dir.path().join("ctx").exists());
+    }
+
+    #[test]
+    fn test_entries() {
+        let dir = tempdir::TempDir::new("disk_cache").unwrap();
+        let cache = WordchipperDiskCache::new(
+            WordchipperDiskCacheOptions::default().with_cache_dir(Some(dir.path().join("cache"))),
+        )
+        .unwrap();
+
+        assert!(cache.list_entries().unwrap().is_empty());
+
+        let abc = cache.cache_path(&["a", "b"], "abc.txt");
+        let xyz = cache.cache_path(&["x"], "xyz.txt");
+        fs::create_dir_all(abc.parent().unwrap()).unwrap();
+        fs::create_dir_all(xyz.parent().unwrap()).unwrap();
+        fs::write(&abc, "abc").unwrap();
+        fs::write(&xyz, "xyz!").unwrap();
+        fs::write(sibling_path(&abc, "lock"), "").unwrap();
+        fs::write(sibling_path(&xyz, "123.part"), "xy").unwrap();
+
+        let entries = cache.list_entries().unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.resource_id(), e.size))
+                .collect::<Vec<_>>(),
+            vec![("a/b/abc.txt".to_string(), 3), ("x/xyz.txt".to_string(), 4)]
+        );
+        assert_eq!(entries[0].path, abc);
+
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(
+            cache.verify_entry(&entries[0], Some(hash)).unwrap(),
+            CacheEntryStatus::Verified
+        );
+        assert!(
+            cache
+                .verify_entry(&entries[1], Some(hash))
+                .unwrap()
+                .is_mismatch()
+        );
+        assert_eq!(
+            cache.verify_entry(&entries[1], None).unwrap(),
+            CacheEntryStatus::Unverified
+        );
+
+        // Removal waits for the lock; and leaves the lock file in place.
+        let lock_file = fs::File::create(sibling_path(&abc, "lock")).unwrap();
+        lock_file.lock().unwrap();
+        std::thread::scope(|scope| {
+            let remover = scope.spawn(|| cache.remove_entry(&["a", "b"], "abc.txt").unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            assert!(abc.exists());
+            lock_file.unlock().unwrap();
+            assert!(remover.join().unwrap());
+        });
+        assert!(!abc.exists());
+        assert!(!cache.remove_entry(&["a", "b"], "abc.txt").unwrap());
+        assert!(sibling_path(&abc, "lock").exists());
+        assert_eq!(cache.list_entries().unwrap().len(), 1);
+
+        cache.clear().unwrap();
+        assert!(!cache.cache_dir().exists());
+        assert!(cache.list_entries().unwrap().is_empty());
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl DownloadReporter for Recorder {
+        fn on_failure(
+            &self,
+            failure: &MirrorFailure,
+        ) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("failure {} {}", failure.round, failure.url));
+        }
+
+        fn on_retry(
+            &self,
+            _file: &str,
+            round: u32,
+            _delay: Duration,
+        ) {
+            self.events.lock().unwrap().push(format!("retry {round}"));
+        }
+
+        fn on_finish(
+            &self,
+            file: &str,
+            bytes: u64,
+        ) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("finish {file} {bytes}"));
+        }
+    }
+
+    /// Serve a single HTTP response with `body` on a local port.
+    fn serve_once(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        });
+        format!("http://{addr}/files/abc.txt")
+    }
+
+    #[test]
+    fn test_download_failover() {
+        let dir = tempdir::TempDir::new("disk_cache").unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let mut cache = WordchipperDiskCache::new(
+            WordchipperDiskCacheOptions::default()
+                .with_cache_dir(Some(dir.path()))
+                .with_retries(1)
+                .with_retry_backoff(Duration::from_millis(1))
+                .with_timeout(Some(Duration::from_secs(10)))
+                .with_reporter(Some(recorder.clone())),
+        )
+        .unwrap();
+
+        // Nothing listens on port 1.
+        let dead = "http://127.0.0.1:1/files/abc.txt";
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        let err = cache
+            .load_cached_path_verified(&["ctx"], &[dead, dead], true, Some(hash))
+            .unwrap_err();
+        let err = err.downcast_ref::<DownloadError>().unwrap();
+        assert_eq!(err.file, "abc.txt");
+        assert_eq!(err.failures.len(), 4);
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            vec![
+                format!("failure 0 {dead}"),
+                format!("failure 0 {dead}"),
+                "retry 1".to_string(),
+                format!("failure 1 {dead}"),
+                format!("failure 1 {dead}"),
+            ]
+        );
+        recorder.events.lock().unwrap().clear();
+
+        let live = serve_once("abc");
+        let path = cache
+            .load_cached_path_verified(&["ctx"], &[dead, live.as_str()], true, Some(hash))
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abc");
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            vec![format!("failure 0 {dead}"), "finish abc.txt 3".to_string()]
+        );
+
+        // No partial downloads are left behind.
+        let names: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .filter(|n| n.ends_with(".part"))
+            .collect();
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn test_download_hash_mismatch() {
+        let dir = tempdir::TempDir::new("disk_cache").unwrap();
+        let mut cache = WordchipperDiskCache::new(
+            WordchipperDiskCacheOptions::default()
+                .with_cache_dir(Some(dir.path()))
+                .with_retries(0),
+        )
+        .unwrap();
+
+        let live = serve_once("abd");
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let err = cache
+            .load_cached_path_verified(&["ctx"], &[live.as_str()], true, Some(hash))
+            .unwrap_err();
+        let err = err.downcast_ref::<DownloadError>().unwrap();
+        assert!(err.failures[0].reason.starts_with("hash mismatch"));
+        assert!(!cache.cache_path(&["ctx"], "abc.txt").exists());
+    }
+
+    #[test]
+    fn test_sibling_path() {
+        assert_eq!(
+            sibling_path(Path::new("/a/b/c.txt"), "lock"),
+            PathBuf::from("/a/b/c.txt.lock")
+        );
+    }
 }
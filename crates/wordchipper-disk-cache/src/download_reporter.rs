@@ -0,0 +1,251 @@
+//! # Download Progress and Failure Reporting
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Observer for the progress of [`WordchipperDiskCache`](crate::WordchipperDiskCache) downloads.
+///
+/// All methods have no-op defaults; `file` is the name of the file being fetched.
+pub trait DownloadReporter: Send + Sync {
+    /// A download attempt from `url` has started.
+    ///
+    /// `total` is the expected size in bytes, if the server reported one.
+    fn on_start(
+        &self,
+        file: &str,
+        url: &str,
+        total: Option<u64>,
+    ) {
+        let _ = (file, url, total);
+    }
+
+    /// `current` bytes of `total` have been received.
+    fn on_progress(
+        &self,
+        file: &str,
+        current: u64,
+        total: Option<u64>,
+    ) {
+        let _ = (file, current, total);
+    }
+
+    /// A download attempt from one mirror has failed.
+    fn on_failure(
+        &self,
+        failure: &MirrorFailure,
+    ) {
+        let _ = failure;
+    }
+
+    /// Every mirror has failed; retrying after `delay`.
+    fn on_retry(
+        &self,
+        file: &str,
+        round: u32,
+        delay: Duration,
+    ) {
+        let _ = (file, round, delay);
+    }
+
+    /// The download completed and was verified.
+    fn on_finish(
+        &self,
+        file: &str,
+        bytes: u64,
+    ) {
+        let _ = (file, bytes);
+    }
+}
+
+/// A failed download attempt from a single mirror.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorFailure {
+    /// The file being downloaded.
+    pub file: String,
+
+    /// The mirror URL.
+    pub url: String,
+
+    /// The retry round, starting at `0`.
+    pub round: u32,
+
+    /// Why the attempt failed.
+    pub reason: String,
+}
+
+impl fmt::Display for MirrorFailure {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "[round {}] {}: {}", self.round, self.url, self.reason)
+    }
+}
+
+/// Every mirror failed, on every retry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadError {
+    /// The file being downloaded.
+    pub file: String,
+
+    /// Every failed attempt, in order.
+    pub failures: Vec<MirrorFailure>,
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "download of {} failed after {} attempts",
+            self.file,
+            self.failures.len()
+        )?;
+        for failure in &self.failures {
+            write!(f, "\n  {failure}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// Adapts a [`DownloadReporter`] to the [`downloader`] progress interface.
+pub(crate) struct ReporterAdapter {
+    reporter: Arc<dyn DownloadReporter>,
+    file: String,
+    url: String,
+    total: Mutex<Option<u64>>,
+}
+
+impl ReporterAdapter {
+    pub(crate) fn new(
+        reporter: Arc<dyn DownloadReporter>,
+        file: &str,
+        url: &str,
+    ) -> Self {
+        Self {
+            reporter,
+            file: file.to_string(),
+            url: url.to_string(),
+            total: Mutex::new(None),
+        }
+    }
+}
+
+impl downloader::progress::Reporter for ReporterAdapter {
+    fn setup(
+        &self,
+        max_progress: Option<u64>,
+        _message: &str,
+    ) {
+        *self.total.lock().unwrap() = max_progress;
+        self.reporter.on_start(&self.file, &self.url, max_progress);
+    }
+
+    fn progress(
+        &self,
+        current: u64,
+    ) {
+        let total = *self.total.lock().unwrap();
+        self.reporter.on_progress(&self.file, current, total);
+    }
+
+    fn set_message(
+        &self,
+        _message: &str,
+    ) {
+    }
+
+    fn done(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use downloader::progress::Reporter;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl DownloadReporter for Recorder {
+        fn on_start(
+            &self,
+            file: &str,
+            url: &str,
+            total: Option<u64>,
+        ) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {file} {url} {total:?}"));
+        }
+
+        fn on_progress(
+            &self,
+            file: &str,
+            current: u64,
+            total: Option<u64>,
+        ) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("progress {file} {current} {total:?}"));
+        }
+    }
+
+    #[test]
+    fn test_reporter_adapter() {
+        let recorder = Arc::new(Recorder::default());
+        let adapter = ReporterAdapter::new(recorder.clone(), "a.txt", "https://x/a.txt");
+
+        adapter.setup(Some(10), "msg");
+        adapter.progress(4);
+        adapter.progress(10);
+        adapter.done();
+
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            vec![
+                "start a.txt https://x/a.txt Some(10)",
+                "progress a.txt 4 Some(10)",
+                "progress a.txt 10 Some(10)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_download_error_display() {
+        let err = DownloadError {
+            file: "a.txt".to_string(),
+            failures: vec![
+                MirrorFailure {
+                    file: "a.txt".to_string(),
+                    url: "https://x/a.txt".to_string(),
+                    round: 0,
+                    reason: "HTTP status 503".to_string(),
+                },
+                MirrorFailure {
+                    file: "a.txt".to_string(),
+                    url: "https://y/a.txt".to_string(),
+                    round: 0,
+                    reason: "hash mismatch".to_string(),
+                },
+            ],
+        };
+        assert_eq!(
+            err.to_string(),
+            "download of a.txt failed after 2 attempts\n  \
+             [round 0] https://x/a.txt: HTTP status 503\n  \
+             [round 0] https://y/a.txt: hash mismatch"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! # File Hashing
+//!
+//! This crate sits below `wordchipper` in the dependency graph, so it cannot
+//! use `wordchipper::support::hashing`; keep the two in step.
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+/// Compute the lowercase hex SHA-256 digest of a file.
+///
+/// # Arguments
+/// * `path` - the file to hash.
+pub fn file_sha256<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+/// Check a file against an expected hex SHA-256 digest.
+///
+/// The comparison is case-insensitive.
+///
+/// # Arguments
+/// * `path` - the file to check.
+/// * `hash` - the expected hex digest.
+pub fn verify_file_sha256<P: AsRef<Path>>(
+    path: P,
+    hash: &str,
+) -> io::Result<bool> {
+    Ok(file_sha256(path)?.eq_ignore_ascii_case(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_file_sha256() {
+        let dir = tempdir::TempDir::new("hashing").unwrap();
+        let path = dir.path().join("abc.txt");
+        fs::write(&path, "abc").unwrap();
+
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(file_sha256(&path).unwrap(), expected);
+        assert!(verify_file_sha256(&path, expected).unwrap());
+        assert!(verify_file_sha256(&path, &expected.to_uppercase()).unwrap());
+        assert!(!verify_file_sha256(&path, &expected[1..]).unwrap());
+    }
+}
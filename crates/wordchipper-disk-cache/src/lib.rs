@@ -1,12 +1,26 @@
 //! # Wordchipper Disk Cache
 #![warn(missing_docs)]
 
+mod cache_entry;
 mod disk_cache;
+mod download_reporter;
+mod hashing;
 mod path_resolver;
 mod path_utils;
 
 #[doc(inline)]
-pub use disk_cache::{WordchipperDiskCache, WordchipperDiskCacheOptions};
+pub use cache_entry::{CacheEntry, CacheEntryStatus};
+#[doc(inline)]
+pub use disk_cache::{
+    DEFAULT_DOWNLOAD_BACKOFF,
+    DEFAULT_DOWNLOAD_RETRIES,
+    WordchipperDiskCache,
+    WordchipperDiskCacheOptions,
+};
+#[doc(inline)]
+pub use download_reporter::{DownloadError, DownloadReporter, MirrorFailure};
+#[doc(inline)]
+pub use hashing::{file_sha256, verify_file_sha256};
 #[doc(inline)]
 pub use path_resolver::PathResolver;
 #[doc(inline)]
@@ -16,6 +30,9 @@ pub use path_utils::extend_path;
 pub const WORDCHIPPER_CACHE_DIR: &str = "WORDCHIPPER_CACHE_DIR";
 /// Environment variable key to override the default data directory.
 pub const WORDCHIPPER_DATA_DIR: &str = "WORDCHIPPER_DATA_DIR";
+/// Environment variable key to force offline mode; any value other than
+/// `""`, `"0"` or `"false"` disables downloads.
+pub const WORDCHIPPER_OFFLINE: &str = "WORDCHIPPER_OFFLINE";
 
 /// Default [`PathResolver`] for wordchipper.
 pub const WORDCHIPPER_CACHE_CONFIG: PathResolver = PathResolver {
@@ -72,7 +72,7 @@
 //!         .into();
 //!
 //!     if let Some(path) = vocab_save_path {
-//!         save_base64_span_map_path(&vocab.span_vocab().span_map(), &path)
+//!         save_base64_span_map_path(&vocab.span_vocab().to_span_map(), &path)
 //!             .expect("failed to save vocab");
 //!         println!("- tiktoken vocab: {path:?}");
 //!     }
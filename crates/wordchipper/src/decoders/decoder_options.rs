@@ -7,7 +7,9 @@ use crate::{
     TokenType,
     UnifiedTokenVocab,
     alloc::sync::Arc,
-    decoders::SlabIndexDecoder,
+    decoders::{SlabIndexDecoder, UnigramDecoder, WordPieceDecoder},
+    support::parallel_backend::ParallelBackend,
+    vocab::{UnigramVocab, WordPieceVocab},
 };
 
 /// Options for configuring a [`TokenDecoder`].
@@ -18,6 +20,9 @@ pub struct TokenDecoderOptions {
     ///
     /// Enabling parallelism will request a threaded implementation.
     pub parallel: bool,
+
+    /// The threading backend, when [`parallel`](Self::parallel).
+    pub parallel_backend: ParallelBackend,
 }
 
 impl TokenDecoderOptions {
@@ -49,18 +54,83 @@ impl TokenDecoderOptions {
         self
     }
 
+    /// Gets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn parallel_backend(&self) -> ParallelBackend {
+        self.parallel_backend
+    }
+
+    /// Sets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn set_parallel_backend(
+        &mut self,
+        parallel_backend: ParallelBackend,
+    ) {
+        self.parallel_backend = parallel_backend;
+    }
+
+    /// Sets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn with_parallel_backend(
+        mut self,
+        parallel_backend: ParallelBackend,
+    ) -> Self {
+        self.set_parallel_backend(parallel_backend);
+        self
+    }
+
     /// Build a [`TokenDecoder`] for the given vocab.
     pub fn build<T: TokenType>(
         &self,
         vocab: Arc<UnifiedTokenVocab<T>>,
+    ) -> Arc<dyn TokenDecoder<T>> {
+        self.wrap(Arc::new(SlabIndexDecoder::from_vocab(vocab)))
+    }
+
+    /// Build a [`WordPieceDecoder`] for the given wordpiece vocab.
+    pub fn build_wordpiece<T: TokenType>(
+        &self,
+        wordpiece_vocab: Arc<WordPieceVocab<T>>,
+    ) -> Arc<dyn TokenDecoder<T>> {
+        self.wrap(Arc::new(WordPieceDecoder::from_vocab(wordpiece_vocab)))
+    }
+
+    /// Build a [`UnigramDecoder`] for the given unigram vocab.
+    pub fn build_unigram<T: TokenType>(
+        &self,
+        unigram_vocab: Arc<UnigramVocab<T>>,
+    ) -> Arc<dyn TokenDecoder<T>> {
+        self.wrap(Arc::new(UnigramDecoder::from_vocab(unigram_vocab)))
+    }
+
+    /// Wrap a decoder per the parallelism options.
+    fn wrap<T: TokenType>(
+        &self,
+        dec: Arc<dyn TokenDecoder<T>>,
     ) -> Arc<dyn TokenDecoder<T>> {
         #[allow(unused_mut)]
-        let mut dec: Arc<dyn TokenDecoder<T>> = Arc::new(SlabIndexDecoder::from_vocab(vocab));
+        let mut dec = dec;
 
-        #[cfg(feature = "parallel")]
         if self.parallel {
-            use crate::support::concurrency::rayon::ParallelRayonDecoder;
-            dec = Arc::new(ParallelRayonDecoder::new(dec));
+            match self.parallel_backend {
+                #[cfg(feature = "parallel")]
+                ParallelBackend::Rayon => {
+                    use crate::support::concurrency::rayon::ParallelRayonDecoder;
+                    dec = Arc::new(ParallelRayonDecoder::new(dec));
+                }
+                #[cfg(feature = "concurrent")]
+                ParallelBackend::Threads => {
+                    use crate::support::concurrency::scoped_threads::ParallelThreadDecoder;
+                    dec = Arc::new(ParallelThreadDecoder::new(dec));
+                }
+                #[allow(unreachable_patterns)]
+                backend => log::warn!(
+                    "parallel backend {backend:?} is not compiled in; using a single-threaded decoder"
+                ),
+            }
         }
 
         dec
@@ -29,6 +29,9 @@ mod decoder_options;
 mod slab_index_decoder;
 mod token_decoder;
 mod token_dict_decoder;
+mod token_pieces;
+mod unigram_decoder;
+mod wordpiece_decoder;
 
 #[doc(inline)]
 pub use decode_results::*;
@@ -40,3 +43,9 @@ pub use slab_index_decoder::*;
 pub use token_decoder::*;
 #[doc(inline)]
 pub use token_dict_decoder::*;
+#[doc(inline)]
+pub use token_pieces::*;
+#[doc(inline)]
+pub use unigram_decoder::*;
+#[doc(inline)]
+pub use wordpiece_decoder::*;
@@ -2,6 +2,7 @@
 
 use crate::{
     TokenType,
+    WCError,
     WCResult,
     alloc::{string::String, vec::Vec},
     decoders::{BatchDecodeResult, DecodeResult},
@@ -83,6 +84,57 @@ pub trait TokenDecoder<T: TokenType>: Send + Sync {
             .collect::<WCResult<Vec<_>>>()
             .map(BatchDecodeResult::from)
     }
+
+    /// Decodes tokens into bytes, failing on the first unknown token.
+    ///
+    /// ## Arguments
+    /// * `tokens` - A slice of tokens to decode.
+    ///
+    /// ## Returns
+    /// The decoded bytes.
+    ///
+    /// ## Errors
+    /// [`WCError::UnknownToken`] naming the first token which could not be decoded,
+    /// and its index in `tokens`.
+    fn try_decode_to_bytes_strict(
+        &self,
+        tokens: &[T],
+    ) -> WCResult<Vec<u8>> {
+        let res = self.try_decode_to_bytes(tokens)?;
+        if let Some(remaining) = res.remaining {
+            let index = tokens.len() - remaining;
+            return Err(WCError::UnknownToken {
+                // Every `TokenType` is an unsigned primitive, which fits in `u64`.
+                token: tokens[index].to_u64().unwrap_or(u64::MAX),
+                index,
+            });
+        }
+        Ok(res.value)
+    }
+
+    /// Decodes tokens into a string, failing on unknown tokens or invalid UTF-8.
+    ///
+    /// Unlike [`try_decode_to_string`](Self::try_decode_to_string),
+    /// invalid UTF-8 sequences are not replaced.
+    ///
+    /// ## Arguments
+    /// * `tokens` - A slice of tokens to decode.
+    ///
+    /// ## Returns
+    /// The decoded string.
+    ///
+    /// ## Errors
+    /// * [`WCError::UnknownToken`] - see [`try_decode_to_bytes_strict`](Self::try_decode_to_bytes_strict).
+    /// * [`WCError::InvalidUtf8`] - naming the byte offset of the first invalid sequence.
+    fn try_decode_to_string_strict(
+        &self,
+        tokens: &[T],
+    ) -> WCResult<String> {
+        let bytes = self.try_decode_to_bytes_strict(tokens)?;
+        String::from_utf8(bytes).map_err(|e| WCError::InvalidUtf8 {
+            position: e.utf8_error().valid_up_to(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -112,6 +164,42 @@ mod tests {
         let result = decoder.try_decode_to_bytes(&tokens).unwrap();
         assert_eq!(result.value, "hello world".as_bytes().to_vec());
         assert_eq!(result.remaining, Some(2));
+
+        match decoder.try_decode_to_bytes_strict(&tokens) {
+            Err(WCError::UnknownToken { token, index }) => {
+                assert_eq!(token, 256);
+                assert_eq!(index, 11);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_strict() {
+        type T = u32;
+        let decoder: ByteDecoder<T> = ByteDecoder::default();
+
+        let tokens: Vec<T> = "héllo".as_bytes().iter().map(|&b| b as T).collect();
+        assert_eq!(
+            decoder.try_decode_to_bytes_strict(&tokens).unwrap(),
+            "héllo".as_bytes().to_vec()
+        );
+        assert_eq!(
+            decoder.try_decode_to_string_strict(&tokens).unwrap(),
+            "héllo".to_string()
+        );
+
+        // Drop the second byte of 'é'.
+        let mut broken = tokens.clone();
+        broken.remove(2);
+        match decoder.try_decode_to_string_strict(&broken) {
+            Err(WCError::InvalidUtf8 { position }) => assert_eq!(position, 1),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert_eq!(
+            decoder.try_decode_to_string(&broken).unwrap().unwrap(),
+            "h\u{FFFD}llo".to_string()
+        );
     }
 
     #[test]
@@ -0,0 +1,174 @@
+//! # Per-Token Piece Decoding
+//!
+//! Decodes each token individually, for token visualizers and debugging.
+
+use crate::{
+    TokenType,
+    WCError,
+    WCResult,
+    alloc::{string::String, vec::Vec},
+    decoders::TokenDecoder,
+    support::strings::escape_bytes_for_display,
+    vocab::{SpecialVocab, VocabIndex},
+};
+
+/// A single decoded token.
+///
+/// ## Style Hints
+///
+/// Instance names should prefer `piece`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenPiece<T: TokenType> {
+    token: T,
+    bytes: Vec<u8>,
+    display: String,
+    is_special: bool,
+}
+
+impl<T: TokenType> TokenPiece<T> {
+    /// Create a new piece.
+    ///
+    /// The display rendering is computed from `bytes`;
+    /// see [`escape_bytes_for_display`].
+    ///
+    /// ## Arguments
+    /// * `token` - The token.
+    /// * `bytes` - The raw bytes of the token.
+    /// * `is_special` - Is this a special token?
+    pub fn new(
+        token: T,
+        bytes: Vec<u8>,
+        is_special: bool,
+    ) -> Self {
+        let display = escape_bytes_for_display(&bytes);
+        Self {
+            token,
+            bytes,
+            display,
+            is_special,
+        }
+    }
+
+    /// Get the token.
+    pub fn token(&self) -> T {
+        self.token
+    }
+
+    /// Get the raw bytes of the token.
+    ///
+    /// These may not be valid UTF-8 on their own.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Get the escaped, human-readable rendering of the token.
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+
+    /// Is this a special token?
+    pub fn is_special(&self) -> bool {
+        self.is_special
+    }
+}
+
+/// Decode each token into a [`TokenPiece`].
+///
+/// ## Arguments
+/// * `decoder` - The decoder used to expand each token.
+/// * `specials` - The special vocabulary used to flag special tokens.
+/// * `tokens` - The tokens to decode.
+///
+/// ## Returns
+/// One piece per token, in order.
+///
+/// ## Errors
+/// [`WCError::UnknownToken`] if a token cannot be decoded.
+pub fn decode_pieces<T: TokenType>(
+    decoder: &dyn TokenDecoder<T>,
+    specials: &SpecialVocab<T>,
+    tokens: &[T],
+) -> WCResult<Vec<TokenPiece<T>>> {
+    let special_tokens = specials.tokens();
+
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(index, &token)| {
+            let res = decoder.try_decode_to_bytes(&[token])?;
+            if !res.is_complete() {
+                return Err(WCError::UnknownToken {
+                    token: token.to_u64().unwrap_or(u64::MAX),
+                    index,
+                });
+            }
+            Ok(TokenPiece::new(
+                token,
+                res.value,
+                special_tokens.contains(&token),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenEncoder,
+        TokenizerOptions,
+        alloc::{sync::Arc, vec},
+        pretrained::openai::OA_CL100K_BASE_PATTERN,
+        spanners::TextSpanningConfig,
+        vocab::{
+            UnifiedTokenVocab,
+            utility::testing::{build_test_shift_byte_vocab, build_test_vocab},
+        },
+    };
+
+    #[test]
+    fn test_decode_pieces() {
+        type T = u16;
+
+        let mut vocab: UnifiedTokenVocab<T> = build_test_vocab(
+            build_test_shift_byte_vocab(10),
+            TextSpanningConfig::from_pattern(OA_CL100K_BASE_PATTERN),
+        );
+        vocab.special_vocab_mut().add_str_word("<|end|>", 1000);
+        let vocab: Arc<UnifiedTokenVocab<T>> = vocab.into();
+
+        let tokenizer = TokenizerOptions::default()
+            .with_parallel(false)
+            .build(vocab.clone());
+
+        let tokens = tokenizer.try_encode("hello world\n<|end|>").unwrap();
+        let pieces = tokenizer.decode_pieces(&tokens).unwrap();
+
+        assert_eq!(pieces.len(), tokens.len());
+        assert_eq!(pieces.iter().map(|p| p.token()).collect::<Vec<_>>(), tokens);
+        assert_eq!(
+            pieces.iter().map(|p| p.display()).collect::<Vec<_>>(),
+            vec!["hello", "Ġ", "world", "\\n", "<|end|>"]
+        );
+        assert_eq!(
+            pieces.iter().map(|p| p.is_special()).collect::<Vec<_>>(),
+            vec![false, false, false, false, true]
+        );
+        assert_eq!(pieces[2].bytes(), b"world");
+
+        // Partial UTF-8: 'é' split across two byte tokens.
+        let byte_vocab = vocab.byte_vocab();
+        let split = vec![byte_vocab.get_token(0xc3), byte_vocab.get_token(0xa9)];
+        let pieces = tokenizer.decode_pieces(&split).unwrap();
+        assert_eq!(pieces[0].display(), "\\xc3");
+        assert_eq!(pieces[1].display(), "\\xa9");
+
+        match tokenizer.decode_pieces(&[tokens[0], 5000]) {
+            Err(WCError::UnknownToken { token, index }) => {
+                assert_eq!(token, 5000);
+                assert_eq!(index, 1);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! # Unigram Token Decoder
+
+use crate::{
+    TokenType,
+    WCResult,
+    alloc::{sync::Arc, vec::Vec},
+    decoders::{DecodeResult, TokenDecoder},
+    types::WCHashMap,
+    vocab::{DEFAULT_BYTE_PER_TOKEN_RATIO, SENTENCEPIECE_SPACE, UnigramVocab},
+};
+
+/// A [`TokenDecoder<T>`] for [`UnigramVocab`] tokens.
+///
+/// Pieces are joined with each [`SENTENCEPIECE_SPACE`] marker replaced by
+/// a space; and the leading space of the decoded text, the sentencepiece
+/// dummy prefix, is removed. So `[▁hello, ▁wor, ld]` decodes as `"hello world"`.
+#[derive(Clone)]
+pub struct UnigramDecoder<T: TokenType> {
+    /// `{ T -> surface bytes }`.
+    token_pieces: WCHashMap<T, Vec<u8>>,
+}
+
+impl<T: TokenType> UnigramDecoder<T> {
+    /// Build a [`UnigramDecoder`] from a [`UnigramVocab`].
+    pub fn from_vocab(unigram_vocab: Arc<UnigramVocab<T>>) -> Self {
+        let token_pieces = unigram_vocab
+            .token_spans()
+            .into_iter()
+            .map(|(t, span)| (t, replace_space_markers(&span)))
+            .collect();
+        Self { token_pieces }
+    }
+
+    /// Lookup a token's surface bytes.
+    pub fn lookup_piece(
+        &self,
+        token: &T,
+    ) -> Option<&[u8]> {
+        self.token_pieces.get(token).map(Vec::as_slice)
+    }
+}
+
+/// Replace each [`SENTENCEPIECE_SPACE`] in `span` with a space.
+fn replace_space_markers(span: &[u8]) -> Vec<u8> {
+    let marker = SENTENCEPIECE_SPACE.as_bytes();
+    let mut out = Vec::with_capacity(span.len());
+    let mut rest = span;
+    while !rest.is_empty() {
+        if rest.starts_with(marker) {
+            out.push(b' ');
+            rest = &rest[marker.len()..];
+        } else {
+            out.push(rest[0]);
+            rest = &rest[1..];
+        }
+    }
+    out
+}
+
+impl<T: TokenType> TokenDecoder<T> for UnigramDecoder<T> {
+    fn try_decode_to_bytes(
+        &self,
+        tokens: &[T],
+    ) -> WCResult<DecodeResult<Vec<u8>>> {
+        let capacity = (tokens.len() as f32 * DEFAULT_BYTE_PER_TOKEN_RATIO) as usize;
+        let mut value = Vec::with_capacity(capacity);
+
+        let mut consumed = 0;
+        for t in tokens {
+            let Some(span) = self.lookup_piece(t) else {
+                break;
+            };
+            match (consumed, span) {
+                (0, [b' ', rest @ ..]) => value.extend_from_slice(rest),
+                _ => value.extend_from_slice(span),
+            }
+            consumed += 1;
+        }
+        Ok(DecodeResult::new(value, Some(tokens.len() - consumed)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vocab::{DEFAULT_UNIGRAM_UNK_WORD, sentencepiece_spanning_config};
+
+    #[test]
+    fn test_decode() {
+        let words = [
+            ("<unk>", 0.0),
+            ("<s>", 0.0),
+            ("\u{2581}hello", -1.0),
+            ("\u{2581}wor", -1.0),
+            ("ld", -1.0),
+            ("a\u{2581}b", -1.0),
+        ];
+        let vocab: Arc<UnigramVocab<u32>> = UnigramVocab::from_scored_words(
+            sentencepiece_spanning_config(),
+            words,
+            &["<unk>", "<s>"],
+            DEFAULT_UNIGRAM_UNK_WORD,
+        )
+        .unwrap()
+        .into();
+        let decoder = UnigramDecoder::from_vocab(vocab);
+
+        assert_eq!(decoder.lookup_piece(&3), Some(b" wor".as_slice()));
+        assert_eq!(decoder.lookup_piece(&5), Some(b"a b".as_slice()));
+
+        assert_eq!(
+            decoder
+                .try_decode_to_string(&[2, 3, 4, 5, 0])
+                .unwrap()
+                .unwrap(),
+            "hello worlda b<unk>"
+        );
+        assert_eq!(
+            decoder.try_decode_to_string(&[1, 2]).unwrap().unwrap(),
+            "<s> hello"
+        );
+
+        let res = decoder.try_decode_to_bytes(&[3, 4, 99, 2]).unwrap();
+        assert_eq!(res.value, b"world".to_vec());
+        assert_eq!(res.remaining, Some(2));
+    }
+}
@@ -3,6 +3,7 @@
 use crate::{
     TokenEncoderOptions,
     TokenType,
+    WCError,
     alloc::{sync::Arc, vec, vec::Vec},
     decoders::TokenDecoder,
     support::{strings::string_from_utf8_lossy, traits::static_is_send_sync_check},
@@ -87,4 +88,21 @@ pub fn common_decoder_unit_test<T: TokenType, D: TokenDecoder<T>>(
         assert_eq!(ctx.value, sample.as_bytes().to_vec());
         assert_eq!(ctx.remaining, Some(expected_stack.len()));
     }
+
+    // Strict Decode
+    for (sample, tokens) in samples.iter().zip(token_batch.iter()) {
+        assert_eq!(
+            &decoder.try_decode_to_string_strict(tokens).unwrap(),
+            sample
+        );
+    }
+    for (tokens, partial) in token_batch.iter().zip(partial_tokens.iter()) {
+        match decoder.try_decode_to_bytes_strict(partial) {
+            Err(WCError::UnknownToken { token, index }) => {
+                assert_eq!(token, novel_token.to_u64().unwrap());
+                assert_eq!(index, tokens.len());
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
 }
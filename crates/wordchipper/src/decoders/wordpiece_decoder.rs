@@ -0,0 +1,109 @@
+//! # Wordpiece Token Decoder
+
+use crate::{
+    TokenType,
+    WCResult,
+    alloc::{sync::Arc, vec::Vec},
+    decoders::{DecodeResult, TokenDecoder},
+    types::WCHashMap,
+    vocab::{DEFAULT_BYTE_PER_TOKEN_RATIO, WordPieceVocab},
+};
+
+/// A [`TokenDecoder<T>`] for [`WordPieceVocab`] tokens.
+///
+/// Continuation pieces are joined to the preceding piece with their
+/// prefix removed; every other token is separated from the preceding
+/// text by a space. So `[un, ##aff, ##able, ",", the]` decodes as
+/// `"unaffable , the"`: the encoding does not record the original spacing.
+#[derive(Clone)]
+pub struct WordPieceDecoder<T: TokenType> {
+    /// `{ T -> (surface bytes, is_continuation) }`.
+    token_pieces: WCHashMap<T, (Vec<u8>, bool)>,
+}
+
+impl<T: TokenType> WordPieceDecoder<T> {
+    /// Build a [`WordPieceDecoder`] from a [`WordPieceVocab`].
+    pub fn from_vocab(wordpiece_vocab: Arc<WordPieceVocab<T>>) -> Self {
+        let prefix = wordpiece_vocab.continuation_prefix().as_bytes();
+        let token_pieces = wordpiece_vocab
+            .token_spans()
+            .into_iter()
+            .map(|(t, span)| {
+                let piece = match span.strip_prefix(prefix) {
+                    Some(rest) if !prefix.is_empty() && !rest.is_empty() => (rest.to_vec(), true),
+                    _ => (span, false),
+                };
+                (t, piece)
+            })
+            .collect();
+        Self { token_pieces }
+    }
+
+    /// Lookup a token's surface bytes, and whether it is a continuation piece.
+    pub fn lookup_piece(
+        &self,
+        token: &T,
+    ) -> Option<(&[u8], bool)> {
+        self.token_pieces
+            .get(token)
+            .map(|(span, cont)| (span.as_slice(), *cont))
+    }
+}
+
+impl<T: TokenType> TokenDecoder<T> for WordPieceDecoder<T> {
+    fn try_decode_to_bytes(
+        &self,
+        tokens: &[T],
+    ) -> WCResult<DecodeResult<Vec<u8>>> {
+        let capacity = (tokens.len() as f32 * DEFAULT_BYTE_PER_TOKEN_RATIO) as usize;
+        let mut value = Vec::with_capacity(capacity);
+
+        let mut consumed = 0;
+        for t in tokens {
+            let Some((span, cont)) = self.lookup_piece(t) else {
+                break;
+            };
+            if !cont && !value.is_empty() {
+                value.push(b' ');
+            }
+            value.extend_from_slice(span);
+            consumed += 1;
+        }
+        Ok(DecodeResult::new(value, Some(tokens.len() - consumed)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vocab::{BERT_SPECIAL_WORDS, DEFAULT_WORDPIECE_UNK_WORD, bert_spanning_config};
+
+    #[test]
+    fn test_decode() {
+        let words = ["[UNK]", "[CLS]", "un", "##aff", "##able", ",", "the", "##"];
+        let vocab: Arc<WordPieceVocab<u32>> = WordPieceVocab::from_words(
+            bert_spanning_config(true),
+            words,
+            &BERT_SPECIAL_WORDS,
+            DEFAULT_WORDPIECE_UNK_WORD,
+        )
+        .unwrap()
+        .into();
+        let decoder = WordPieceDecoder::from_vocab(vocab);
+
+        assert_eq!(decoder.lookup_piece(&3), Some((b"aff".as_slice(), true)));
+        assert_eq!(decoder.lookup_piece(&7), Some((b"##".as_slice(), false)));
+
+        assert_eq!(
+            decoder
+                .try_decode_to_string(&[1, 2, 3, 4, 5, 6, 7])
+                .unwrap()
+                .unwrap(),
+            "[CLS] unaffable , the ##"
+        );
+
+        let res = decoder.try_decode_to_bytes(&[2, 3, 99, 6]).unwrap();
+        assert_eq!(res.value, b"unaff".to_vec());
+        assert_eq!(res.remaining, Some(2));
+    }
+}
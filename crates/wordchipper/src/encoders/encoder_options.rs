@@ -2,13 +2,29 @@
 //!
 //! Options for building a [`TokenEncoder`].
 
+use core::num::NonZeroUsize;
+
 use crate::{
     TokenEncoder,
     TokenType,
     UnifiedTokenVocab,
-    alloc::sync::Arc,
-    encoders::token_span_encoder::{SpanEncoderSelector, TokenSpanEncoder},
+    WCResult,
+    alloc::{boxed::Box, sync::Arc},
+    encoders::token_span_encoder::{
+        SpanEncoder,
+        SpanEncoderSelector,
+        TokenSpanEncoder,
+        span_encoders::{
+            BpeBacktrackSpanEncoder,
+            DEFAULT_FALLBACK_SPAN_BYTES,
+            FallbackSpanEncoder,
+            UnigramSpanEncoder,
+            WordPieceSpanEncoder,
+        },
+    },
     spanners::TextSpannerBuilder,
+    support::parallel_backend::ParallelBackend,
+    vocab::{UnigramVocab, WordPieceVocab},
 };
 
 /// Options for configuring a [`TokenEncoder`].
@@ -30,10 +46,34 @@ pub struct TokenEncoderOptions {
     /// Should the encoder be threaded?
     pub parallel: bool,
 
+    /// The threading backend, when [`parallel`](Self::parallel).
+    pub parallel_backend: ParallelBackend,
+
     /// Should the encoder be concurrent?
     ///
     /// Concurrent encoders select defaults to be called concurrently.
     pub concurrent: bool,
+
+    /// Max cached compound spans per span encoder.
+    ///
+    /// `0` disables the span cache; see [`TokenSpanEncoder::with_span_cache`].
+    /// Needs the `concurrent` feature.
+    pub span_cache_size: usize,
+
+    /// Max word span length, in bytes.
+    ///
+    /// `0` disables the cap; see [`TextSpannerBuilder::set_max_span_bytes`].
+    pub max_span_bytes: usize,
+
+    /// Min compound span length handed to the [`BpeBacktrackSpanEncoder`].
+    ///
+    /// `0` disables the fallback; see [`FallbackSpanEncoder`].
+    pub fallback_span_bytes: usize,
+
+    /// Min document piece length, in bytes, when encoding one text in parallel.
+    ///
+    /// `0` disables splitting; see [`set_document_piece_bytes`](Self::set_document_piece_bytes).
+    pub document_piece_bytes: usize,
 }
 
 impl Default for TokenEncoderOptions {
@@ -42,7 +82,12 @@ impl Default for TokenEncoderOptions {
             span_encoder: None,
             accelerated_lexers: true,
             parallel: false,
+            parallel_backend: ParallelBackend::default(),
             concurrent: false,
+            span_cache_size: 0,
+            max_span_bytes: 0,
+            fallback_span_bytes: DEFAULT_FALLBACK_SPAN_BYTES,
+            document_piece_bytes: 0,
         }
     }
 }
@@ -155,6 +200,34 @@ impl TokenEncoderOptions {
         self
     }
 
+    /// Gets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn parallel_backend(&self) -> ParallelBackend {
+        self.parallel_backend
+    }
+
+    /// Sets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn set_parallel_backend(
+        &mut self,
+        parallel_backend: ParallelBackend,
+    ) {
+        self.parallel_backend = parallel_backend;
+    }
+
+    /// Sets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn with_parallel_backend(
+        mut self,
+        parallel_backend: ParallelBackend,
+    ) -> Self {
+        self.set_parallel_backend(parallel_backend);
+        self
+    }
+
     /// Returns true if either parallel or concurrent is enabled.
     pub fn is_concurrent(&self) -> bool {
         self.concurrent || self.parallel
@@ -197,28 +270,331 @@ impl TokenEncoderOptions {
         self
     }
 
+    /// Gets the configured span cache size.
+    ///
+    /// The max cached compound spans per span encoder; `0` disables the cache.
+    pub fn span_cache_size(&self) -> usize {
+        self.span_cache_size
+    }
+
+    /// Sets the configured span cache size.
+    ///
+    /// The max cached compound spans per span encoder; `0` disables the cache.
+    pub fn set_span_cache_size(
+        &mut self,
+        span_cache_size: usize,
+    ) {
+        self.span_cache_size = span_cache_size;
+    }
+
+    /// Sets the configured span cache size.
+    ///
+    /// The max cached compound spans per span encoder; `0` disables the cache.
+    pub fn with_span_cache_size(
+        mut self,
+        span_cache_size: usize,
+    ) -> Self {
+        self.set_span_cache_size(span_cache_size);
+        self
+    }
+
+    /// Gets the configured max word span length, in bytes.
+    ///
+    /// Longer word spans are split into chunks, at char boundaries;
+    /// `0` disables the cap.
+    pub fn max_span_bytes(&self) -> usize {
+        self.max_span_bytes
+    }
+
+    /// Sets the configured max word span length, in bytes.
+    ///
+    /// Longer word spans are split into chunks, at char boundaries;
+    /// `0` disables the cap. This bounds the encode cost of adversarial
+    /// input, but tokens never span a chunk boundary; so text with
+    /// oversized spans may encode differently than without the cap.
+    ///
+    /// See [`TextSpannerBuilder::set_max_span_bytes`].
+    pub fn set_max_span_bytes(
+        &mut self,
+        max_span_bytes: usize,
+    ) {
+        self.max_span_bytes = max_span_bytes;
+    }
+
+    /// Sets the configured max word span length, in bytes.
+    ///
+    /// See [`set_max_span_bytes`](Self::set_max_span_bytes).
+    pub fn with_max_span_bytes(
+        mut self,
+        max_span_bytes: usize,
+    ) -> Self {
+        self.set_max_span_bytes(max_span_bytes);
+        self
+    }
+
+    /// Gets the configured fallback span length, in bytes.
+    ///
+    /// Compound spans at least this long are encoded by a
+    /// [`BpeBacktrackSpanEncoder`]; `0` disables the fallback.
+    pub fn fallback_span_bytes(&self) -> usize {
+        self.fallback_span_bytes
+    }
+
+    /// Sets the configured fallback span length, in bytes.
+    ///
+    /// Compound spans at least this long are encoded by a
+    /// [`BpeBacktrackSpanEncoder`]; `0` disables the fallback.
+    /// Defaults to [`DEFAULT_FALLBACK_SPAN_BYTES`].
+    ///
+    /// The fallback only backtracks on vocabs whose tokens are all reachable
+    /// by their merges, so the tokens are unchanged; only the worst-case cost.
+    /// See [`BpeBacktrackSpanEncoder::lazy_reachable_builder`].
+    pub fn set_fallback_span_bytes(
+        &mut self,
+        fallback_span_bytes: usize,
+    ) {
+        self.fallback_span_bytes = fallback_span_bytes;
+    }
+
+    /// Sets the configured fallback span length, in bytes.
+    ///
+    /// See [`set_fallback_span_bytes`](Self::set_fallback_span_bytes).
+    pub fn with_fallback_span_bytes(
+        mut self,
+        fallback_span_bytes: usize,
+    ) -> Self {
+        self.set_fallback_span_bytes(fallback_span_bytes);
+        self
+    }
+
+    /// Gets the configured document piece length, in bytes.
+    ///
+    /// When [`parallel`](Self::parallel), texts at least twice this long
+    /// are split into pieces encoded in parallel; `0` disables splitting.
+    pub fn document_piece_bytes(&self) -> usize {
+        self.document_piece_bytes
+    }
+
+    /// Sets the configured document piece length, in bytes.
+    ///
+    /// When [`parallel`](Self::parallel), texts at least twice this long
+    /// are split into pieces encoded in parallel; `0` disables splitting.
+    /// Splits are only taken at line starts which a check of the spanner and
+    /// normalizer finds leave the tokens unchanged; a heuristic, exact for the
+    /// pretrained patterns and normalizers.
+    /// See [`DocumentSplitter`](crate::spanners::DocumentSplitter).
+    pub fn set_document_piece_bytes(
+        &mut self,
+        document_piece_bytes: usize,
+    ) {
+        self.document_piece_bytes = document_piece_bytes;
+    }
+
+    /// Sets the configured document piece length, in bytes.
+    ///
+    /// See [`set_document_piece_bytes`](Self::set_document_piece_bytes).
+    pub fn with_document_piece_bytes(
+        mut self,
+        document_piece_bytes: usize,
+    ) -> Self {
+        self.set_document_piece_bytes(document_piece_bytes);
+        self
+    }
+
     /// Build a [`TokenEncoder`] for the given vocab.
+    ///
+    /// A [`SpanEncoderSelector::Auto`] selection is resolved first;
+    /// see [`SpanEncoderSelector::resolve`].
+    ///
+    /// Unless [`fallback_span_bytes`](Self::fallback_span_bytes) is `0`, or
+    /// the selected encoder is already [`SpanEncoderSelector::BpeBacktrack`];
+    /// huge compound spans fall back to a [`BpeBacktrackSpanEncoder`],
+    /// when the vocab's tokens are all reachable by their merges.
     pub fn build<T: TokenType>(
         &self,
         vocab: Arc<UnifiedTokenVocab<T>>,
+    ) -> Arc<dyn TokenEncoder<T>> {
+        // `Auto` calibrates on as many threads as a parallel encode will use.
+        let threads = match self.parallel() {
+            #[cfg(feature = "concurrent")]
+            true => crate::support::concurrency::threads::resolve_max_pool(None),
+            _ => 1,
+        };
+        let selector = self
+            .effective_span_encoder()
+            .resolve_for_threads(&vocab, threads);
+        let mut se_builder = selector.span_encoder_builder(&vocab);
+        if self.fallback_span_bytes() != 0 && selector != SpanEncoderSelector::BpeBacktrack {
+            let fallback_builder =
+                BpeBacktrackSpanEncoder::lazy_reachable_builder(vocab.clone(), se_builder.clone());
+            se_builder = FallbackSpanEncoder::builder(
+                se_builder,
+                fallback_builder,
+                self.fallback_span_bytes(),
+            );
+        }
+        self.build_with_span_encoder(vocab, se_builder)
+    }
+
+    /// Build a wordpiece [`TokenEncoder`] for the given wordpiece vocab.
+    ///
+    /// The span encoder is always a [`WordPieceSpanEncoder`];
+    /// [`span_encoder`](Self::span_encoder) is ignored.
+    ///
+    /// ## Errors
+    /// If [`WordPieceVocab::to_unified_vocab`] fails.
+    pub fn build_wordpiece<T: TokenType>(
+        &self,
+        wordpiece_vocab: Arc<WordPieceVocab<T>>,
+    ) -> WCResult<Arc<dyn TokenEncoder<T>>> {
+        let vocab = Arc::new(wordpiece_vocab.to_unified_vocab()?);
+        Ok(self.build_with_span_encoder(vocab, WordPieceSpanEncoder::builder(wordpiece_vocab)))
+    }
+
+    /// Build a unigram [`TokenEncoder`] for the given unigram vocab.
+    ///
+    /// The span encoder is always a Viterbi [`UnigramSpanEncoder`];
+    /// [`span_encoder`](Self::span_encoder) is ignored.
+    ///
+    /// ## Errors
+    /// If [`UnigramVocab::to_unified_vocab`] fails.
+    pub fn build_unigram<T: TokenType>(
+        &self,
+        unigram_vocab: Arc<UnigramVocab<T>>,
+    ) -> WCResult<Arc<dyn TokenEncoder<T>>> {
+        let vocab = Arc::new(unigram_vocab.to_unified_vocab()?);
+        Ok(self.build_with_span_encoder(vocab, UnigramSpanEncoder::builder(unigram_vocab)))
+    }
+
+    /// Build a [`TokenSpanEncoder`] with the given span encoder builder.
+    fn build_with_span_encoder<T: TokenType>(
+        &self,
+        vocab: Arc<UnifiedTokenVocab<T>>,
+        se_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
     ) -> Arc<dyn TokenEncoder<T>> {
         let spanner = TextSpannerBuilder::new(vocab.spanning().clone())
             .with_accelerated_lexers(self.accelerated_lexers())
             .with_concurrent(self.is_concurrent())
+            .with_max_span_bytes(NonZeroUsize::new(self.max_span_bytes()))
             .build();
 
         #[allow(unused_mut)]
-        let mut enc: Arc<dyn TokenEncoder<T>> = Arc::new(TokenSpanEncoder::<T>::new_with_selector(
-            spanner,
-            vocab,
-            self.effective_span_encoder(),
-        ));
+        let mut enc: Arc<dyn TokenEncoder<T>> = Arc::new(
+            TokenSpanEncoder::<T>::new_with_builder(spanner, vocab, se_builder)
+                .with_span_cache(self.span_cache_size()),
+        );
 
-        #[cfg(feature = "parallel")]
         if self.parallel() {
-            enc = Arc::new(crate::support::concurrency::rayon::ParallelRayonEncoder::new(enc));
+            match self.parallel_backend() {
+                #[cfg(feature = "parallel")]
+                ParallelBackend::Rayon => {
+                    enc = Arc::new(
+                        crate::support::concurrency::rayon::ParallelRayonEncoder::new(enc)
+                            .with_document_piece_bytes(self.document_piece_bytes()),
+                    );
+                }
+                #[cfg(feature = "concurrent")]
+                ParallelBackend::Threads => {
+                    enc = Arc::new(
+                        crate::support::concurrency::scoped_threads::ParallelThreadEncoder::new(
+                            enc,
+                        )
+                        .with_document_piece_bytes(self.document_piece_bytes()),
+                    );
+                }
+                #[allow(unreachable_patterns)]
+                backend => log::warn!(
+                    "parallel backend {backend:?} is not compiled in; using a single-threaded encoder"
+                ),
+            }
         }
 
         enc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{format, string::String, vec::Vec},
+        decoders::{TokenDecoder, TokenDictDecoder},
+        encoders::testing::{common_encoder_test_vocab, unreachable_merge_test_vocab},
+    };
+
+    #[test]
+    fn test_max_span_bytes() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+        let decoder = TokenDictDecoder::from_vocab(vocab.clone());
+
+        let options = TokenEncoderOptions::default().with_max_span_bytes(4);
+        assert_eq!(options.max_span_bytes(), 4);
+        let capped = options.build(vocab.clone());
+        let plain = TokenEncoderOptions::default().build(vocab.clone());
+
+        // Oversized spans encode as independent chunks.
+        let text = "hellohello world";
+        let tokens = capped.try_encode(text).unwrap();
+        let chunks: Vec<T> = ["hell", "ohel", "lo", " wor", "ld"]
+            .iter()
+            .flat_map(|chunk| plain.try_encode(chunk).unwrap())
+            .collect();
+        assert_eq!(tokens, chunks);
+        assert_eq!(
+            decoder.try_decode_to_string(&tokens).unwrap().unwrap(),
+            text
+        );
+    }
+
+    #[test]
+    fn test_fallback_span_bytes() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+
+        let options = TokenEncoderOptions::default();
+        assert_eq!(options.fallback_span_bytes(), DEFAULT_FALLBACK_SPAN_BYTES);
+        let guarded = options.build(vocab.clone());
+        let small = options.with_fallback_span_bytes(64).build(vocab.clone());
+        let plain = options.with_fallback_span_bytes(0).build(vocab.clone());
+
+        let blob: String = "aGVsbG8gd29ybGQ".repeat(300);
+        let text = format!("short {blob} words");
+        assert_eq!(
+            guarded.try_encode(&text).unwrap(),
+            plain.try_encode(&text).unwrap()
+        );
+        assert_eq!(
+            small.try_encode(&text).unwrap(),
+            plain.try_encode(&text).unwrap()
+        );
+
+        // Backtracking would emit the unreachable `abcd`; the fallback must not.
+        let vocab: Arc<UnifiedTokenVocab<T>> = unreachable_merge_test_vocab().into();
+        let small = options.with_fallback_span_bytes(4).build(vocab.clone());
+        let plain = options.with_fallback_span_bytes(0).build(vocab.clone());
+        assert_eq!(
+            small.try_encode(" xabcd").unwrap(),
+            plain.try_encode(" xabcd").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parallel_backend() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+
+        let options = TokenEncoderOptions::default().with_parallel(true);
+        assert_eq!(options.parallel_backend(), ParallelBackend::default());
+        let plain = options.with_parallel(false).build(vocab.clone());
+
+        let batch = ["hello world", "it's the heat", "", "san francisco"];
+        let expected = plain.try_encode_batch(&batch).unwrap();
+        for backend in [ParallelBackend::Rayon, ParallelBackend::Threads] {
+            let options = options.with_parallel_backend(backend);
+            assert_eq!(options.parallel_backend(), backend);
+            let encoder = options.build(vocab.clone());
+            assert_eq!(encoder.try_encode_batch(&batch).unwrap(), expected);
+        }
+    }
+}
@@ -9,6 +9,8 @@ use crate::{
     spanners::TextSpanningConfig,
     support::{slices::inner_slice_view, traits::static_is_send_sync_check},
     vocab::{
+        SpanMapVocab,
+        SpanTokenMap,
         UnifiedTokenVocab,
         VocabIndex,
         utility::testing::{build_test_shift_byte_vocab, build_test_vocab},
@@ -27,6 +29,26 @@ pub fn common_encoder_test_vocab<T: TokenType>() -> UnifiedTokenVocab<T> {
     vocab
 }
 
+/// Build a test vocabulary with a token its merges can't reach.
+///
+/// The byte tokens, plus `ab`, `abcd`, `bc` and `bcd` in that order;
+/// merging `abcd` stops at `[ab, c, d]`, so `abcd` is never produced
+/// by the merge encoders.
+pub fn unreachable_merge_test_vocab<T: TokenType>() -> UnifiedTokenVocab<T> {
+    let mut span_map: SpanTokenMap<T> = Default::default();
+    span_map.extend(
+        ["ab", "abcd", "bc", "bcd"]
+            .iter()
+            .enumerate()
+            .map(|(idx, span)| (span.as_bytes().to_vec(), T::from_usize(256 + idx).unwrap())),
+    );
+    UnifiedTokenVocab::from_span_vocab(
+        TextSpanningConfig::from_pattern(OA_CL100K_BASE_PATTERN),
+        SpanMapVocab::from_span_map(span_map),
+    )
+    .unwrap()
+}
+
 /// Common [`TokenEncoder`] tests.
 pub fn common_encoder_tests<T: TokenType>(
     vocab: Arc<UnifiedTokenVocab<T>>,
@@ -49,6 +71,21 @@ pub fn common_encoder_tests<T: TokenType>(
 
     assert_eq!(decoded_strings, samples);
 
+    // Byte encoding matches text encoding on valid UTF-8,
+    // and round-trips invalid bytes.
+    let byte_samples: Vec<&[u8]> = samples.iter().map(|s| s.as_bytes()).collect();
+    assert_eq!(
+        encoder.try_encode_bytes_batch(&byte_samples).unwrap(),
+        token_batch
+    );
+
+    let bytes: &[u8] = b"hello \xff\xfe world \xe2\x82 it's\x80";
+    let tokens = encoder.try_encode_bytes(bytes).unwrap();
+    assert_eq!(
+        decoder.try_decode_to_bytes(&tokens).unwrap().unwrap(),
+        bytes
+    );
+
     // Build and test a list of all special tokens.
 
     let specials: Vec<(&[u8], T)> = vocab
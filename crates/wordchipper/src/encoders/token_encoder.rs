@@ -4,7 +4,7 @@ use crate::{
     TokenType,
     WCResult,
     alloc::{sync::Arc, vec::Vec},
-    spanners::TextSpanner,
+    spanners::{TextNormalizer, TextSpanner},
     vocab::SpecialVocab,
 };
 
@@ -26,6 +26,13 @@ pub trait TokenEncoder<T: TokenType>: Send + Sync {
     /// A reference to the internal `SpecialVocab`.
     fn special_vocab(&self) -> &SpecialVocab<T>;
 
+    /// Return the text normalizer applied before spanning, if known.
+    ///
+    /// Wrappers which cannot see their encoder's normalizer return `None`.
+    fn normalizer(&self) -> Option<&TextNormalizer> {
+        None
+    }
+
     /// Return the expected bytes per token ratio.
     ///
     /// This is used by [`TokenEncoder::expected_token_count`] to predict
@@ -76,6 +83,69 @@ pub trait TokenEncoder<T: TokenType>: Send + Sync {
         Ok(tokens)
     }
 
+    /// Encode bytes, which need not be valid UTF-8, into tokens.
+    ///
+    /// Valid UTF-8 runs are encoded as text; each invalid run is encoded
+    /// directly, as byte tokens for byte-level vocabularies. So for byte-level
+    /// BPE without normalization, decoding the tokens yields `bytes` exactly.
+    ///
+    /// The default implementation has no byte tokens to fall back on; it
+    /// encodes each invalid run as a separate `U+FFFD`, as `String::from_utf8_lossy`
+    /// would. Encoders which know their byte vocabulary should override it.
+    ///
+    /// ## Arguments
+    /// * `bytes` - The bytes to encode.
+    /// * `tokens` - The target token buffer to append to.
+    fn try_encode_bytes_append(
+        &self,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) -> WCResult<()> {
+        for chunk in bytes.utf8_chunks() {
+            self.try_encode_append(chunk.valid(), tokens)?;
+            if !chunk.invalid().is_empty() {
+                self.try_encode_append("\u{FFFD}", tokens)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode bytes, which need not be valid UTF-8, into tokens.
+    ///
+    /// See: [`TokenEncoder::try_encode_bytes_append`].
+    ///
+    /// ## Arguments
+    /// * `bytes` - The bytes to encode.
+    ///
+    /// ## Returns
+    /// A `Result` containing the vector of tokens or an error.
+    fn try_encode_bytes(
+        &self,
+        bytes: &[u8],
+    ) -> WCResult<Vec<T>> {
+        let capacity = (bytes.len() as f32 / self.expected_bytes_per_token()) as usize * 115 / 100;
+        let mut tokens = Vec::with_capacity(capacity);
+
+        self.try_encode_bytes_append(bytes, &mut tokens)?;
+        Ok(tokens)
+    }
+
+    /// Encode a batch of byte strings into tokens.
+    ///
+    /// See: [`TokenEncoder::try_encode_bytes_append`].
+    ///
+    /// ## Arguments
+    /// * `batch` - A slice of byte strings to encode.
+    ///
+    /// ## Returns
+    /// A `Result` containing the vector of token vectors or an error.
+    fn try_encode_bytes_batch(
+        &self,
+        batch: &[&[u8]],
+    ) -> WCResult<Vec<Vec<T>>> {
+        batch.iter().map(|b| self.try_encode_bytes(b)).collect()
+    }
+
     /// Encode a batch of text into tokens, returning an error if the encoding fails.
     ///
     /// ## Arguments
@@ -90,3 +160,60 @@ pub trait TokenEncoder<T: TokenType>: Send + Sync {
         batch.iter().map(|s| self.try_encode(s)).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenEncoderOptions,
+        UnifiedTokenVocab,
+        encoders::testing::common_encoder_test_vocab,
+    };
+
+    /// An encoder which only implements the required methods.
+    struct TextOnlyEncoder<T: TokenType>(Arc<dyn TokenEncoder<T>>);
+
+    impl<T: TokenType> TokenEncoder<T> for TextOnlyEncoder<T> {
+        fn spanner(&self) -> &Arc<dyn TextSpanner> {
+            self.0.spanner()
+        }
+
+        fn special_vocab(&self) -> &SpecialVocab<T> {
+            self.0.special_vocab()
+        }
+
+        fn try_encode_append(
+            &self,
+            text: &str,
+            tokens: &mut Vec<T>,
+        ) -> WCResult<()> {
+            self.0.try_encode_append(text, tokens)
+        }
+    }
+
+    #[test]
+    fn test_default_encode_bytes() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+        let inner = TokenEncoderOptions::default()
+            .with_parallel(false)
+            .build(vocab);
+        let encoder = TextOnlyEncoder(inner.clone());
+
+        assert_eq!(
+            encoder.try_encode_bytes(b"hello world").unwrap(),
+            inner.try_encode("hello world").unwrap()
+        );
+        // Each invalid run is encoded as a separate `U+FFFD`.
+        let expected: Vec<T> = ["hello ", "\u{FFFD}", "\u{FFFD}", " world", "\u{FFFD}"]
+            .iter()
+            .flat_map(|text| inner.try_encode(text).unwrap())
+            .collect();
+        assert_eq!(
+            encoder
+                .try_encode_bytes(b"hello \xff\xfe world\x80")
+                .unwrap(),
+            expected
+        );
+    }
+}
@@ -1,10 +1,13 @@
 //! # [`TokenSpanEncoder`] Utilities
 
+mod span_cache;
 pub mod span_encoders;
 
 #[allow(clippy::module_inception)]
 mod token_span_encoder;
 
+#[doc(inline)]
+pub use span_cache::*;
 #[doc(inline)]
 pub use span_encoders::{SpanEncoder, SpanEncoderSelector};
 #[doc(inline)]
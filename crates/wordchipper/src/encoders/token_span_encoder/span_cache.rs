@@ -0,0 +1,296 @@
+//! # Span Token Cache
+//!
+//! A bounded memo of `{ span bytes -> tokens }` for compound spans.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    alloc::{boxed::Box, sync::Arc, vec::Vec},
+    encoders::token_span_encoder::SpanEncoder,
+    types::WCHashMap,
+};
+
+/// Spans longer than this bypass the cache.
+///
+/// Keeps the cache bounded in bytes as well as entries; so that adversarial
+/// input (megabyte runs without whitespace) is never copied into it.
+pub const MAX_CACHED_SPAN_BYTES: usize = 64;
+
+/// Hit/miss counters for a [`CachedSpanEncoder`].
+///
+/// Each encoder has its own counters, so pooled encoders don't contend;
+/// see [`SpanCacheStats::sum`] to aggregate them.
+#[derive(Debug, Default)]
+pub struct SpanCacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl SpanCacheCounters {
+    /// Take a snapshot of the counters.
+    pub fn stats(&self) -> SpanCacheStats {
+        SpanCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset the counters to zero.
+    pub fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A snapshot of span cache hit/miss counts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SpanCacheStats {
+    /// Compound spans served from the cache.
+    pub hits: u64,
+
+    /// Compound spans which ran the merge loop.
+    pub misses: u64,
+}
+
+impl SpanCacheStats {
+    /// Sum the stats of several caches.
+    pub fn sum<I>(stats: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        stats.into_iter().fold(Self::default(), |acc, s| Self {
+            hits: acc.hits + s.hits,
+            misses: acc.misses + s.misses,
+        })
+    }
+
+    /// The total number of cache lookups.
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// The fraction of lookups which hit; `0.0` when there were none.
+    pub fn hit_rate(&self) -> f64 {
+        match self.lookups() {
+            0 => 0.0,
+            n => self.hits as f64 / n as f64,
+        }
+    }
+}
+
+/// A cached span; and whether it was hit since the clock hand last passed.
+struct CacheEntry<T> {
+    tokens: Vec<T>,
+    referenced: bool,
+}
+
+/// A [`SpanEncoder`] which memoizes the compound spans of an inner encoder.
+///
+/// The cache holds at most `capacity` spans; when full, it evicts with
+/// the clock (second-chance) policy: the hand sweeps the entries in
+/// insertion order, clearing the mark of each entry hit since its last
+/// pass, and evicts the first unmarked one. So the working set of
+/// frequent words survives a stream of one-off spans.
+///
+/// Spans longer than [`MAX_CACHED_SPAN_BYTES`] go straight to the inner
+/// encoder, and are not counted as hits or misses.
+pub struct CachedSpanEncoder<T: TokenType> {
+    inner: Box<dyn SpanEncoder<T>>,
+    cache: WCHashMap<Arc<[u8]>, CacheEntry<T>>,
+    /// The cached keys, in clock order.
+    clock: Vec<Arc<[u8]>>,
+    /// The next clock position to consider for eviction.
+    hand: usize,
+    capacity: usize,
+    counters: Arc<SpanCacheCounters>,
+}
+
+impl<T: TokenType> CachedSpanEncoder<T> {
+    /// Wrap an encoder with a cache.
+    ///
+    /// ## Arguments
+    /// * `inner` - the encoder to memoize.
+    /// * `capacity` - the max number of cached spans.
+    /// * `counters` - the hit/miss counters.
+    pub fn new(
+        inner: Box<dyn SpanEncoder<T>>,
+        capacity: usize,
+        counters: Arc<SpanCacheCounters>,
+    ) -> Self {
+        Self {
+            inner,
+            cache: WCHashMap::default(),
+            clock: Vec::new(),
+            hand: 0,
+            capacity,
+            counters,
+        }
+    }
+
+    /// Get the max number of cached spans.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of cached spans.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Is the cache empty?
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Get the hit/miss counters.
+    pub fn counters(&self) -> &Arc<SpanCacheCounters> {
+        &self.counters
+    }
+
+    /// Insert a span, evicting one if the cache is full.
+    fn insert(
+        &mut self,
+        span: &[u8],
+        tokens: Vec<T>,
+    ) {
+        let key: Arc<[u8]> = span.into();
+        let entry = CacheEntry {
+            tokens,
+            referenced: false,
+        };
+
+        if self.clock.len() < self.capacity {
+            self.clock.push(key.clone());
+            self.cache.insert(key, entry);
+            return;
+        }
+
+        loop {
+            let slot = self.hand;
+            self.hand = (self.hand + 1) % self.clock.len();
+
+            // Every clock key is a cache key; they are inserted and removed together.
+            let victim = self
+                .cache
+                .get_mut(&self.clock[slot])
+                .expect("clock key missing from span cache");
+            if victim.referenced {
+                victim.referenced = false;
+                continue;
+            }
+            self.cache.remove(&self.clock[slot]);
+            self.clock[slot] = key.clone();
+            self.cache.insert(key, entry);
+            return;
+        }
+    }
+}
+
+impl<T: TokenType> SpanEncoder<T> for CachedSpanEncoder<T> {
+    fn lookup_word_token(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+    ) -> Option<T> {
+        self.inner.lookup_word_token(vocab, span)
+    }
+
+    fn encode_append_invalid_bytes(
+        &mut self,
+        vocab: &UnifiedTokenVocab<T>,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        self.inner.encode_append_invalid_bytes(vocab, bytes, tokens)
+    }
+
+    fn encode_append_compound_span(
+        &mut self,
+        vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        if self.capacity == 0 || span.len() > MAX_CACHED_SPAN_BYTES {
+            self.inner.encode_append_compound_span(vocab, span, tokens);
+            return;
+        }
+
+        if let Some(cached) = self.cache.get_mut(span) {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            cached.referenced = true;
+            tokens.extend_from_slice(&cached.tokens);
+            return;
+        }
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+
+        let start = tokens.len();
+        self.inner.encode_append_compound_span(vocab, span, tokens);
+        self.insert(span, tokens[start..].to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::vec,
+        encoders::{testing::common_encoder_test_vocab, token_span_encoder::SpanEncoderSelector},
+    };
+
+    #[test]
+    fn test_cached_span_encoder() {
+        type T = u32;
+        let vocab: UnifiedTokenVocab<T> = common_encoder_test_vocab();
+
+        let counters = Arc::new(SpanCacheCounters::default());
+        let inner = SpanEncoderSelector::SingleThreadDefault.span_encoder_builder(&vocab)();
+        let mut reference = SpanEncoderSelector::SingleThreadDefault.span_encoder_builder(&vocab)();
+        let mut se = CachedSpanEncoder::new(inner, 2, counters.clone());
+        assert_eq!(se.capacity(), 2);
+        assert!(se.is_empty());
+
+        let spans: [&[u8]; 7] = [
+            b"hello", b"world", b"hello", b"other", b"hello", b"again", b"other",
+        ];
+        for span in spans {
+            let mut expected = vec![];
+            reference.encode_append_compound_span(&vocab, span, &mut expected);
+
+            let mut tokens = vec![];
+            se.encode_append_compound_span(&vocab, span, &mut tokens);
+            assert_eq!(tokens, expected);
+            assert!(se.len() <= se.capacity());
+        }
+
+        // hello (miss), world (miss), hello (hit), other (miss; evicts world,
+        // as hello was hit), hello (hit), again (miss; evicts other, as hello
+        // was hit again), other (miss).
+        let stats = se.counters().stats();
+        assert_eq!(stats, SpanCacheStats { hits: 2, misses: 5 });
+        assert_eq!(stats.lookups(), 7);
+        assert_eq!(stats.hit_rate(), 2.0 / 7.0);
+        assert_eq!(
+            SpanCacheStats::sum([stats, SpanCacheStats { hits: 1, misses: 0 }]),
+            SpanCacheStats { hits: 3, misses: 5 }
+        );
+
+        // Long spans bypass the cache.
+        let long = [b'a'; MAX_CACHED_SPAN_BYTES + 1];
+        for _ in 0..2 {
+            let mut expected = vec![];
+            reference.encode_append_compound_span(&vocab, &long, &mut expected);
+
+            let mut tokens = vec![];
+            se.encode_append_compound_span(&vocab, &long, &mut tokens);
+            assert_eq!(tokens, expected);
+        }
+        assert!(!se.cache.contains_key(long.as_slice()));
+        assert_eq!(se.counters().stats(), stats);
+
+        counters.reset();
+        assert_eq!(counters.stats(), SpanCacheStats::default());
+        assert_eq!(counters.stats().hit_rate(), 0.0);
+    }
+}
@@ -6,13 +6,14 @@
 //! Uses an Aho-Corasick automaton for leftmost-longest token matching,
 //! combined with a backtracking loop that validates BPE merge boundaries.
 
-use alloc::{sync::Arc, vec, vec::Vec};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
 
 use aho_corasick::{AhoCorasick, MatchKind};
+use once_cell::sync::OnceCell;
 
 use crate::{
     TokenType,
-    encoders::token_span_encoder::SpanEncoder,
+    encoders::token_span_encoder::{SpanEncoder, span_encoders::BufferSweepSpanEncoder},
     types::{Pair, WCHashMap},
     vocab::{UnifiedTokenVocab, VocabIndex},
 };
@@ -204,6 +205,33 @@ fn is_valid_token_pair<T: TokenType>(
     }
 }
 
+/// Check whether every span token is reachable by its merges.
+///
+/// [`BpeBacktrackSpanEncoder`] matches the merge encoders only on such vocabs;
+/// a token whose own bytes merge to something else (e.g. `abcd` when only
+/// `ab`, `bc` and `bcd` precede it) may be emitted by backtracking but never
+/// by merging.
+///
+/// This encodes each span token once; O(vocab) work.
+///
+/// ## Arguments
+/// * `vocab` - the vocab to check.
+///
+/// ## Returns
+/// `true` when merging each span token's bytes yields exactly that token.
+pub fn merges_reach_all_tokens<T: TokenType>(vocab: &UnifiedTokenVocab<T>) -> bool {
+    let mut encoder = BufferSweepSpanEncoder::<T>::default();
+    let mut tokens = Vec::new();
+    vocab.span_vocab().iter().all(|(span, &token)| {
+        if span.len() <= 1 {
+            return true;
+        }
+        tokens.clear();
+        encoder.encode_append_compound_span(vocab, span, &mut tokens);
+        tokens.as_slice() == [token]
+    })
+}
+
 /// Bitfield with all bits initially set to 1.
 struct BitField {
     words: Vec<u64>,
@@ -258,6 +286,45 @@ impl<T: TokenType> BpeBacktrackSpanEncoder<T> {
             bitfield: BitField::new(0),
         }
     }
+
+    /// Get a builder which defers building the [`BpeVocab`].
+    ///
+    /// The [`BpeVocab`] is built on the first call, and shared by every
+    /// encoder the builder returns; so a rarely used builder is cheap.
+    pub fn lazy_builder(
+        vocab: Arc<UnifiedTokenVocab<T>>
+    ) -> Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync> {
+        let bpe_vocab: OnceCell<Arc<BpeVocab<T>>> = OnceCell::new();
+        Arc::new(move || {
+            let bpe_vocab = bpe_vocab.get_or_init(|| Arc::new(BpeVocab::from_vocab(&vocab)));
+            Box::new(Self::new(bpe_vocab.clone()))
+        })
+    }
+
+    /// Get a lazy builder which only backtracks on merge-reachable vocabs.
+    ///
+    /// The first call checks [`merges_reach_all_tokens`]; then builds the
+    /// [`BpeVocab`] when it holds, so tokens match the merge encoders.
+    /// On other vocabs, every call returns an encoder from `otherwise`.
+    ///
+    /// ## Arguments
+    /// * `vocab` - the vocab to encode with.
+    /// * `otherwise` - builds the encoders used when the check fails.
+    pub fn lazy_reachable_builder(
+        vocab: Arc<UnifiedTokenVocab<T>>,
+        otherwise: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+    ) -> Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync> {
+        let bpe_vocab: OnceCell<Option<Arc<BpeVocab<T>>>> = OnceCell::new();
+        Arc::new(move || {
+            let bpe_vocab = bpe_vocab.get_or_init(|| {
+                merges_reach_all_tokens(&vocab).then(|| Arc::new(BpeVocab::from_vocab(&vocab)))
+            });
+            match bpe_vocab {
+                Some(bpe_vocab) => Box::new(Self::new(bpe_vocab.clone())),
+                None => otherwise(),
+            }
+        })
+    }
 }
 
 impl<T: TokenType> core::fmt::Debug for BpeBacktrackSpanEncoder<T> {
@@ -336,8 +403,16 @@ mod tests {
         TokenEncoder,
         TokenType,
         encoders::{
-            testing::{common_encoder_test_vocab, common_encoder_tests},
-            token_span_encoder::{SpanEncoderSelector, TokenSpanEncoder},
+            testing::{
+                common_encoder_test_vocab,
+                common_encoder_tests,
+                unreachable_merge_test_vocab,
+            },
+            token_span_encoder::{
+                SpanEncoderSelector,
+                TokenSpanEncoder,
+                span_encoders::merges_reach_all_tokens,
+            },
         },
         spanners::TextSpannerBuilder,
         vocab::UnifiedTokenVocab,
@@ -363,4 +438,32 @@ mod tests {
     fn test_encoder_u32() {
         test_encoder::<u32>();
     }
+
+    #[test]
+    fn test_merges_reach_all_tokens() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+        assert!(merges_reach_all_tokens(&vocab));
+
+        let vocab: Arc<UnifiedTokenVocab<T>> = unreachable_merge_test_vocab().into();
+        assert!(!merges_reach_all_tokens(&vocab));
+
+        let encode = |selector| {
+            TokenSpanEncoder::<T>::new_with_selector(
+                TextSpannerBuilder::default(&vocab),
+                vocab.clone(),
+                selector,
+            )
+            .try_encode(" xabcd")
+            .unwrap()
+        };
+        assert_eq!(
+            encode(SpanEncoderSelector::BufferSweep),
+            vec![32, 120, 256, 99, 100]
+        );
+        assert_eq!(
+            encode(SpanEncoderSelector::BpeBacktrack),
+            vec![32, 120, 257]
+        );
+    }
 }
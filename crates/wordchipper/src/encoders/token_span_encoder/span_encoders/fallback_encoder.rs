@@ -0,0 +1,169 @@
+//! # Huge Span Fallback [`SpanEncoder`].
+//!
+//! Routes oversized compound spans to a second encoder,
+//! to bound the worst-case cost of pathological input.
+
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    alloc::{boxed::Box, sync::Arc, vec::Vec},
+    encoders::token_span_encoder::SpanEncoder,
+};
+
+/// The default [`FallbackSpanEncoder`] threshold, in bytes.
+///
+/// Natural text words are far shorter; spans this long are
+/// blobs (base64, minified code, runs of one char).
+pub const DEFAULT_FALLBACK_SPAN_BYTES: usize = 4 * 1024;
+
+/// A [`SpanEncoder`] which hands huge compound spans to a fallback encoder.
+///
+/// Merge encoders slow down on long spans; the
+/// [`BpeBacktrackSpanEncoder`](super::BpeBacktrackSpanEncoder) stays
+/// near-linear. It yields the same tokens on BPE vocabs whose tokens are
+/// all reachable by their merges; see
+/// [`lazy_reachable_builder`](super::BpeBacktrackSpanEncoder::lazy_reachable_builder)
+/// to fall back only on such vocabs. Compound spans of at least
+/// `fallback_span_bytes` go to the fallback; all others to the inner encoder.
+///
+/// The fallback encoder is built on the first huge span.
+pub struct FallbackSpanEncoder<T: TokenType> {
+    inner: Box<dyn SpanEncoder<T>>,
+    fallback_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+    fallback: Option<Box<dyn SpanEncoder<T>>>,
+    fallback_span_bytes: usize,
+}
+
+impl<T: TokenType> FallbackSpanEncoder<T> {
+    /// Wrap an encoder with a fallback.
+    ///
+    /// ## Arguments
+    /// * `inner` - the encoder for ordinary spans.
+    /// * `fallback_builder` - builds the encoder for huge spans.
+    /// * `fallback_span_bytes` - the min span length sent to the fallback.
+    pub fn new(
+        inner: Box<dyn SpanEncoder<T>>,
+        fallback_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+        fallback_span_bytes: usize,
+    ) -> Self {
+        Self {
+            inner,
+            fallback_builder,
+            fallback: None,
+            fallback_span_bytes,
+        }
+    }
+
+    /// Get a builder wrapping each built encoder with a fallback.
+    ///
+    /// ## Arguments
+    /// * `se_builder` - builds the encoders for ordinary spans.
+    /// * `fallback_builder` - builds the encoders for huge spans.
+    /// * `fallback_span_bytes` - the min span length sent to the fallback.
+    pub fn builder(
+        se_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+        fallback_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+        fallback_span_bytes: usize,
+    ) -> Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync> {
+        Arc::new(move || {
+            Box::new(Self::new(
+                se_builder(),
+                fallback_builder.clone(),
+                fallback_span_bytes,
+            ))
+        })
+    }
+
+    /// Get the min span length sent to the fallback.
+    pub fn fallback_span_bytes(&self) -> usize {
+        self.fallback_span_bytes
+    }
+
+    /// Has the fallback encoder been built?
+    pub fn has_fallback(&self) -> bool {
+        self.fallback.is_some()
+    }
+}
+
+impl<T: TokenType> SpanEncoder<T> for FallbackSpanEncoder<T> {
+    fn lookup_word_token(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+    ) -> Option<T> {
+        self.inner.lookup_word_token(vocab, span)
+    }
+
+    fn encode_append_invalid_bytes(
+        &mut self,
+        vocab: &UnifiedTokenVocab<T>,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        self.inner.encode_append_invalid_bytes(vocab, bytes, tokens)
+    }
+
+    fn encode_append_compound_span(
+        &mut self,
+        vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        if span.len() < self.fallback_span_bytes {
+            return self.inner.encode_append_compound_span(vocab, span, tokens);
+        }
+        self.fallback
+            .get_or_insert_with(|| (self.fallback_builder)())
+            .encode_append_compound_span(vocab, span, tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenEncoder,
+        encoders::{
+            testing::{common_encoder_test_vocab, common_encoder_tests},
+            token_span_encoder::{
+                SpanEncoderSelector,
+                TokenSpanEncoder,
+                span_encoders::BpeBacktrackSpanEncoder,
+            },
+        },
+        spanners::TextSpannerBuilder,
+    };
+
+    #[test]
+    fn test_fallback_encoder() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+        let buffer_sweep = SpanEncoderSelector::BufferSweep.span_encoder_builder(&vocab);
+        let backtrack = BpeBacktrackSpanEncoder::lazy_builder(vocab.clone());
+
+        // A tiny threshold routes nearly every compound span to the fallback.
+        let encoder = TokenSpanEncoder::<T>::new_with_builder(
+            TextSpannerBuilder::default(&vocab),
+            vocab.clone(),
+            FallbackSpanEncoder::builder(buffer_sweep.clone(), backtrack.clone(), 2),
+        );
+        let encoder: Arc<dyn TokenEncoder<T>> = Arc::new(encoder);
+        common_encoder_tests(vocab.clone(), encoder);
+
+        let mut se = FallbackSpanEncoder::new(buffer_sweep(), backtrack, 8);
+        assert_eq!(se.fallback_span_bytes(), 8);
+
+        let mut tokens = Vec::new();
+        se.encode_append_compound_span(&vocab, b"hello", &mut tokens);
+        assert!(!se.has_fallback());
+
+        let span = b"hellohellohello";
+        let mut expected = Vec::new();
+        buffer_sweep().encode_append_compound_span(&vocab, span, &mut expected);
+
+        let mut tokens = Vec::new();
+        se.encode_append_compound_span(&vocab, span, &mut tokens);
+        assert!(se.has_fallback());
+        assert_eq!(tokens, expected);
+    }
+}
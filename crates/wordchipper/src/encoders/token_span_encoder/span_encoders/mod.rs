@@ -2,23 +2,37 @@
 
 mod bpe_backtrack_encoder;
 mod buffer_sweep_encoder;
+mod fallback_encoder;
 mod merge_heap_encoder;
 mod priority_merge_encoder;
 mod span_encoder;
+#[cfg(feature = "std")]
+mod span_encoder_calibration;
 mod span_encoder_selector;
 mod tail_sweep_encoder;
+mod unigram_encoder;
+mod wordpiece_encoder;
 
 #[doc(inline)]
 pub use bpe_backtrack_encoder::*;
 #[doc(inline)]
 pub use buffer_sweep_encoder::*;
 #[doc(inline)]
+pub use fallback_encoder::*;
+#[doc(inline)]
 pub use merge_heap_encoder::*;
 #[doc(inline)]
 pub use priority_merge_encoder::*;
 #[doc(inline)]
 pub use span_encoder::*;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use span_encoder_calibration::*;
 #[doc(inline)]
 pub use span_encoder_selector::*;
 #[doc(inline)]
 pub use tail_sweep_encoder::*;
+#[doc(inline)]
+pub use unigram_encoder::*;
+#[doc(inline)]
+pub use wordpiece_encoder::*;
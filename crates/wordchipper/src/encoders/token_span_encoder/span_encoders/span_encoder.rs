@@ -15,6 +15,42 @@ pub trait SpanEncoder<T: TokenType>: Send {
         tokens: &mut Vec<T>,
     );
 
+    /// Look up a whole word span as a single token.
+    ///
+    /// Words found here skip [`encode_append_compound_span`](Self::encode_append_compound_span).
+    /// The default looks the span up in the `vocab` span table;
+    /// see [`SpanMapVocab::lookup_token`](crate::vocab::SpanMapVocab::lookup_token).
+    ///
+    /// ## Arguments
+    /// * `vocab` - The reference vocabulary.
+    /// * `span` - The byte span.
+    fn lookup_word_token(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+    ) -> Option<T> {
+        vocab.lookup_token(span)
+    }
+
+    /// Encodes a run of bytes which are not valid UTF-8.
+    ///
+    /// The default encodes each byte as its byte token; so byte-level
+    /// vocabularies decode the run back exactly.
+    ///
+    /// ## Arguments
+    /// * `vocab` - The reference vocabulary.
+    /// * `bytes` - The invalid bytes.
+    /// * `tokens` - The target token buffer to append to.
+    fn encode_append_invalid_bytes(
+        &mut self,
+        vocab: &UnifiedTokenVocab<T>,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        let byte_vocab = vocab.byte_vocab();
+        tokens.extend(bytes.iter().map(|&b| byte_vocab.get_token(b)));
+    }
+
     /// Encodes a single [`SpanRef`]".
     ///
     /// ## Arguments
@@ -32,7 +68,7 @@ pub trait SpanEncoder<T: TokenType>: Send {
         match span_ref {
             SpanRef::Word(range) => {
                 let span = &text[range].as_bytes();
-                if let Some(token) = vocab.lookup_token(span) {
+                if let Some(token) = self.lookup_word_token(vocab, span) {
                     // 1. Faster;
                     // 2. Correct-or: Some words may not exist in the pair mappings.
                     tokens.push(token);
@@ -0,0 +1,784 @@
+//! # Span Encoder Calibration
+//!
+//! Times each [`SpanEncoder`] against a vocabulary, to pick the fastest;
+//! see [`SpanEncoderSelector::Auto`].
+//!
+//! Calibrations are kept per process in [`SpanEncoderCalibrations::global`];
+//! set [`WORDCHIPPER_CALIBRATION_CACHE`] to keep them in a file across processes.
+
+use core::{fmt, str::FromStr, time::Duration};
+use std::{ffi::OsString, fs, hint::black_box, path::Path, sync::Barrier, thread, time::Instant};
+
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
+use spin::RwLock;
+
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    WCError,
+    WCResult,
+    encoders::token_span_encoder::{
+        SpanEncoder,
+        SpanEncoderSelector,
+        span_encoders::merges_reach_all_tokens,
+    },
+    prelude::*,
+    spanners::{SpanRef, TextSpannerBuilder},
+    support::hashing::hex_digest,
+    types::WCHashMap,
+};
+
+/// Environment variable naming a file which keeps calibrations across processes.
+///
+/// When set, [`SpanEncoderCalibrations::global`] loads the file on first use,
+/// and [`SpanEncoderCalibration::cached`] rewrites it after each new calibration.
+pub const WORDCHIPPER_CALIBRATION_CACHE: &str = "WORDCHIPPER_CALIBRATION_CACHE";
+
+/// The encoders timed by a calibration; in order of preference on ties.
+///
+/// [`SpanEncoderSelector::BpeBacktrack`] is only timed on vocabs whose
+/// tokens are all reachable by their merges (see [`merges_reach_all_tokens`]);
+/// on others it encodes differently, so it is never selected.
+pub const CALIBRATION_CANDIDATES: &[SpanEncoderSelector] = &[
+    SpanEncoderSelector::MergeHeap,
+    SpanEncoderSelector::PriorityMerge,
+    SpanEncoderSelector::TailSweep,
+    SpanEncoderSelector::BpeBacktrack,
+    SpanEncoderSelector::BufferSweep,
+];
+
+/// The timed rounds per encoder; the fastest round counts.
+pub const DEFAULT_CALIBRATION_ROUNDS: usize = 7;
+
+/// The copies of [`CALIBRATION_SAMPLE_TEXT`] encoded per round by
+/// [`SpanEncoderCalibration::cached`]; about 25 KiB.
+pub const CALIBRATION_SAMPLE_REPEATS: usize = 32;
+
+/// The default calibration text.
+///
+/// Prose, code, numbers and non-latin text; repeated so that
+/// each round runs long enough to time.
+pub const CALIBRATION_SAMPLE_TEXT: &str = "\
+The quick brown fox jumps over the lazy dog; it wasn't the first time, and it won't be the last.
+In 1969, 600 million people watched the landing. Prices rose 3.75% to $1,024.50 (year-over-year).
+fn main() { let xs: Vec<u32> = (0..10).map(|x| x * x).collect(); println!(\"{xs:?}\"); }
+<div class=\"header\">Hello, World!</div> https://example.com/path?query=value&other=123
+Die Straßenbahn fährt um 7:45 Uhr ab. Les élèves étudient à la bibliothèque universitaire.
+東京は日本の首都です。 Москва — столица России. 서울은 대한민국의 수도입니다. مرحبا بالعالم
+    indented_line = {\"key\": [1, 2, 3], \"nested\": {\"a\": null, \"b\": true}}
+Supercalifragilisticexpialidocious antidisestablishmentarianism pneumonoultramicroscopic!!!
+";
+
+/// The measured encode time of one [`SpanEncoder`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpanEncoderTiming {
+    /// The timed encoder.
+    pub selector: SpanEncoderSelector,
+
+    /// The fastest round.
+    pub duration: Duration,
+
+    /// The bytes encoded per round, across all threads.
+    pub sample_bytes: usize,
+}
+
+impl SpanEncoderTiming {
+    /// The measured throughput, in bytes per second.
+    pub fn bytes_per_second(&self) -> f64 {
+        self.sample_bytes as f64 / self.duration.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+impl fmt::Display for SpanEncoderTiming {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.1} MB/s",
+            self.selector,
+            self.bytes_per_second() / 1e6
+        )
+    }
+}
+
+/// The result of timing each [`SpanEncoder`] against a vocabulary.
+///
+/// [`SpanEncoderSelector::Auto`] resolves through [`cached`](Self::cached);
+/// the timings can be logged with the [`Display`](fmt::Display) impl.
+///
+/// Timings are on the encoders only; the spanning cost is the same
+/// for all encoders, and is excluded. A calibration on more than one
+/// thread runs one encoder per thread, concurrently, and times the
+/// whole round; as a parallel batch encode would.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanEncoderCalibration {
+    selected: SpanEncoderSelector,
+    threads: usize,
+    timings: Vec<SpanEncoderTiming>,
+}
+
+/// A fingerprint of the vocabulary content which encoders depend upon.
+///
+/// A SHA-256 digest, in lowercase hex, over the spans sorted by token;
+/// so equal vocabularies, loaded separately, share a fingerprint.
+fn vocab_fingerprint<T: TokenType>(vocab: &UnifiedTokenVocab<T>) -> String {
+    let mut hasher = Sha256::new();
+    let mut update = |bytes: &[u8]| {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    };
+    update(core::any::type_name::<T>().as_bytes());
+    update(vocab.spanning().pattern().as_str().as_bytes());
+    update(&(vocab.pair_vocab().pair_map().len() as u64).to_le_bytes());
+
+    let mut spans: Vec<(T, &[u8])> = vocab
+        .span_vocab()
+        .iter()
+        .map(|(span, &token)| (token, span))
+        .collect();
+    spans.sort_unstable();
+    for (token, span) in spans {
+        update(&token.to_u64().unwrap_or_default().to_le_bytes());
+        update(span);
+    }
+
+    hex_digest(&hasher.finalize())
+}
+
+/// Time one round of encoding `span_refs`; one encoder per thread.
+fn time_round<T: TokenType>(
+    vocab: &UnifiedTokenVocab<T>,
+    sample: &str,
+    span_refs: &[SpanRef],
+    encoders: &mut [Box<dyn SpanEncoder<T>>],
+) -> Duration {
+    let encode = |se: &mut Box<dyn SpanEncoder<T>>| {
+        let mut tokens = Vec::with_capacity(sample.len());
+        for span_ref in span_refs {
+            se.encode_append_span_ref(vocab, sample, span_ref.clone(), &mut tokens);
+        }
+        black_box(tokens);
+    };
+
+    if let [se] = encoders {
+        let start = Instant::now();
+        encode(se);
+        return start.elapsed();
+    }
+
+    // Threads are started before the clock, and wait on the barrier.
+    let barrier = Barrier::new(encoders.len() + 1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = encoders
+            .iter_mut()
+            .map(|se| {
+                let (barrier, encode) = (&barrier, &encode);
+                scope.spawn(move || {
+                    barrier.wait();
+                    encode(se)
+                })
+            })
+            .collect();
+        barrier.wait();
+        let start = Instant::now();
+        for handle in handles {
+            handle.join().expect("calibration thread panicked");
+        }
+        start.elapsed()
+    })
+}
+
+impl SpanEncoderCalibration {
+    /// Build a single-threaded calibration from timings.
+    ///
+    /// Selects the fastest timing; the first, on ties.
+    /// Use this to restore a saved calibration; see [`store`](Self::store),
+    /// and [`with_threads`](Self::with_threads).
+    ///
+    /// ## Arguments
+    /// * `timings` - the measured timings.
+    ///
+    /// ## Returns
+    /// `None` if `timings` is empty.
+    pub fn from_timings(timings: Vec<SpanEncoderTiming>) -> Option<Self> {
+        let selected = timings
+            .iter()
+            .reduce(|best, t| match t.duration < best.duration {
+                true => t,
+                false => best,
+            })?
+            .selector;
+        Some(Self {
+            selected,
+            threads: 1,
+            timings,
+        })
+    }
+
+    /// Set the thread count the timings were measured on.
+    pub fn with_threads(
+        mut self,
+        threads: usize,
+    ) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Time each of the [`CALIBRATION_CANDIDATES`] on `vocab`.
+    ///
+    /// [`SpanEncoderSelector::BpeBacktrack`] is skipped unless the vocab
+    /// passes [`merges_reach_all_tokens`].
+    ///
+    /// Each encoder gets one untimed warm-up round; the timed rounds are
+    /// interleaved across the encoders, so that changes in machine load
+    /// are shared between them.
+    ///
+    /// ## Arguments
+    /// * `vocab` - the vocabulary.
+    /// * `sample` - the text to encode, per thread.
+    /// * `rounds` - the timed rounds per encoder; the fastest counts.
+    /// * `threads` - the threads to encode on, concurrently.
+    pub fn run<T: TokenType>(
+        vocab: &UnifiedTokenVocab<T>,
+        sample: &str,
+        rounds: usize,
+        threads: usize,
+    ) -> Self {
+        let threads = threads.max(1);
+        let spanner = TextSpannerBuilder::default(vocab);
+        let mut span_refs: Vec<SpanRef> = Vec::new();
+        spanner.for_each_split_span(sample, &mut |span_ref| {
+            span_refs.push(span_ref);
+            true
+        });
+
+        let reachable = merges_reach_all_tokens(vocab);
+        let candidates: Vec<SpanEncoderSelector> = CALIBRATION_CANDIDATES
+            .iter()
+            .copied()
+            .filter(|&selector| reachable || selector != SpanEncoderSelector::BpeBacktrack)
+            .collect();
+
+        let mut encoders: Vec<Vec<Box<dyn SpanEncoder<T>>>> = candidates
+            .iter()
+            .map(|selector| {
+                let builder = selector.span_encoder_builder(vocab);
+                (0..threads).map(|_| builder()).collect()
+            })
+            .collect();
+
+        let mut durations = vec![Duration::MAX; candidates.len()];
+        for round in 0..=rounds.max(1) {
+            for (ses, best) in encoders.iter_mut().zip(&mut durations) {
+                let duration = time_round(vocab, sample, &span_refs, ses);
+                if round > 0 {
+                    *best = duration.min(*best);
+                }
+            }
+        }
+
+        let timings = candidates
+            .into_iter()
+            .zip(durations)
+            .map(|(selector, duration)| SpanEncoderTiming {
+                selector,
+                duration,
+                sample_bytes: sample.len() * threads,
+            })
+            .collect();
+
+        // The merge encoders are always candidates; so there are timings.
+        Self::from_timings(timings)
+            .expect("no calibration candidates")
+            .with_threads(threads)
+    }
+
+    /// Get the calibration for `vocab` on `threads`; running it, if not cached.
+    ///
+    /// Calibrations are kept in [`SpanEncoderCalibrations::global`];
+    /// a new calibration is run on [`CALIBRATION_SAMPLE_REPEATS`] copies of
+    /// [`CALIBRATION_SAMPLE_TEXT`], and saved to [`WORDCHIPPER_CALIBRATION_CACHE`],
+    /// when set.
+    pub fn cached<T: TokenType>(
+        vocab: &UnifiedTokenVocab<T>,
+        threads: usize,
+    ) -> Self {
+        let calibrations = SpanEncoderCalibrations::global();
+        let threads = threads.max(1);
+        if let Some(calibration) = calibrations.get(vocab, threads) {
+            return calibration;
+        }
+
+        let calibration = Self::run(
+            vocab,
+            &CALIBRATION_SAMPLE_TEXT.repeat(CALIBRATION_SAMPLE_REPEATS),
+            DEFAULT_CALIBRATION_ROUNDS,
+            threads,
+        );
+        log::debug!("span encoder calibration: {calibration}");
+        let calibration = calibrations.get_or_insert(vocab, calibration);
+
+        if let Some(path) = std::env::var_os(WORDCHIPPER_CALIBRATION_CACHE)
+            && let Err(e) = calibrations.save(&path)
+        {
+            log::warn!(
+                "span encoder calibration: cannot save {}: {e}",
+                Path::new(&path).display()
+            );
+        }
+        calibration
+    }
+
+    /// Store this calibration for `vocab` in [`SpanEncoderCalibrations::global`],
+    /// replacing any stored result for the same thread count.
+    ///
+    /// Later [`cached`](Self::cached) calls, and [`SpanEncoderSelector::Auto`]
+    /// builds, for the same vocabulary content will use it.
+    pub fn store<T: TokenType>(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+    ) {
+        SpanEncoderCalibrations::global().insert(vocab, self.clone());
+    }
+
+    /// Get the fastest encoder.
+    pub fn selected(&self) -> SpanEncoderSelector {
+        self.selected
+    }
+
+    /// Get the thread count the timings were measured on.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Get the measured timings; in [`CALIBRATION_CANDIDATES`] order.
+    ///
+    /// Without [`SpanEncoderSelector::BpeBacktrack`] on vocabs whose tokens
+    /// its merges can't all reach.
+    pub fn timings(&self) -> &[SpanEncoderTiming] {
+        &self.timings
+    }
+}
+
+impl fmt::Display for SpanEncoderCalibration {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "selected {}", self.selected)?;
+        for timing in &self.timings {
+            write!(f, "; {timing}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A store of [`SpanEncoderCalibration`]s, by vocabulary content and thread count.
+///
+/// [`SpanEncoderSelector::Auto`] uses the process-wide [`global`](Self::global) store.
+///
+/// Stores can be saved to, and loaded from, a text file; one calibration
+/// per line, as: `<fingerprint> <threads> <selector>=<nanos>/<bytes> ...`
+#[derive(Debug, Default)]
+pub struct SpanEncoderCalibrations {
+    entries: RwLock<WCHashMap<(String, usize), SpanEncoderCalibration>>,
+}
+
+/// The process-wide calibrations.
+static CALIBRATIONS: OnceCell<SpanEncoderCalibrations> = OnceCell::new();
+
+impl SpanEncoderCalibrations {
+    /// Get the process-wide store.
+    ///
+    /// On first use, loads the [`WORDCHIPPER_CALIBRATION_CACHE`] file, if set and present;
+    /// a file which cannot be loaded is logged, and ignored.
+    pub fn global() -> &'static Self {
+        CALIBRATIONS.get_or_init(|| {
+            let calibrations = Self::default();
+            if let Some(path) = std::env::var_os(WORDCHIPPER_CALIBRATION_CACHE)
+                && Path::new(&path).exists()
+                && let Err(e) = calibrations.load(&path)
+            {
+                log::warn!(
+                    "span encoder calibration: cannot load {}: {e}",
+                    Path::new(&path).display()
+                );
+            }
+            calibrations
+        })
+    }
+
+    /// Get the number of stored calibrations.
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    /// Returns true if no calibrations are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the stored calibration for `vocab` on `threads`, if any.
+    pub fn get<T: TokenType>(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        threads: usize,
+    ) -> Option<SpanEncoderCalibration> {
+        self.entries
+            .read()
+            .get(&(vocab_fingerprint(vocab), threads.max(1)))
+            .cloned()
+    }
+
+    /// Store `calibration` for `vocab`, replacing any result for the same thread count.
+    pub fn insert<T: TokenType>(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        calibration: SpanEncoderCalibration,
+    ) {
+        let key = (vocab_fingerprint(vocab), calibration.threads());
+        self.entries.write().insert(key, calibration);
+    }
+
+    /// Store `calibration` for `vocab`, unless a result for the same thread count is stored.
+    ///
+    /// ## Returns
+    /// The stored calibration.
+    fn get_or_insert<T: TokenType>(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        calibration: SpanEncoderCalibration,
+    ) -> SpanEncoderCalibration {
+        let key = (vocab_fingerprint(vocab), calibration.threads());
+        self.entries
+            .write()
+            .entry(key)
+            .or_insert(calibration)
+            .clone()
+    }
+
+    /// Load calibrations from a file written by [`save`](Self::save).
+    ///
+    /// Loaded calibrations replace stored results for the same key.
+    ///
+    /// ## Arguments
+    /// * `path` - the file to read.
+    ///
+    /// ## Returns
+    /// The number of calibrations loaded.
+    ///
+    /// ## Errors
+    /// If the file cannot be read, or a line cannot be parsed.
+    pub fn load(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> WCResult<usize> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let parse_error = |line: usize, what: &str| {
+            WCError::Parse(format!("{}:{}: {what}", path.display(), line + 1))
+        };
+
+        let mut loaded = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_ascii_whitespace();
+            let fingerprint = fields
+                .next()
+                .ok_or_else(|| parse_error(line_no, "missing fingerprint"))?;
+            let threads = fields
+                .next()
+                .and_then(|s| usize::from_str(s).ok())
+                .ok_or_else(|| parse_error(line_no, "bad thread count"))?;
+            let timings = fields
+                .map(|field| {
+                    let (selector, rest) = field.split_once('=')?;
+                    let (nanos, bytes) = rest.split_once('/')?;
+                    Some(SpanEncoderTiming {
+                        selector: SpanEncoderSelector::from_str(selector).ok()?,
+                        duration: Duration::from_nanos(nanos.parse().ok()?),
+                        sample_bytes: bytes.parse().ok()?,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| parse_error(line_no, "bad timing"))?;
+            let calibration = SpanEncoderCalibration::from_timings(timings)
+                .ok_or_else(|| parse_error(line_no, "missing timings"))?
+                .with_threads(threads);
+            loaded.push((
+                (fingerprint.to_string(), calibration.threads()),
+                calibration,
+            ));
+        }
+
+        let count = loaded.len();
+        self.entries.write().extend(loaded);
+        Ok(count)
+    }
+
+    /// Save the stored calibrations to a file, for [`load`](Self::load).
+    ///
+    /// The file is written beside `path`, then renamed over it;
+    /// so concurrent readers never see a partial file.
+    ///
+    /// ## Arguments
+    /// * `path` - the file to write.
+    ///
+    /// ## Errors
+    /// If the file cannot be written.
+    pub fn save(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> WCResult<()> {
+        let path = path.as_ref();
+        let mut lines: Vec<String> = self
+            .entries
+            .read()
+            .iter()
+            .map(|((fingerprint, threads), calibration)| {
+                let mut line = format!("{fingerprint} {threads}");
+                for t in calibration.timings() {
+                    line.push_str(&format!(
+                        " {}={}/{}",
+                        t.selector,
+                        t.duration.as_nanos(),
+                        t.sample_bytes
+                    ));
+                }
+                line
+            })
+            .collect();
+        lines.sort();
+
+        let mut text = String::from("# wordchipper span encoder calibrations\n");
+        for line in lines {
+            text.push_str(&line);
+            text.push('\n');
+        }
+
+        let mut tmp = OsString::from(path.as_os_str());
+        tmp.push(".tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenEncoder,
+        alloc::sync::Arc,
+        encoders::{
+            testing::{
+                common_encoder_test_vocab,
+                common_encoder_tests,
+                unreachable_merge_test_vocab,
+            },
+            token_span_encoder::TokenSpanEncoder,
+        },
+        pretrained::openai::OA_CL100K_BASE_PATTERN,
+        spanners::TextSpanningConfig,
+        vocab::SpanTokenMap,
+    };
+
+    fn timing(
+        selector: SpanEncoderSelector,
+        millis: u64,
+    ) -> SpanEncoderTiming {
+        SpanEncoderTiming {
+            selector,
+            duration: Duration::from_millis(millis),
+            sample_bytes: 1000,
+        }
+    }
+
+    #[test]
+    fn test_calibration() {
+        type T = u32;
+        let vocab: UnifiedTokenVocab<T> = common_encoder_test_vocab();
+
+        for threads in [1, 2] {
+            let calibration =
+                SpanEncoderCalibration::run(&vocab, CALIBRATION_SAMPLE_TEXT, 1, threads);
+            assert_eq!(calibration.threads(), threads);
+            assert_eq!(calibration.timings().len(), CALIBRATION_CANDIDATES.len());
+            for (timing, &selector) in calibration.timings().iter().zip(CALIBRATION_CANDIDATES) {
+                assert_eq!(timing.selector, selector);
+                assert_eq!(timing.sample_bytes, CALIBRATION_SAMPLE_TEXT.len() * threads);
+                assert!(timing.bytes_per_second() > 0.0);
+            }
+            let fastest = calibration
+                .timings()
+                .iter()
+                .map(|t| t.duration)
+                .min()
+                .unwrap();
+            let selected = calibration
+                .timings()
+                .iter()
+                .find(|t| t.selector == calibration.selected())
+                .unwrap();
+            assert_eq!(selected.duration, fastest);
+            assert!(
+                calibration
+                    .to_string()
+                    .starts_with(&format!("selected {}", calibration.selected()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_calibration_skips_unreachable_backtrack() {
+        type T = u32;
+        let vocab: UnifiedTokenVocab<T> = unreachable_merge_test_vocab();
+
+        let calibration = SpanEncoderCalibration::run(&vocab, " xabcd abcd", 1, 1);
+        let selectors: Vec<SpanEncoderSelector> =
+            calibration.timings().iter().map(|t| t.selector).collect();
+        let expected: Vec<SpanEncoderSelector> = CALIBRATION_CANDIDATES
+            .iter()
+            .copied()
+            .filter(|&selector| selector != SpanEncoderSelector::BpeBacktrack)
+            .collect();
+        assert_eq!(selectors, expected);
+        assert_ne!(calibration.selected(), SpanEncoderSelector::BpeBacktrack);
+    }
+
+    #[test]
+    fn test_from_timings() {
+        assert_eq!(SpanEncoderCalibration::from_timings(Vec::new()), None);
+
+        let calibration = SpanEncoderCalibration::from_timings(vec![
+            timing(SpanEncoderSelector::MergeHeap, 3),
+            timing(SpanEncoderSelector::TailSweep, 2),
+            timing(SpanEncoderSelector::BufferSweep, 2),
+        ])
+        .unwrap();
+        assert_eq!(calibration.selected(), SpanEncoderSelector::TailSweep);
+        assert_eq!(calibration.threads(), 1);
+        assert_eq!(calibration.timings()[1].bytes_per_second(), 500_000.0);
+        assert_eq!(calibration.timings()[1].to_string(), "TailSweep: 0.5 MB/s");
+        assert_eq!(calibration.with_threads(0).threads(), 1);
+    }
+
+    #[test]
+    fn test_vocab_fingerprint() {
+        let vocab: UnifiedTokenVocab<u32> = common_encoder_test_vocab();
+        let fingerprint = vocab_fingerprint(&vocab);
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(
+            vocab_fingerprint(&common_encoder_test_vocab::<u32>()),
+            fingerprint
+        );
+        assert_ne!(
+            vocab_fingerprint(&common_encoder_test_vocab::<u16>()),
+            fingerprint
+        );
+
+        // Swapping the tokens of two spans changes the fingerprint.
+        let mut spans: Vec<(Vec<u8>, u32)> = vocab
+            .span_vocab()
+            .iter()
+            .map(|(span, &token)| (span.to_vec(), token))
+            .collect();
+        spans.sort();
+        let n = spans.len();
+        let (a, b) = (spans[n - 1].1, spans[n - 2].1);
+        spans[n - 1].1 = b;
+        spans[n - 2].1 = a;
+        let swapped = UnifiedTokenVocab::from_span_vocab(
+            vocab.spanning().clone(),
+            spans.into_iter().collect::<SpanTokenMap<u32>>().into(),
+        )
+        .unwrap();
+        assert_ne!(vocab_fingerprint(&swapped), fingerprint);
+    }
+
+    #[test]
+    fn test_calibrations_store() {
+        let vocab: UnifiedTokenVocab<u32> = common_encoder_test_vocab();
+        let single = SpanEncoderCalibration::from_timings(vec![
+            timing(SpanEncoderSelector::MergeHeap, 3),
+            timing(SpanEncoderSelector::TailSweep, 2),
+        ])
+        .unwrap();
+        let threaded = SpanEncoderCalibration::from_timings(vec![
+            timing(SpanEncoderSelector::MergeHeap, 1),
+            timing(SpanEncoderSelector::TailSweep, 2),
+        ])
+        .unwrap()
+        .with_threads(4);
+
+        let calibrations = SpanEncoderCalibrations::default();
+        assert!(calibrations.is_empty());
+        calibrations.insert(&vocab, single.clone());
+        calibrations.insert(&vocab, threaded.clone());
+        assert_eq!(calibrations.len(), 2);
+
+        let copy: UnifiedTokenVocab<u32> = common_encoder_test_vocab();
+        assert_eq!(calibrations.get(&copy, 1), Some(single.clone()));
+        assert_eq!(calibrations.get(&copy, 4), Some(threaded.clone()));
+        assert_eq!(calibrations.get(&copy, 2), None);
+        assert_eq!(
+            calibrations.get_or_insert(&copy, threaded.clone().with_threads(1)),
+            single
+        );
+
+        let dir = tempdir::TempDir::new("calibrations").unwrap();
+        let path = dir.path().join("calibrations.txt");
+        calibrations.save(&path).unwrap();
+
+        let loaded = SpanEncoderCalibrations::default();
+        assert_eq!(loaded.load(&path).unwrap(), 2);
+        assert_eq!(loaded.get(&vocab, 1), Some(single));
+        assert_eq!(loaded.get(&vocab, 4), Some(threaded));
+
+        fs::write(&path, "abc 1 MergeHeap=12\n").unwrap();
+        assert!(matches!(loaded.load(&path), Err(WCError::Parse(_))));
+    }
+
+    #[test]
+    fn test_auto_selector() {
+        type T = u16;
+        // A pattern only this test uses; so the global store entry is its own.
+        let base: UnifiedTokenVocab<T> = common_encoder_test_vocab();
+        let vocab: Arc<UnifiedTokenVocab<T>> = UnifiedTokenVocab::new(
+            TextSpanningConfig::from_pattern(format!(
+                "{}|test_auto_selector",
+                OA_CL100K_BASE_PATTERN.as_str()
+            )),
+            base.span_vocab().clone(),
+            base.pair_vocab().clone(),
+        )
+        .unwrap()
+        .into();
+
+        // A stored calibration is used by later `Auto` builds.
+        let stored =
+            SpanEncoderCalibration::from_timings(vec![timing(SpanEncoderSelector::TailSweep, 1)])
+                .unwrap();
+        stored.store(&vocab);
+
+        assert_eq!(SpanEncoderCalibration::cached(&vocab, 1), stored);
+        assert_eq!(
+            SpanEncoderSelector::Auto.resolve(&vocab),
+            SpanEncoderSelector::TailSweep
+        );
+
+        let encoder = TokenSpanEncoder::<T>::new_with_selector(
+            TextSpannerBuilder::default(&vocab),
+            vocab.clone(),
+            SpanEncoderSelector::Auto,
+        );
+        let encoder: Arc<dyn TokenEncoder<T>> = Arc::new(encoder);
+        common_encoder_tests(vocab, encoder)
+    }
+}
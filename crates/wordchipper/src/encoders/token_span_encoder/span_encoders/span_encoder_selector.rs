@@ -60,9 +60,48 @@ pub enum SpanEncoderSelector {
 
     /// Use the [`BpeBacktrackSpanEncoder`] encoder.
     BpeBacktrack,
+
+    /// Time the encoders against the vocabulary, and use the fastest.
+    ///
+    /// Calibration runs once per vocabulary and thread count per process;
+    /// see `SpanEncoderCalibration::cached` (needs `std`). Results can be kept
+    /// across processes; see `WORDCHIPPER_CALIBRATION_CACHE`.
+    /// Without `std`, this is an alias for: [`ConcurrentDefault`](`Self::ConcurrentDefault`)
+    Auto,
 }
 
 impl SpanEncoderSelector {
+    /// Resolve [`Auto`](Self::Auto) to the calibrated single-threaded encoder for `vocab`.
+    ///
+    /// Other selectors resolve to themselves.
+    pub fn resolve<T: TokenType>(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+    ) -> SpanEncoderSelector {
+        self.resolve_for_threads(vocab, 1)
+    }
+
+    /// Resolve [`Auto`](Self::Auto) to the calibrated encoder for `vocab`,
+    /// when encoding on `threads` threads at once.
+    ///
+    /// Other selectors resolve to themselves.
+    pub fn resolve_for_threads<T: TokenType>(
+        &self,
+        vocab: &UnifiedTokenVocab<T>,
+        threads: usize,
+    ) -> SpanEncoderSelector {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Auto => super::SpanEncoderCalibration::cached(vocab, threads).selected(),
+            #[cfg(not(feature = "std"))]
+            Self::Auto => {
+                let _ = (vocab, threads);
+                Self::ConcurrentDefault
+            }
+            selector => *selector,
+        }
+    }
+
     /// Get a builder for the configured [`SpanEncoder`].
     ///
     /// The `vocab` parameter is needed by encoders that pre-build data structures
@@ -87,6 +126,7 @@ impl SpanEncoderSelector {
                 let bpe_vocab = Arc::new(BpeVocab::from_vocab(vocab));
                 Arc::new(move || Box::new(BpeBacktrackSpanEncoder::new(bpe_vocab.clone())))
             }
+            Auto => self.resolve(vocab).span_encoder_builder(vocab),
         }
     }
 }
@@ -0,0 +1,519 @@
+//! # Unigram [`SpanEncoder`].
+//!
+//! Viterbi segmentation of each word span over the lattice of
+//! [`UnigramVocab`] pieces; with n-best and sampled segmentations.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use num_traits::Float;
+
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    alloc::{boxed::Box, sync::Arc, vec, vec::Vec},
+    encoders::token_span_encoder::SpanEncoder,
+    support::random::SplitMix64,
+    vocab::UnigramVocab,
+};
+
+/// A lattice edge; a piece covering `start..end` of the word.
+#[derive(Debug, Clone, Copy)]
+struct LatticeEdge<T> {
+    start: usize,
+    end: usize,
+    token: T,
+    score: f64,
+}
+
+/// A unigram [`SpanEncoder`].
+///
+/// Word spans are encoded against the [`UnigramVocab`]; the
+/// [`UnifiedTokenVocab`] passed to the encode methods supplies only
+/// the special tokens (see [`UnigramVocab::to_unified_vocab`]).
+///
+/// By default each word encodes as its best (Viterbi) segmentation. With
+/// [`with_sampling`](Self::with_sampling), segmentations are instead sampled
+/// in proportion to their probability; e.g. for subword regularization.
+/// Sampling encoders should not be wrapped in a span cache.
+pub struct UnigramSpanEncoder<T: TokenType> {
+    unigram_vocab: Arc<UnigramVocab<T>>,
+
+    /// The sampling smoothing parameter, and generator.
+    sampling: Option<(f64, SplitMix64)>,
+
+    /// Scratch buffer for the lattice edges, in `start` order.
+    edges: Vec<LatticeEdge<T>>,
+
+    /// Scratch buffer for per-position scores.
+    scores: Vec<f64>,
+
+    /// Scratch buffer for per-position best incoming edges.
+    back: Vec<usize>,
+
+    /// Scratch buffer for the edges of the current path.
+    path: Vec<usize>,
+}
+
+impl<T: TokenType> UnigramSpanEncoder<T> {
+    /// Create a new encoder.
+    pub fn new(unigram_vocab: Arc<UnigramVocab<T>>) -> Self {
+        Self {
+            unigram_vocab,
+            sampling: None,
+            edges: Vec::new(),
+            scores: Vec::new(),
+            back: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+
+    /// Sample segmentations, rather than taking the best.
+    ///
+    /// ## Arguments
+    /// * `alpha` - the smoothing parameter; each segmentation is sampled
+    ///   with probability proportional to `exp(alpha * score)`. Large values
+    ///   approach the best segmentation; `0.0` is uniform.
+    /// * `seed` - the generator seed.
+    pub fn with_sampling(
+        self,
+        alpha: f32,
+        seed: u64,
+    ) -> Self {
+        Self {
+            sampling: Some((alpha as f64, SplitMix64::new(seed))),
+            ..self
+        }
+    }
+
+    /// Build a span encoder builder closure, for
+    /// [`TokenSpanEncoder::new_with_builder`](crate::encoders::token_span_encoder::TokenSpanEncoder::new_with_builder).
+    pub fn builder(
+        unigram_vocab: Arc<UnigramVocab<T>>
+    ) -> Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync> {
+        Arc::new(move || Box::new(Self::new(unigram_vocab.clone())))
+    }
+
+    /// Build a sampling span encoder builder closure.
+    ///
+    /// See [`with_sampling`](Self::with_sampling). The n-th built encoder
+    /// is seeded with the n-th seed derived from `seed`
+    /// (see [`SplitMix64::derive_seed`]); so pooled encoders, and encoders
+    /// built per call, don't repeat each other's samples.
+    pub fn sampling_builder(
+        unigram_vocab: Arc<UnigramVocab<T>>,
+        alpha: f32,
+        seed: u64,
+    ) -> Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync> {
+        let built = AtomicU64::new(0);
+        Arc::new(move || {
+            let seed = SplitMix64::derive_seed(seed, built.fetch_add(1, Ordering::Relaxed));
+            Box::new(Self::new(unigram_vocab.clone()).with_sampling(alpha, seed))
+        })
+    }
+
+    /// Get the [`UnigramVocab`].
+    pub fn unigram_vocab(&self) -> &Arc<UnigramVocab<T>> {
+        &self.unigram_vocab
+    }
+
+    /// Build the lattice of `word` into `self.edges`.
+    ///
+    /// Every char is covered: a char which starts no single-char piece
+    /// gets an unknown edge.
+    fn build_lattice(
+        &mut self,
+        word: &str,
+    ) {
+        let vocab = &self.unigram_vocab;
+        let bytes = word.as_bytes();
+
+        self.edges.clear();
+        for (start, c) in word.char_indices() {
+            let single_end = start + c.len_utf8();
+            let max_end = bytes.len().min(start + vocab.max_piece_len());
+
+            let mut has_single = false;
+            for (offset, c) in word[start..].char_indices() {
+                let end = start + offset + c.len_utf8();
+                if end > max_end {
+                    break;
+                }
+                if let Some((token, score)) = vocab.lookup_piece(&bytes[start..end]) {
+                    has_single |= end == single_end;
+                    self.edges.push(LatticeEdge {
+                        start,
+                        end,
+                        token,
+                        score: score as f64,
+                    });
+                }
+            }
+            if !has_single {
+                self.edges.push(LatticeEdge {
+                    start,
+                    end: single_end,
+                    token: vocab.unk_token(),
+                    score: vocab.unk_score() as f64,
+                });
+            }
+        }
+    }
+
+    /// Append the tokens of the edges of `self.path`, fusing unknowns.
+    fn append_path(
+        &self,
+        tokens: &mut Vec<T>,
+    ) {
+        let unk = self.unigram_vocab.unk_token();
+        let fuse_unk = self.unigram_vocab.fuse_unk();
+        let mut last = None;
+        for &idx in &self.path {
+            let token = self.edges[idx].token;
+            if fuse_unk && token == unk && last == Some(unk) {
+                continue;
+            }
+            tokens.push(token);
+            last = Some(token);
+        }
+    }
+
+    /// Find the best path through the lattice, into `self.path`.
+    fn viterbi(
+        &mut self,
+        len: usize,
+    ) {
+        self.scores.clear();
+        self.scores.resize(len + 1, f64::NEG_INFINITY);
+        self.scores[0] = 0.0;
+        self.back.clear();
+        self.back.resize(len + 1, usize::MAX);
+
+        for (idx, edge) in self.edges.iter().enumerate() {
+            let score = self.scores[edge.start] + edge.score;
+            if score > self.scores[edge.end] {
+                self.scores[edge.end] = score;
+                self.back[edge.end] = idx;
+            }
+        }
+
+        self.path.clear();
+        let mut pos = len;
+        while pos > 0 {
+            let idx = self.back[pos];
+            self.path.push(idx);
+            pos = self.edges[idx].start;
+        }
+        self.path.reverse();
+    }
+
+    /// Sample a path through the lattice, into `self.path`.
+    ///
+    /// Forward-filtering, backward-sampling.
+    fn sample(
+        &mut self,
+        len: usize,
+    ) {
+        let Some((alpha, rng)) = self.sampling.as_mut() else {
+            return self.viterbi(len);
+        };
+        let alpha = *alpha;
+
+        // `scores[pos]` is the log-sum of the weights of the paths to `pos`.
+        self.scores.clear();
+        self.scores.resize(len + 1, f64::NEG_INFINITY);
+        self.scores[0] = 0.0;
+        for edge in &self.edges {
+            let score = self.scores[edge.start] + alpha * edge.score;
+            self.scores[edge.end] = log_add_exp(self.scores[edge.end], score);
+        }
+
+        self.path.clear();
+        let mut pos = len;
+        while pos > 0 {
+            let mut target = rng.next_f64();
+            let mut chosen = usize::MAX;
+            for (idx, edge) in self.edges.iter().enumerate() {
+                if edge.end != pos {
+                    continue;
+                }
+                chosen = idx;
+                target -=
+                    Float::exp(self.scores[edge.start] + alpha * edge.score - self.scores[pos]);
+                if target < 0.0 {
+                    break;
+                }
+            }
+            self.path.push(chosen);
+            pos = self.edges[chosen].start;
+        }
+        self.path.reverse();
+    }
+
+    /// Encode a word as its best segmentation; or a sampled one,
+    /// if [`with_sampling`](Self::with_sampling) is set.
+    ///
+    /// ## Arguments
+    /// * `word` - The word.
+    /// * `tokens` - The target token buffer to append to.
+    pub fn encode_append_word(
+        &mut self,
+        word: &str,
+        tokens: &mut Vec<T>,
+    ) {
+        if word.is_empty() {
+            return;
+        }
+        self.build_lattice(word);
+        match self.sampling {
+            Some(_) => self.sample(word.len()),
+            None => self.viterbi(word.len()),
+        }
+        self.append_path(tokens);
+    }
+
+    /// Find the `n` best segmentations of a word.
+    ///
+    /// ## Arguments
+    /// * `word` - The word.
+    /// * `n` - The max number of segmentations.
+    ///
+    /// ## Returns
+    /// The segmentations and their scores, best first.
+    pub fn nbest_word(
+        &mut self,
+        word: &str,
+        n: usize,
+    ) -> Vec<(Vec<T>, f32)> {
+        if word.is_empty() || n == 0 {
+            return Vec::new();
+        }
+        self.build_lattice(word);
+
+        // `nodes[pos]` holds the best `(score, edge, rank of edge start)`
+        // paths to `pos`, best first.
+        let mut nodes: Vec<Vec<(f64, usize, usize)>> = vec![Vec::new(); word.len() + 1];
+        nodes[0].push((0.0, usize::MAX, 0));
+        for (idx, edge) in self.edges.iter().enumerate() {
+            let (head, tail) = nodes.split_at_mut(edge.end);
+            for (rank, &(score, ..)) in head[edge.start].iter().enumerate() {
+                let candidate = (score + edge.score, idx, rank);
+                let target = &mut tail[0];
+                let at = target.partition_point(|&(s, ..)| s >= candidate.0);
+                if at < n {
+                    target.insert(at, candidate);
+                    target.truncate(n);
+                }
+            }
+        }
+
+        let mut results = Vec::with_capacity(n);
+        for &(score, ..) in &nodes[word.len()] {
+            results.push((Vec::new(), score as f32));
+        }
+        for (rank, (tokens, _)) in results.iter_mut().enumerate() {
+            self.path.clear();
+            let (mut pos, mut rank) = (word.len(), rank);
+            while pos > 0 {
+                let (_, idx, prev) = nodes[pos][rank];
+                self.path.push(idx);
+                (pos, rank) = (self.edges[idx].start, prev);
+            }
+            self.path.reverse();
+            self.append_path(tokens);
+        }
+        results
+    }
+}
+
+/// `ln(exp(a) + exp(b))`, stably.
+fn log_add_exp(
+    a: f64,
+    b: f64,
+) -> f64 {
+    let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+    if lo == f64::NEG_INFINITY {
+        return hi;
+    }
+    hi + Float::ln_1p(Float::exp(lo - hi))
+}
+
+impl<T: TokenType> SpanEncoder<T> for UnigramSpanEncoder<T> {
+    fn lookup_word_token(
+        &self,
+        _vocab: &UnifiedTokenVocab<T>,
+        _span: &[u8],
+    ) -> Option<T> {
+        // A whole-word piece is not always the best segmentation.
+        None
+    }
+
+    fn encode_append_invalid_bytes(
+        &mut self,
+        _vocab: &UnifiedTokenVocab<T>,
+        _bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        // The unigram decoder has no byte tokens.
+        tokens.push(self.unigram_vocab.unk_token());
+    }
+
+    fn encode_append_compound_span(
+        &mut self,
+        _vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        match core::str::from_utf8(span) {
+            Ok(word) => self.encode_append_word(word, tokens),
+            Err(_) => tokens.push(self.unigram_vocab.unk_token()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenEncoder,
+        encoders::token_span_encoder::TokenSpanEncoder,
+        spanners::TextSpannerBuilder,
+        vocab::{DEFAULT_UNIGRAM_UNK_WORD, sentencepiece_spanning_config},
+    };
+
+    fn test_vocab() -> UnigramVocab<u32> {
+        let words = [
+            ("<unk>", 0.0),
+            ("<s>", 0.0),
+            ("</s>", 0.0),
+            ("\u{2581}", -3.0),
+            ("\u{2581}hello", -4.0),
+            ("\u{2581}he", -3.0),
+            ("llo", -3.0),
+            ("h", -4.0),
+            ("e", -4.0),
+            ("l", -4.0),
+            ("o", -4.0),
+            ("\u{2581}world", -5.0),
+            ("ab", -2.0),
+            ("a", -2.0),
+            ("b", -2.0),
+        ];
+        UnigramVocab::from_scored_words(
+            sentencepiece_spanning_config(),
+            words,
+            &["<unk>", "<s>", "</s>"],
+            DEFAULT_UNIGRAM_UNK_WORD,
+        )
+        .unwrap()
+    }
+
+    fn encode(
+        se: &mut UnigramSpanEncoder<u32>,
+        word: &str,
+    ) -> Vec<u32> {
+        let mut tokens = vec![];
+        se.encode_append_word(word, &mut tokens);
+        tokens
+    }
+
+    #[test]
+    fn test_viterbi() {
+        let mut se = UnigramSpanEncoder::new(Arc::new(test_vocab()));
+
+        // -4.0 beats -3.0 + -3.0.
+        assert_eq!(encode(&mut se, "\u{2581}hello"), vec![4]);
+        assert_eq!(encode(&mut se, "\u{2581}helo"), vec![5, 9, 10]);
+        // -2.0 beats -2.0 + -2.0.
+        assert_eq!(encode(&mut se, "abab"), vec![12, 12]);
+        assert!(encode(&mut se, "").is_empty());
+
+        // Unknown runs are fused.
+        assert_eq!(encode(&mut se, "axyb"), vec![13, 0, 14]);
+        let mut se = UnigramSpanEncoder::new(Arc::new(test_vocab().with_fuse_unk(false)));
+        assert_eq!(encode(&mut se, "axyb"), vec![13, 0, 0, 14]);
+    }
+
+    #[test]
+    fn test_nbest() {
+        let mut se = UnigramSpanEncoder::new(Arc::new(test_vocab()));
+
+        assert_eq!(
+            se.nbest_word("\u{2581}hello", 3),
+            vec![
+                (vec![4], -4.0),
+                (vec![5, 6], -6.0),
+                (vec![3, 7, 8, 6], -14.0),
+            ]
+        );
+        assert_eq!(
+            se.nbest_word("ab", 10),
+            vec![(vec![12], -2.0), (vec![13, 14], -4.0)]
+        );
+        assert!(se.nbest_word("ab", 0).is_empty());
+    }
+
+    #[test]
+    fn test_sampling() {
+        let vocab = Arc::new(test_vocab());
+
+        // A large alpha approaches the best segmentation.
+        let mut se = UnigramSpanEncoder::new(vocab.clone()).with_sampling(100.0, 1);
+        for _ in 0..10 {
+            assert_eq!(encode(&mut se, "\u{2581}hello"), vec![4]);
+        }
+
+        // A uniform alpha samples every segmentation.
+        let mut se = UnigramSpanEncoder::new(vocab.clone()).with_sampling(0.0, 1);
+        let mut seen = crate::WCHashSet::default();
+        for _ in 0..200 {
+            let tokens = encode(&mut se, "abab");
+            let pieces: Vec<u8> = tokens
+                .iter()
+                .flat_map(|t| vocab.token_spans()[t].clone())
+                .collect();
+            assert_eq!(pieces, b"abab");
+            seen.insert(tokens);
+        }
+        // `ab|a,b` for each half.
+        assert_eq!(seen.len(), 4);
+
+        // Sampling is reproducible.
+        let mut a = UnigramSpanEncoder::new(vocab.clone()).with_sampling(0.5, 7);
+        let mut b = UnigramSpanEncoder::new(vocab.clone()).with_sampling(0.5, 7);
+        for _ in 0..10 {
+            assert_eq!(encode(&mut a, "abab"), encode(&mut b, "abab"));
+        }
+
+        // Each built encoder samples with its own seed.
+        let unified = test_vocab().to_unified_vocab().unwrap();
+        let builder = UnigramSpanEncoder::sampling_builder(vocab, 0.0, 7);
+        let samples = |mut se: Box<dyn SpanEncoder<u32>>| -> Vec<Vec<u32>> {
+            (0..20)
+                .map(|_| {
+                    let mut tokens = vec![];
+                    se.encode_append_compound_span(&unified, b"abab", &mut tokens);
+                    tokens
+                })
+                .collect()
+        };
+        assert_ne!(samples(builder()), samples(builder()));
+    }
+
+    #[test]
+    fn test_token_span_encoder() {
+        let vocab = Arc::new(test_vocab());
+        let unified = Arc::new(vocab.to_unified_vocab().unwrap());
+
+        let encoder = TokenSpanEncoder::new_with_builder(
+            TextSpannerBuilder::default(&unified),
+            unified.clone(),
+            UnigramSpanEncoder::builder(vocab),
+        );
+
+        assert_eq!(
+            encoder.try_encode("<s>  hello   world xy</s>").unwrap(),
+            vec![1, 4, 11, 3, 0, 2]
+        );
+        assert_eq!(encoder.try_encode_bytes(b"ab\xff").unwrap(), vec![3, 12, 0]);
+    }
+}
@@ -0,0 +1,226 @@
+//! # Wordpiece [`SpanEncoder`].
+//!
+//! Greedy longest-match-first segmentation of each word span,
+//! over a [`WordPieceVocab`].
+
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    alloc::{boxed::Box, sync::Arc, vec::Vec},
+    encoders::token_span_encoder::SpanEncoder,
+    vocab::WordPieceVocab,
+};
+
+/// A wordpiece [`SpanEncoder`].
+///
+/// Word spans are encoded against the [`WordPieceVocab`]; the
+/// [`UnifiedTokenVocab`] passed to the encode methods supplies only
+/// the special tokens (see [`WordPieceVocab::to_unified_vocab`]).
+///
+/// Words longer than
+/// [`max_input_chars_per_word`](WordPieceVocab::max_input_chars_per_word),
+/// and words with no complete segmentation, encode as the unknown token.
+pub struct WordPieceSpanEncoder<T: TokenType> {
+    wordpiece_vocab: Arc<WordPieceVocab<T>>,
+
+    /// Scratch buffer for prefixed piece lookups.
+    piece_buf: Vec<u8>,
+
+    /// Scratch buffer for the pieces of the current word.
+    word_tokens: Vec<T>,
+}
+
+impl<T: TokenType> WordPieceSpanEncoder<T> {
+    /// Create a new encoder.
+    pub fn new(wordpiece_vocab: Arc<WordPieceVocab<T>>) -> Self {
+        Self {
+            wordpiece_vocab,
+            piece_buf: Vec::new(),
+            word_tokens: Vec::new(),
+        }
+    }
+
+    /// Build a span encoder builder closure, for
+    /// [`TokenSpanEncoder::new_with_builder`](crate::encoders::token_span_encoder::TokenSpanEncoder::new_with_builder).
+    pub fn builder(
+        wordpiece_vocab: Arc<WordPieceVocab<T>>
+    ) -> Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync> {
+        Arc::new(move || Box::new(Self::new(wordpiece_vocab.clone())))
+    }
+
+    /// Get the [`WordPieceVocab`].
+    pub fn wordpiece_vocab(&self) -> &Arc<WordPieceVocab<T>> {
+        &self.wordpiece_vocab
+    }
+
+    /// Segment `word` into `self.word_tokens`.
+    ///
+    /// ## Returns
+    /// `false` if the word has no complete segmentation.
+    fn segment_word(
+        &mut self,
+        word: &str,
+    ) -> bool {
+        let vocab = &self.wordpiece_vocab;
+        let prefix = vocab.continuation_prefix().as_bytes();
+        let bytes = word.as_bytes();
+
+        self.word_tokens.clear();
+        let mut start = 0;
+        while start < bytes.len() {
+            let limit = match start {
+                0 => vocab.max_piece_len(),
+                _ => vocab.max_piece_len().saturating_sub(prefix.len()),
+            };
+            let mut end = bytes.len().min(start + limit);
+
+            let mut found = None;
+            while end > start {
+                if word.is_char_boundary(end) {
+                    let piece = match start {
+                        0 => &bytes[..end],
+                        _ => {
+                            self.piece_buf.clear();
+                            self.piece_buf.extend_from_slice(prefix);
+                            self.piece_buf.extend_from_slice(&bytes[start..end]);
+                            &self.piece_buf
+                        }
+                    };
+                    if let Some(token) = vocab.lookup_piece(piece) {
+                        found = Some(token);
+                        break;
+                    }
+                }
+                end -= 1;
+            }
+
+            match found {
+                Some(token) => self.word_tokens.push(token),
+                None => return false,
+            }
+            start = end;
+        }
+        true
+    }
+}
+
+impl<T: TokenType> SpanEncoder<T> for WordPieceSpanEncoder<T> {
+    fn lookup_word_token(
+        &self,
+        _vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+    ) -> Option<T> {
+        // A whole-word piece is always the longest first match.
+        self.wordpiece_vocab.lookup_piece(span)
+    }
+
+    fn encode_append_invalid_bytes(
+        &mut self,
+        _vocab: &UnifiedTokenVocab<T>,
+        _bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        // The wordpiece decoder has no byte tokens.
+        tokens.push(self.wordpiece_vocab.unk_token());
+    }
+
+    fn encode_append_compound_span(
+        &mut self,
+        _vocab: &UnifiedTokenVocab<T>,
+        span: &[u8],
+        tokens: &mut Vec<T>,
+    ) {
+        let unk = self.wordpiece_vocab.unk_token();
+        let Ok(word) = core::str::from_utf8(span) else {
+            tokens.push(unk);
+            return;
+        };
+        if word.chars().count() > self.wordpiece_vocab.max_input_chars_per_word() {
+            tokens.push(unk);
+            return;
+        }
+
+        match self.segment_word(word) {
+            true => tokens.extend_from_slice(&self.word_tokens),
+            false => tokens.push(unk),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenEncoder,
+        alloc::vec,
+        encoders::token_span_encoder::TokenSpanEncoder,
+        spanners::TextSpannerBuilder,
+        vocab::{BERT_SPECIAL_WORDS, DEFAULT_WORDPIECE_UNK_WORD, bert_spanning_config},
+    };
+
+    fn test_vocab() -> WordPieceVocab<u32> {
+        let words = [
+            "[PAD]", "[UNK]", "[CLS]", "[SEP]", "[MASK]", "un", "##aff", "##able", "want", "##ed",
+            "runn", "##ing", ",", "the", "a", "##é", "日", "本",
+        ];
+        WordPieceVocab::from_words(
+            bert_spanning_config(true),
+            words,
+            &BERT_SPECIAL_WORDS,
+            DEFAULT_WORDPIECE_UNK_WORD,
+        )
+        .unwrap()
+    }
+
+    fn encode(
+        se: &mut WordPieceSpanEncoder<u32>,
+        word: &str,
+    ) -> Vec<u32> {
+        let unified = se.wordpiece_vocab().to_unified_vocab().unwrap();
+        let mut tokens = vec![];
+        se.encode_append_compound_span(&unified, word.as_bytes(), &mut tokens);
+        tokens
+    }
+
+    #[test]
+    fn test_segment_words() {
+        let vocab = Arc::new(test_vocab());
+        let mut se = WordPieceSpanEncoder::new(vocab.clone());
+
+        assert_eq!(encode(&mut se, "unaffable"), vec![5, 6, 7]);
+        assert_eq!(encode(&mut se, "wanted"), vec![8, 9]);
+        assert_eq!(encode(&mut se, "running"), vec![10, 11]);
+        assert_eq!(encode(&mut se, "aé"), vec![14, 15]);
+        // No complete segmentation.
+        assert_eq!(encode(&mut se, "unwanted"), vec![1]);
+        assert_eq!(encode(&mut se, "xyz"), vec![1]);
+
+        let mut se =
+            WordPieceSpanEncoder::new(Arc::new(test_vocab().with_max_input_chars_per_word(4)));
+        assert_eq!(encode(&mut se, "want"), vec![8]);
+        assert_eq!(encode(&mut se, "wanted"), vec![1]);
+    }
+
+    #[test]
+    fn test_token_span_encoder() {
+        let vocab = Arc::new(test_vocab());
+        let unified = Arc::new(vocab.to_unified_vocab().unwrap());
+
+        let encoder = TokenSpanEncoder::new_with_builder(
+            TextSpannerBuilder::default(&unified),
+            unified.clone(),
+            WordPieceSpanEncoder::builder(vocab),
+        );
+
+        assert_eq!(
+            encoder
+                .try_encode("[CLS] UnAffable, the RUNNING 日本 wanted[SEP]")
+                .unwrap(),
+            vec![2, 5, 6, 7, 12, 13, 10, 11, 16, 17, 8, 9, 3]
+        );
+        assert_eq!(
+            encoder.try_encode_bytes(b"wanted \xff\xfe").unwrap(),
+            vec![8, 9, 1, 1]
+        );
+    }
+}
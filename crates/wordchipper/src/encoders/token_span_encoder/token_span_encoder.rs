@@ -4,12 +4,36 @@ use crate::{
     UnifiedTokenVocab,
     WCResult,
     alloc::{boxed::Box, sync::Arc, vec::Vec},
-    encoders::token_span_encoder::{SpanEncoder, SpanEncoderSelector},
-    spanners::TextSpanner,
+    encoders::token_span_encoder::{
+        SpanCacheCounters,
+        SpanCacheStats,
+        SpanEncoder,
+        SpanEncoderSelector,
+    },
+    spanners::{TextNormalizer, TextSpanner},
     vocab::SpecialVocab,
 };
 
+/// Builder closure for [`SpanEncoder`]s.
+type SpanEncoderBuilder<T> = Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>;
+
+/// Pool of [`SpanEncoder`]s.
+#[cfg(feature = "concurrent")]
+type SpanEncoderPool<T> =
+    crate::support::concurrency::PoolToy<std::sync::Mutex<Box<dyn SpanEncoder<T>>>>;
+
 /// A [`TokenEncoder`] that composes a [`TextSpanner`] with a [`SpanEncoder`].
+///
+/// ## Span Cache
+///
+/// With [`with_span_cache`](Self::with_span_cache), each pooled span encoder
+/// is wrapped in a
+/// [`CachedSpanEncoder`](crate::encoders::token_span_encoder::CachedSpanEncoder);
+/// repeated compound spans skip the merge loop. Each cache counts its own
+/// hits/misses; [`span_cache_stats`](Self::span_cache_stats) sums them.
+///
+/// The cache needs the `concurrent` feature: without it, there is no pool
+/// and each call builds a fresh span encoder; so the cache is not used.
 pub struct TokenSpanEncoder<T>
 where
     T: TokenType,
@@ -20,11 +44,17 @@ where
     /// Text Spanner.
     spanner: Arc<dyn TextSpanner>,
 
-    #[cfg(feature = "concurrent")]
-    se_pool: crate::support::concurrency::PoolToy<std::sync::Mutex<Box<dyn SpanEncoder<T>>>>,
+    /// Builds the (un-cached) span encoders.
+    se_builder: SpanEncoderBuilder<T>,
+
+    /// Max cached spans per span encoder; `0` disables the cache.
+    span_cache_size: usize,
+
+    /// Span cache counters; one per pooled span encoder.
+    span_cache_counters: Vec<Arc<SpanCacheCounters>>,
 
-    #[cfg(not(feature = "concurrent"))]
-    se_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+    #[cfg(feature = "concurrent")]
+    se_pool: SpanEncoderPool<T>,
 }
 
 impl<T: TokenType> TokenSpanEncoder<T> {
@@ -45,29 +75,109 @@ impl<T: TokenType> TokenSpanEncoder<T> {
     pub fn new_with_builder(
         spanner: Arc<dyn TextSpanner>,
         vocab: Arc<UnifiedTokenVocab<T>>,
-        se_builder: Arc<dyn Fn() -> Box<dyn SpanEncoder<T>> + Send + Sync>,
+        se_builder: SpanEncoderBuilder<T>,
     ) -> Self {
-        cfg_if::cfg_if! {
-            if #[cfg(feature = "concurrent")] {
-                use crate::support::concurrency::{PoolToy, threads::resolve_max_pool};
+        Self {
+            #[cfg(feature = "concurrent")]
+            se_pool: Self::build_pool(&se_builder, 0).0,
+            vocab,
+            spanner,
+            se_builder,
+            span_cache_size: 0,
+            span_cache_counters: Vec::new(),
+        }
+    }
 
-                let pool_size = resolve_max_pool(None);
-                let pool: Vec<std::sync::Mutex<Box<dyn SpanEncoder<T>>>> =
-                    (0..pool_size).map(|_| std::sync::Mutex::new(se_builder())).collect();
+    /// Enable a bounded span cache on each pooled span encoder.
+    ///
+    /// Only spans of up to [`MAX_CACHED_SPAN_BYTES`](super::MAX_CACHED_SPAN_BYTES)
+    /// are cached. Has no effect without the `concurrent` feature.
+    ///
+    /// ## Arguments
+    /// * `span_cache_size` - max cached spans per span encoder; `0` disables the cache.
+    pub fn with_span_cache(
+        mut self,
+        span_cache_size: usize,
+    ) -> Self {
+        self.span_cache_size = span_cache_size;
+        #[cfg(feature = "concurrent")]
+        {
+            (self.se_pool, self.span_cache_counters) =
+                Self::build_pool(&self.se_builder, span_cache_size);
+        }
+        self
+    }
 
-                Self {
-                    vocab,
-                    spanner,
-                    se_pool: PoolToy::from_pool(pool),
-                }
+    /// Get the max cached spans per span encoder; `0` when disabled.
+    pub fn span_cache_size(&self) -> usize {
+        self.span_cache_size
+    }
+
+    /// Get a snapshot of the span cache hit/miss counts, summed over the pool.
+    pub fn span_cache_stats(&self) -> SpanCacheStats {
+        SpanCacheStats::sum(self.span_cache_counters.iter().map(|c| c.stats()))
+    }
+
+    /// Run `f` with a span encoder; pooled, when concurrent.
+    fn with_span_encoder<R>(
+        &self,
+        f: impl FnOnce(&mut dyn SpanEncoder<T>) -> R,
+    ) -> R {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "concurrent")] {
+                let mut se = self.se_pool.get().lock().unwrap_or_else(|e| e.into_inner());
             } else {
-                Self {
-                    vocab,
-                    spanner,
-                    se_builder,
-                }
+                let mut se = (self.se_builder)();
             }
         }
+        f(se.as_mut())
+    }
+
+    /// Normalize, span and encode text.
+    fn encode_append_text(
+        &self,
+        se: &mut dyn SpanEncoder<T>,
+        text: &str,
+        tokens: &mut Vec<T>,
+    ) {
+        let text = self
+            .vocab
+            .spanning()
+            .normalizer()
+            .normalize_str_around_specials(text, self.spanner.special_lexer());
+        self.spanner.for_each_split_span(&text, &mut |span_ref| {
+            se.encode_append_span_ref(&self.vocab, &text, span_ref, tokens);
+            true
+        });
+    }
+
+    /// Build the span encoder pool; and the counters of its caches.
+    #[cfg(feature = "concurrent")]
+    fn build_pool(
+        se_builder: &SpanEncoderBuilder<T>,
+        span_cache_size: usize,
+    ) -> (SpanEncoderPool<T>, Vec<Arc<SpanCacheCounters>>) {
+        use crate::{
+            encoders::token_span_encoder::CachedSpanEncoder,
+            support::concurrency::{PoolToy, threads::resolve_max_pool},
+        };
+
+        let pool_size = resolve_max_pool(None);
+        let mut counters = Vec::new();
+        let pool: Vec<std::sync::Mutex<Box<dyn SpanEncoder<T>>>> = (0..pool_size)
+            .map(|_| {
+                let se: Box<dyn SpanEncoder<T>> = match span_cache_size {
+                    0 => se_builder(),
+                    size => {
+                        let c = Arc::new(SpanCacheCounters::default());
+                        counters.push(c.clone());
+                        Box::new(CachedSpanEncoder::new(se_builder(), size, c))
+                    }
+                };
+                std::sync::Mutex::new(se)
+            })
+            .collect();
+        (PoolToy::from_pool(pool), counters)
     }
 }
 
@@ -80,6 +190,10 @@ impl<T: TokenType> TokenEncoder<T> for TokenSpanEncoder<T> {
         self.vocab.spanning().specials()
     }
 
+    fn normalizer(&self) -> Option<&TextNormalizer> {
+        Some(self.vocab.spanning().normalizer())
+    }
+
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", skip(self, text, tokens))
@@ -89,19 +203,94 @@ impl<T: TokenType> TokenEncoder<T> for TokenSpanEncoder<T> {
         text: &str,
         tokens: &mut Vec<T>,
     ) -> WCResult<()> {
-        cfg_if::cfg_if! {
-            if #[cfg(feature = "concurrent")] {
-                let mut se = self.se_pool.get().lock().unwrap_or_else(|e| e.into_inner());
-            } else {
-                let mut se = (self.se_builder)();
+        self.with_span_encoder(|se| self.encode_append_text(se, text, tokens));
+        Ok(())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, bytes, tokens))
+    )]
+    fn try_encode_bytes_append(
+        &self,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) -> WCResult<()> {
+        self.with_span_encoder(|se| {
+            for chunk in bytes.utf8_chunks() {
+                self.encode_append_text(se, chunk.valid(), tokens);
+                if !chunk.invalid().is_empty() {
+                    se.encode_append_invalid_bytes(&self.vocab, chunk.invalid(), tokens);
+                }
             }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encoders::testing::common_encoder_test_vocab, spanners::TextSpannerBuilder};
+
+    #[test]
+    fn test_span_cache() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+        let spanner = TextSpannerBuilder::default(&vocab);
+
+        let plain = TokenSpanEncoder::<T>::new_with_selector(
+            spanner.clone(),
+            vocab.clone(),
+            SpanEncoderSelector::SingleThreadDefault,
+        );
+        assert_eq!(plain.span_cache_size(), 0);
+
+        let cached = TokenSpanEncoder::<T>::new_with_selector(
+            spanner,
+            vocab.clone(),
+            SpanEncoderSelector::SingleThreadDefault,
+        )
+        .with_span_cache(64);
+        assert_eq!(cached.span_cache_size(), 64);
+
+        let text = "hello world, hello again; hello world";
+        assert_eq!(
+            cached.try_encode(text).unwrap(),
+            plain.try_encode(text).unwrap()
+        );
+        assert_eq!(plain.span_cache_stats(), SpanCacheStats::default());
+
+        let stats = cached.span_cache_stats();
+        if cfg!(feature = "concurrent") {
+            assert!(stats.hits > 0);
+            assert!(stats.misses > 0);
+        } else {
+            assert_eq!(stats, SpanCacheStats::default());
         }
+    }
 
-        self.spanner.for_each_split_span(text, &mut |span_ref| {
-            se.encode_append_span_ref(&self.vocab, text, span_ref, tokens);
-            true
-        });
+    #[test]
+    #[cfg(feature = "std")]
+    fn proptest_encode_bytes_round_trip() {
+        use proptest::prelude::*;
 
-        Ok(())
+        use crate::{TokenDecoder, decoders::TokenDictDecoder};
+
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab().into();
+        let encoder = TokenSpanEncoder::<T>::new_with_selector(
+            TextSpannerBuilder::default(&vocab),
+            vocab.clone(),
+            SpanEncoderSelector::SingleThreadDefault,
+        );
+        let decoder = TokenDictDecoder::from_vocab(vocab);
+
+        let config = proptest::test_runner::Config::with_cases(500);
+        proptest!(config, |(bytes in proptest::collection::vec(any::<u8>(), 0..200))| {
+            let tokens = encoder.try_encode_bytes(&bytes).unwrap();
+            let decoded = decoder.try_decode_to_bytes(&tokens).unwrap().unwrap();
+            prop_assert_eq!(decoded, bytes);
+        });
     }
 }
@@ -42,6 +42,22 @@ pub enum WCError {
         remaining: usize,
     },
 
+    /// Strict decoding encountered a token which is not in the vocabulary.
+    #[error("unknown token {token} at index {index}")]
+    UnknownToken {
+        /// The offending token id.
+        token: u64,
+        /// The index of the offending token in the input.
+        index: usize,
+    },
+
+    /// Strict decoding produced bytes which are not valid UTF-8.
+    #[error("invalid utf-8 at byte {position}")]
+    InvalidUtf8 {
+        /// The byte offset of the first invalid sequence.
+        position: usize,
+    },
+
     /// I/O error.
     #[cfg(feature = "std")]
     #[error(transparent)]
@@ -51,6 +67,10 @@ pub enum WCError {
     #[error("parse error: {0}")]
     Parse(String),
 
+    /// The configuration does not support the operation.
+    #[error("unsupported: {0}")]
+    Unsupported(String),
+
     /// Error from an external component.
     #[error("{0}")]
     External(String),
@@ -0,0 +1,6 @@
+//! Public `DeepSeek` Patterns.
+
+pub mod patterns;
+
+#[doc(inline)]
+pub use patterns::*;
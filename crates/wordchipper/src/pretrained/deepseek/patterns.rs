@@ -0,0 +1,69 @@
+//! # `DeepSeek` Patterns
+
+use crate::{join_patterns, support::regex::ConstRegexPattern};
+
+/// The "`deepseek_v3`" pretrained vocabulary word pattern.
+///
+/// `DeepSeek` V3 (and R1) pre-tokenize with a sequence of isolating splits:
+/// `\p{N}{1,3}`, then CJK runs, then a cl100k-like pattern over the rest.
+/// This folds the sequence into one pattern: CJK is excluded from the later
+/// classes, and whitespace before a digit or CJK run is kept whole (as the
+/// split leaves it at the end of a piece, where `\s+(?!\S)` matches it all).
+/// The split keeps the text its pattern leaves unmatched (e.g. unassigned or
+/// format chars, not before a letter); so the last branch matches it too.
+pub const DEEPSEEK_V3_PATTERN: ConstRegexPattern = ConstRegexPattern::Fancy(join_patterns!(
+    r"\p{N}{1,3}",
+    r"[一-龥぀-ゟ゠-ヿ]+",
+    r"[!-/:-@\[-`{-~][A-Za-z]+",
+    r"[^\r\n\p{L}\p{P}\p{S}]?[\p{L}\p{M}--一-龥぀-ゟ゠-ヿ]+",
+    r" ?[\p{P}\p{S}--一-龥぀-ゟ゠-ヿ]+[\r\n]*",
+    r"\s*[\r\n]+",
+    r"\s+(?![^\s\p{N}一-龥぀-ゟ゠-ヿ])",
+    r"\s+",
+    r"(?:[^\s\p{L}\p{M}\p{N}\p{P}\p{S}一-龥぀-ゟ゠-ヿ](?![\p{L}\p{M}--一-龥぀-ゟ゠-ヿ]))+",
+));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{vec, vec::Vec},
+        spanners::{SpanRef, TextSpanner, span_lexers::LexerTextSpanner},
+    };
+
+    fn words(text: &str) -> Vec<&str> {
+        let regex = DEEPSEEK_V3_PATTERN.compile().unwrap();
+        let spanner = LexerTextSpanner::new(crate::alloc::sync::Arc::new(regex), None);
+        spanner
+            .split_spans(text)
+            .into_iter()
+            .map(|s| match s {
+                SpanRef::Word(r) => &text[r],
+                s => panic!("unexpected {s:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_deepseek_v3_pattern() {
+        // Digits in runs of three.
+        assert_eq!(words("x 12345"), vec!["x", " ", "123", "45"]);
+        // Whitespace before an isolated run is not split.
+        assert_eq!(words("a  1"), vec!["a", "  ", "1"]);
+        assert_eq!(words("a  中文"), vec!["a", "  ", "中文"]);
+        // CJK is split from adjacent letters and punctuation.
+        assert_eq!(words("abc中文def"), vec!["abc", "中文", "def"]);
+        assert_eq!(words("!゠"), vec!["!", "゠"]);
+        // ASCII punctuation binds to following ASCII letters.
+        assert_eq!(words(" (abc"), vec![" (", "abc"]);
+        assert_eq!(words("(abc"), vec!["(abc"]);
+        assert_eq!(words("hello  world"), vec!["hello", " ", " world"]);
+        // Unmatched text is kept; up to a letter prefix.
+        assert_eq!(
+            words("a \u{200d}\u{e000}b"),
+            vec!["a", " ", "\u{200d}", "\u{e000}b"]
+        );
+        assert_eq!(words("\u{200d}中"), vec!["\u{200d}", "中"]);
+        assert_eq!(words("\u{200d}\u{3040}"), vec!["\u{200d}", "\u{3040}"]);
+    }
+}
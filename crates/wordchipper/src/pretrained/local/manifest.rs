@@ -0,0 +1,398 @@
+//! # Local Model Manifests
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    WCError,
+    WCResult,
+    alloc::format,
+    prelude::*,
+    spanners::TextSpanningConfig,
+    vocab::io::load_base64_unified_vocab_path,
+};
+
+/// The on-disk format of a local model vocabulary.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LocalVocabFormat {
+    /// A base64 "`.tiktoken`" vocabulary file.
+    ///
+    /// Manifest: `"format": "tiktoken"` (the default).
+    Tiktoken,
+
+    /// A `DataGym` "vocab.bpe" file, and its "encoder.json" file.
+    ///
+    /// Manifest: `"format": "datagym", "encoder_path": "..."`.
+    #[cfg(feature = "datagym")]
+    DataGym {
+        /// Path to the "encoder.json" file.
+        encoder_path: PathBuf,
+    },
+}
+
+/// A model described by a local manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalModelSpec {
+    /// The model name; `"name"` (required).
+    name: String,
+
+    /// A description of the model; `"description"` (optional).
+    description: String,
+
+    /// Path to the vocabulary file; `"path"` (required).
+    path: PathBuf,
+
+    /// The vocabulary file format; `"format"` (optional).
+    format: LocalVocabFormat,
+
+    /// The word split regex pattern; `"pattern"` (required).
+    pattern: String,
+
+    /// The special words; `"specials"` (optional), an object of `{ word: token }`.
+    specials: Vec<(String, usize)>,
+}
+
+impl LocalModelSpec {
+    /// Parse a spec from a manifest entry.
+    ///
+    /// ## Arguments
+    /// * `value` - The JSON manifest entry.
+    /// * `base_dir` - The directory relative paths are resolved against.
+    pub fn from_json(
+        value: &Value,
+        base_dir: &Path,
+    ) -> WCResult<Self> {
+        let obj = value
+            .as_object()
+            .ok_or_else(|| WCError::Parse("model entry is not an object".to_string()))?;
+
+        let get_str = |key: &str| -> WCResult<Option<String>> {
+            match obj.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::String(s)) => Ok(Some(s.clone())),
+                Some(_) => Err(WCError::Parse(format!("\"{key}\" is not a string"))),
+            }
+        };
+        let require_str = |key: &str| -> WCResult<String> {
+            get_str(key)?.ok_or_else(|| WCError::Parse(format!("missing \"{key}\"")))
+        };
+
+        let name = require_str("name")?;
+        let path = base_dir.join(require_str("path")?);
+        let pattern = require_str("pattern")?;
+        let description =
+            get_str("description")?.unwrap_or_else(|| format!("local model `{name}`"));
+
+        let format = match get_str("format")?.as_deref() {
+            None | Some("tiktoken") => LocalVocabFormat::Tiktoken,
+            #[cfg(feature = "datagym")]
+            Some("datagym") => LocalVocabFormat::DataGym {
+                encoder_path: base_dir.join(require_str("encoder_path")?),
+            },
+            Some(other) => {
+                return Err(WCError::Parse(format!(
+                    "model \"{name}\": unsupported format \"{other}\""
+                )));
+            }
+        };
+
+        let mut specials = Vec::new();
+        match obj.get("specials") {
+            None | Some(Value::Null) => (),
+            Some(Value::Object(map)) => {
+                for (word, token) in map {
+                    let token = token.as_u64().ok_or_else(|| {
+                        WCError::Parse(format!(
+                            "model \"{name}\": special \"{word}\" is not a token id"
+                        ))
+                    })?;
+                    specials.push((word.clone(), token as usize));
+                }
+            }
+            Some(_) => {
+                return Err(WCError::Parse(format!(
+                    "model \"{name}\": \"specials\" is not an object"
+                )));
+            }
+        }
+        specials.sort_by_key(|(_, t)| *t);
+
+        Ok(Self {
+            name,
+            description,
+            path,
+            format,
+            pattern,
+            specials,
+        })
+    }
+
+    /// Get the model name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the model description.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Get the vocabulary file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the vocabulary file format.
+    pub fn format(&self) -> &LocalVocabFormat {
+        &self.format
+    }
+
+    /// Get the word split regex pattern.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Get the special words, by token.
+    pub fn specials(&self) -> &[(String, usize)] {
+        &self.specials
+    }
+
+    /// Get the [`TextSpanningConfig`] for this model.
+    pub fn spanning_config<T: TokenType>(&self) -> WCResult<TextSpanningConfig<T>> {
+        let specials = self
+            .specials
+            .iter()
+            .map(|(word, t)| {
+                T::from_usize(*t)
+                    .map(|t| (word.clone(), t))
+                    .ok_or(WCError::TokenOutOfRange)
+            })
+            .collect::<WCResult<Vec<_>>>()?;
+
+        Ok(TextSpanningConfig::from_pattern(self.pattern.as_str()).with_special_words(specials))
+    }
+
+    /// Load the model vocabulary.
+    pub fn load_vocab<T: TokenType>(&self) -> WCResult<UnifiedTokenVocab<T>> {
+        match &self.format {
+            LocalVocabFormat::Tiktoken => {
+                load_base64_unified_vocab_path(&self.path, self.spanning_config()?)
+            }
+            #[cfg(feature = "datagym")]
+            LocalVocabFormat::DataGym { encoder_path } => {
+                use crate::vocab::{SpanMapVocab, io::read_datagym_vocab};
+
+                let mut vocab_reader = BufReader::new(File::open(&self.path)?);
+                let mut encoder_reader = BufReader::new(File::open(encoder_path)?);
+                let span_map = read_datagym_vocab(&mut vocab_reader, &mut encoder_reader, false)?;
+
+                UnifiedTokenVocab::from_span_vocab(
+                    self.spanning_config()?,
+                    SpanMapVocab::from_span_map(span_map).to_token_type()?,
+                )
+            }
+        }
+    }
+}
+
+/// Read a manifest.
+///
+/// ## Arguments
+/// * `reader` - The manifest JSON reader.
+/// * `base_dir` - The directory relative paths are resolved against.
+///
+/// ## Returns
+/// The model specs in manifest order.
+pub fn read_local_manifest(
+    reader: &mut dyn BufRead,
+    base_dir: &Path,
+) -> WCResult<Vec<LocalModelSpec>> {
+    let value: Value =
+        serde_json::from_reader(reader).map_err(|e| WCError::Parse(e.to_string()))?;
+
+    manifest_models(&value)
+        .ok_or_else(|| WCError::Parse("manifest has no \"models\" list".to_string()))?
+        .iter()
+        .map(|m| LocalModelSpec::from_json(m, base_dir))
+        .collect()
+}
+
+/// Get the `"models"` list of a manifest, if it has one.
+fn manifest_models(value: &Value) -> Option<&Vec<Value>> {
+    match value.get("models") {
+        Some(Value::Array(models)) => Some(models),
+        _ => None,
+    }
+}
+
+/// Load a manifest file.
+///
+/// Relative paths are resolved against the directory containing the manifest.
+pub fn load_local_manifest_path(path: impl AsRef<Path>) -> WCResult<Vec<LocalModelSpec>> {
+    let path = path.as_ref();
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let mut reader = BufReader::new(File::open(path)?);
+    read_local_manifest(&mut reader, base_dir)
+        .map_err(|e| WCError::Parse(format!("{}: {e}", path.display())))
+}
+
+/// Scan a manifest file, or a directory of manifest files.
+///
+/// In a directory, every `*.json` file with a top-level `"models"` list
+/// is read as a manifest (in file name order); other JSON files are skipped.
+///
+/// ## Errors
+/// On unreadable files, invalid JSON, or malformed manifests (each
+/// error names its file); and on duplicate model names.
+pub fn scan_local_models(path: impl AsRef<Path>) -> WCResult<Vec<LocalModelSpec>> {
+    let path = path.as_ref();
+    if !path.is_dir() {
+        return load_local_manifest_path(path);
+    }
+
+    let mut manifests: Vec<PathBuf> = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    manifests.retain(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "json"));
+    manifests.sort();
+
+    let mut specs: Vec<LocalModelSpec> = Vec::new();
+    for manifest in manifests {
+        let in_file = |e: &dyn core::fmt::Display| format!("{}: {e}", manifest.display());
+
+        let file = File::open(&manifest).map_err(|e| WCError::Parse(in_file(&e)))?;
+        let value: Value = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| WCError::Parse(in_file(&e)))?;
+        let Some(models) = manifest_models(&value) else {
+            continue;
+        };
+
+        for spec in models
+            .iter()
+            .map(|m| LocalModelSpec::from_json(m, path))
+            .collect::<WCResult<Vec<_>>>()
+            .map_err(|e| WCError::Parse(in_file(&e)))?
+        {
+            if specs.iter().any(|s| s.name == spec.name) {
+                return Err(WCError::DuplicatedResource(format!(
+                    "{}: duplicate local model \"{}\"",
+                    manifest.display(),
+                    spec.name
+                )));
+            }
+            specs.push(spec);
+        }
+    }
+    Ok(specs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_read_local_manifest() {
+        let manifest = r#"{
+            "models": [
+                {
+                    "name": "a",
+                    "path": "a.tiktoken",
+                    "pattern": "\\w+|\\s+",
+                    "specials": { "<|end|>": 301, "<|start|>": 300 }
+                },
+                {
+                    "name": "b",
+                    "description": "model b",
+                    "path": "/abs/b.tiktoken",
+                    "format": "tiktoken",
+                    "pattern": "\\w+"
+                }
+            ]
+        }"#;
+
+        let specs = read_local_manifest(&mut Cursor::new(manifest), Path::new("/models")).unwrap();
+        assert_eq!(specs.len(), 2);
+
+        assert_eq!(specs[0].name(), "a");
+        assert_eq!(specs[0].description(), "local model `a`");
+        assert_eq!(specs[0].path(), Path::new("/models/a.tiktoken"));
+        assert_eq!(specs[0].format(), &LocalVocabFormat::Tiktoken);
+        assert_eq!(specs[0].pattern(), "\\w+|\\s+");
+        assert_eq!(
+            specs[0].specials(),
+            &[("<|start|>".to_string(), 300), ("<|end|>".to_string(), 301)]
+        );
+
+        assert_eq!(specs[1].description, "model b");
+        assert_eq!(specs[1].path, PathBuf::from("/abs/b.tiktoken"));
+        assert!(specs[1].specials.is_empty());
+
+        let config = specs[0].spanning_config::<u32>().unwrap();
+        assert_eq!(config.pattern().as_str(), "\\w+|\\s+");
+        assert_eq!(config.specials().lookup_token(b"<|end|>"), Some(301));
+    }
+
+    #[test]
+    fn test_read_local_manifest_errors() {
+        for bad in [
+            r#"{}"#,
+            r#"{"models": [{"path": "a", "pattern": "a"}]}"#,
+            r#"{"models": [{"name": "a", "pattern": "a"}]}"#,
+            r#"{"models": [{"name": "a", "path": "a"}]}"#,
+            r#"{"models": [{"name": "a", "path": "a", "pattern": "a", "format": "xml"}]}"#,
+            r#"{"models": [{"name": "a", "path": "a", "pattern": "a", "specials": {"x": "y"}}]}"#,
+        ] {
+            assert!(
+                read_local_manifest(&mut Cursor::new(bad), Path::new(".")).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_scan_local_models() {
+        let dir = tempdir::TempDir::new("local_models").unwrap();
+        let model = |name: &str| {
+            format!(r#"{{"models": [{{"name": "{name}", "path": "x", "pattern": "x"}}]}}"#)
+        };
+        std::fs::write(dir.path().join("b.json"), model("b")).unwrap();
+        std::fs::write(dir.path().join("a.json"), model("a")).unwrap();
+        std::fs::write(dir.path().join("other.json"), r#"{"name": "other"}"#).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not json").unwrap();
+
+        let names: Vec<String> = scan_local_models(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["a".to_string(), "b".to_string()]);
+
+        std::fs::write(dir.path().join("c.json"), model("a")).unwrap();
+        assert!(matches!(
+            scan_local_models(dir.path()),
+            Err(WCError::DuplicatedResource(_))
+        ));
+
+        std::fs::write(dir.path().join("c.json"), "{ not json").unwrap();
+        assert!(matches!(
+            scan_local_models(dir.path()),
+            Err(WCError::Parse(msg)) if msg.contains("c.json")
+        ));
+
+        std::fs::write(dir.path().join("c.json"), r#"{"models": [{"name": "c"}]}"#).unwrap();
+        assert!(matches!(
+            scan_local_models(dir.path()),
+            Err(WCError::Parse(msg)) if msg.contains("c.json")
+        ));
+    }
+}
@@ -0,0 +1,37 @@
+//! Local-Directory and Manifest-Driven Models.
+//!
+//! The [`LocalVocabProvider`] registers models described by JSON manifests
+//! under the `local` provider id; so that `load_vocab("local::my_model", ...)`
+//! and [`list_models`](crate::list_models) see them.
+//!
+//! The default provider reads the path in the [`WORDCHIPPER_LOCAL_MODELS`]
+//! environment variable; which may name either a manifest file,
+//! or a directory of manifest files (every `*.json` file with a top-level `"models"` list).
+//!
+//! ## Manifest Format
+//!
+//! ```json
+//! {
+//!   "models": [
+//!     {
+//!       "name": "my_model",
+//!       "description": "Our internal tokenizer",
+//!       "path": "my_model.tiktoken",
+//!       "format": "tiktoken",
+//!       "pattern": "'s|'t| ?\\p{L}+| ?\\p{N}+| ?[^\\s\\p{L}\\p{N}]+|\\s+",
+//!       "specials": { "<|endoftext|>": 50256 }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Relative paths are resolved against the directory containing the manifest.
+//! See [`LocalModelSpec`] for the full list of fields.
+
+mod manifest;
+mod provider;
+
+#[doc(inline)]
+pub use manifest::*;
+#[doc(inline)]
+pub use provider::*;
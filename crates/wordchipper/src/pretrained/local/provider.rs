@@ -0,0 +1,236 @@
+//! # Local Model Provider
+
+use std::path::PathBuf;
+
+use crate::{
+    UnifiedTokenVocab,
+    WCError,
+    WCResult,
+    alloc::{format, sync::Arc, vec::Vec},
+    prelude::*,
+    pretrained::{
+        VocabDescription,
+        VocabProvider,
+        VocabProviderInventoryHook,
+        local::{LocalModelSpec, scan_local_models},
+    },
+    support::resources::ResourceLoader,
+};
+
+/// Environment variable naming the manifest file (or directory) of local models.
+pub const WORDCHIPPER_LOCAL_MODELS: &str = "WORDCHIPPER_LOCAL_MODELS";
+
+inventory::submit! {
+    VocabProviderInventoryHook::new(|| Arc::new(LocalVocabProvider::from_env()))
+}
+
+/// [`VocabProvider`] for models described by local manifests.
+///
+/// Manifests are re-scanned on every listing and load;
+/// so edits to the manifests are visible without a restart.
+///
+/// The default registered provider reads [`WORDCHIPPER_LOCAL_MODELS`];
+/// to use an explicit path instead, replace it in the global factory:
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+///
+/// use wordchipper::pretrained::{local::LocalVocabProvider, with_vocab_factory_mut};
+///
+/// with_vocab_factory_mut(&mut |factory| {
+///     factory.remove_provider("local");
+///     factory.register_provider(Arc::new(LocalVocabProvider::new("/models/manifest.json")))
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LocalVocabProvider {
+    path: Option<PathBuf>,
+}
+
+impl LocalVocabProvider {
+    /// Create a provider for a manifest file, or a directory of manifests.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Create a provider which reads its path from [`WORDCHIPPER_LOCAL_MODELS`].
+    pub fn from_env() -> Self {
+        Self::default()
+    }
+
+    /// Get the configured manifest path, if any.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path
+            .clone()
+            .or_else(|| std::env::var_os(WORDCHIPPER_LOCAL_MODELS).map(PathBuf::from))
+    }
+
+    /// Scan the configured manifests.
+    ///
+    /// ## Returns
+    /// The model specs; or an empty list if no path is configured.
+    pub fn list_specs(&self) -> WCResult<Vec<LocalModelSpec>> {
+        match self.path() {
+            Some(path) => scan_local_models(path),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Find a model spec by name.
+    pub fn resolve_spec(
+        &self,
+        name: &str,
+    ) -> WCResult<LocalModelSpec> {
+        self.list_specs()?
+            .into_iter()
+            .find(|spec| spec.name() == name)
+            .ok_or_else(|| WCError::ResourceNotFound(name.to_string()))
+    }
+}
+
+fn describe(spec: &LocalModelSpec) -> VocabDescription {
+    VocabDescription {
+        id: spec.name().to_string(),
+        context: vec!["local".to_string(), spec.name().to_string()],
+        description: spec.description().to_string(),
+    }
+}
+
+impl VocabProvider for LocalVocabProvider {
+    fn id(&self) -> String {
+        "local".to_string()
+    }
+
+    fn description(&self) -> String {
+        match self.path() {
+            Some(path) => format!("Local vocabularies from {}", path.display()),
+            None => format!("Local vocabularies (set ${WORDCHIPPER_LOCAL_MODELS})"),
+        }
+    }
+
+    fn list_vocabs(&self) -> Vec<VocabDescription> {
+        match self.list_specs() {
+            Ok(specs) => specs.iter().map(describe).collect(),
+            Err(e) => {
+                log::warn!("failed to scan local models: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    fn resolve_vocab(
+        &self,
+        name: &str,
+    ) -> WCResult<VocabDescription> {
+        self.resolve_spec(name).map(|spec| describe(&spec))
+    }
+
+    fn load_vocab(
+        &self,
+        name: &str,
+        _loader: &mut dyn ResourceLoader,
+    ) -> WCResult<(VocabDescription, Arc<UnifiedTokenVocab<u32>>)> {
+        let spec = self.resolve_spec(name)?;
+        let vocab = spec.load_vocab()?;
+        Ok((describe(&spec), vocab.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        TokenDecoder,
+        TokenEncoder,
+        TokenizerOptions,
+        pretrained::{VocabFactory, openai::OA_CL100K_BASE_PATTERN},
+        spanners::TextSpanningConfig,
+        vocab::{ByteMapVocab, io::save_base64_span_map_path, utility::testing::build_test_vocab},
+    };
+
+    struct NoLoader;
+
+    impl ResourceLoader for NoLoader {
+        fn load_resource_path(
+            &mut self,
+            resource: &crate::support::resources::KeyedResource,
+        ) -> WCResult<PathBuf> {
+            Err(WCError::ResourceNotFound(format!("{resource:?}")))
+        }
+    }
+
+    #[test]
+    fn test_local_provider() {
+        let dir = tempdir::TempDir::new("local_models").unwrap();
+
+        let vocab: UnifiedTokenVocab<u32> = build_test_vocab(
+            ByteMapVocab::default(),
+            TextSpanningConfig::from_pattern(OA_CL100K_BASE_PATTERN),
+        );
+        save_base64_span_map_path(
+            &vocab.span_vocab().to_span_map(),
+            dir.path().join("toy.tiktoken"),
+        )
+        .unwrap();
+
+        let manifest = serde_json::json!({
+            "models": [{
+                "name": "toy",
+                "description": "a toy model",
+                "path": "toy.tiktoken",
+                "pattern": OA_CL100K_BASE_PATTERN.as_str(),
+                "specials": { "<|end|>": 1000 },
+            }]
+        });
+        fs::write(dir.path().join("models.json"), manifest.to_string()).unwrap();
+        // Non-manifest JSON files are skipped in directory scans.
+        fs::write(dir.path().join("encoder.json"), "{\"a\": 1}").unwrap();
+
+        let provider = LocalVocabProvider::new(dir.path());
+        assert_eq!(provider.path(), Some(dir.path().to_path_buf()));
+
+        let listed = provider.list_vocabs();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, "toy");
+        assert_eq!(listed[0].description, "a toy model");
+
+        let mut factory = VocabFactory::default();
+        factory.register_provider(Arc::new(provider)).unwrap();
+
+        let (desc, loaded) = factory.load_vocab("local::toy", &mut NoLoader).unwrap();
+        assert_eq!(desc.id, "toy");
+        assert_eq!(loaded.special_vocab().lookup_token(b"<|end|>"), Some(1000));
+
+        let tokenizer = TokenizerOptions::default()
+            .with_parallel(false)
+            .build(loaded);
+        let tokens = tokenizer.try_encode("hello world<|end|>").unwrap();
+        assert_eq!(tokens.last(), Some(&1000));
+        assert_eq!(
+            tokenizer.try_decode_to_string(&tokens).unwrap().unwrap(),
+            "hello world<|end|>"
+        );
+
+        assert!(matches!(
+            factory.load_vocab("local::nope", &mut NoLoader),
+            Err(WCError::ResourceNotFound(_))
+        ));
+
+        // The manifest file may also be named directly.
+        let provider = LocalVocabProvider::new(dir.path().join("models.json"));
+        assert_eq!(provider.list_specs().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_local_provider_missing_path() {
+        let provider = LocalVocabProvider::new("/no/such/manifest.json");
+        assert!(provider.list_specs().is_err());
+        assert!(provider.list_vocabs().is_empty());
+        assert!(provider.resolve_vocab("toy").is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! # Meta `Llama 3` Vocabulary Loaders
+
+#[cfg(feature = "std")]
+use std::{io::BufRead, path::Path};
+
+#[cfg(feature = "std")]
+use crate::support::resources::ResourceLoader;
+#[allow(unused_imports)]
+use crate::{
+    TokenType,
+    UnifiedTokenVocab,
+    prelude::*,
+    pretrained::meta::{
+        META_LLAMA3_PATTERN,
+        resources::META_LLAMA3_KEYED_RESOURCE,
+        specials::{meta_llama3_1_special_tokens, meta_llama3_special_tokens},
+    },
+    spanners::TextSpanningConfig,
+    support::regex::RegexPattern,
+    vocab::utility::factories::ConstVocabularyFactory,
+};
+
+/// Meta `Llama 3` Tokenizer types.
+///
+/// All variants share the same `tokenizer.model` vocabulary;
+/// they differ in their named special tokens.
+#[derive(Clone, Copy, Debug, PartialEq, strum::EnumString, strum::EnumIter, strum::Display)]
+#[non_exhaustive]
+pub enum MetaTokenizer {
+    /// Llama 3 "`llama3`" tokenizer.
+    #[strum(serialize = "llama3")]
+    Llama3,
+
+    /// Llama 3.1, 3.2 and 3.3 "`llama3_1`" tokenizer.
+    #[strum(serialize = "llama3_1")]
+    Llama3_1,
+}
+
+impl MetaTokenizer {
+    /// Get the tokenizer vocabulary factory.
+    pub fn factory(&self) -> &ConstVocabularyFactory {
+        use MetaTokenizer::*;
+        match self {
+            Llama3 => &META_LLAMA3_VOCAB_FACTORY,
+            Llama3_1 => &META_LLAMA3_1_VOCAB_FACTORY,
+        }
+    }
+
+    /// Get the tokenizer regex pattern.
+    pub fn pattern(&self) -> RegexPattern {
+        self.factory().pattern()
+    }
+
+    /// Get the tokenizer special tokens.
+    pub fn special_tokens<T: TokenType>(&self) -> Vec<(String, T)> {
+        self.factory().special_tokens()
+    }
+
+    /// Get the tokenizer spanners config.
+    pub fn spanning_config<T: TokenType>(&self) -> TextSpanningConfig<T> {
+        self.factory().spanning_config()
+    }
+
+    /// Load the `Llama 3` tokenizer vocabulary through the loader.
+    ///
+    /// The public resource is gated; this only succeeds when the
+    /// loader already holds `meta/llama3/tokenizer.model`.
+    #[cfg(feature = "std")]
+    pub fn load_vocab<T: TokenType>(
+        &self,
+        loader: &mut dyn ResourceLoader,
+    ) -> crate::WCResult<crate::UnifiedTokenVocab<T>> {
+        self.factory().load_vocab(loader)
+    }
+
+    /// Load the `Llama 3` tokenizer vocabulary from a `tokenizer.model` file.
+    #[cfg(feature = "std")]
+    pub fn load_path<T: TokenType>(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> crate::WCResult<crate::UnifiedTokenVocab<T>> {
+        self.factory().load_vocab_path(path)
+    }
+
+    /// Read the `Llama 3` tokenizer vocabulary from a reader.
+    #[cfg(feature = "std")]
+    pub fn read_vocab<T: TokenType>(
+        &self,
+        reader: &mut dyn BufRead,
+    ) -> crate::WCResult<crate::UnifiedTokenVocab<T>> {
+        self.factory().read_vocab(reader)
+    }
+}
+
+/// The "`llama3`" tokenizer.
+pub const META_LLAMA3_VOCAB_FACTORY: ConstVocabularyFactory = ConstVocabularyFactory {
+    name: "llama3",
+    resource: META_LLAMA3_KEYED_RESOURCE,
+    pattern: META_LLAMA3_PATTERN,
+    special_builder: &meta_llama3_special_tokens,
+};
+
+/// The "`llama3_1`" tokenizer.
+pub const META_LLAMA3_1_VOCAB_FACTORY: ConstVocabularyFactory = ConstVocabularyFactory {
+    name: "llama3_1",
+    resource: META_LLAMA3_VOCAB_FACTORY.resource,
+    pattern: META_LLAMA3_VOCAB_FACTORY.pattern,
+    special_builder: &meta_llama3_1_special_tokens,
+};
+
+#[cfg(test)]
+mod test {
+    use core::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_meta_tokenizer() {
+        assert_eq!(MetaTokenizer::Llama3.to_string(), "llama3");
+        assert_eq!(MetaTokenizer::Llama3_1.to_string(), "llama3_1");
+
+        assert_eq!(
+            MetaTokenizer::from_str("llama3").unwrap(),
+            MetaTokenizer::Llama3
+        );
+        assert_eq!(
+            MetaTokenizer::from_str("llama3_1").unwrap(),
+            MetaTokenizer::Llama3_1
+        );
+
+        assert_eq!(MetaTokenizer::Llama3.special_tokens::<u32>().len(), 256);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_read_vocab() {
+        use crate::{
+            TokenEncoder,
+            TokenEncoderOptions,
+            alloc::sync::Arc,
+            support::resources::MemoryResourceLoader,
+            vocab::{SpanTokenMap, io::write_base64_span_map},
+        };
+
+        let mut span_map: SpanTokenMap<u32> = Default::default();
+        span_map.extend((0..=255u8).map(|b| (vec![b], b as u32)));
+        span_map.insert(b"hi".to_vec(), 256);
+
+        let mut buf: Vec<u8> = Vec::new();
+        write_base64_span_map(&span_map, &mut buf).unwrap();
+
+        let mut loader =
+            MemoryResourceLoader::new().with_resource("meta/llama3/tokenizer.model", buf);
+        let vocab: Arc<UnifiedTokenVocab<u32>> = MetaTokenizer::Llama3_1
+            .load_vocab(&mut loader)
+            .unwrap()
+            .into();
+
+        assert_eq!(
+            vocab.special_vocab().lookup_token("<|eot_id|>".as_bytes()),
+            Some(128009)
+        );
+
+        let encoder: Arc<dyn TokenEncoder<u32>> = TokenEncoderOptions::default().build(vocab);
+        assert_eq!(
+            encoder.try_encode("hi<|begin_of_text|>!").unwrap(),
+            vec![256, 128000, b'!' as u32]
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Public Meta `Llama 3` Patterns, Constants, and Models.
+//!
+//! The Llama 3 vocabulary is distributed with the (gated) model weights;
+//! so no public download is available. The `tokenizer.model` file must be
+//! supplied by the user, either with [`WORDCHIPPER_LLAMA3_TOKENIZER`],
+//! [`MetaTokenizer::load_path`], or by placing it where a
+//! [`ResourceLoader`](crate::support::resources::ResourceLoader) can find it
+//! (`meta/llama3/tokenizer.model`).
+
+pub mod factories;
+pub mod models;
+pub mod patterns;
+#[cfg(feature = "std")]
+mod provider;
+pub mod resources;
+pub mod spanning;
+pub mod specials;
+
+#[doc(inline)]
+pub use factories::*;
+#[doc(inline)]
+pub use patterns::*;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use provider::*;
+#[doc(inline)]
+pub use spanning::*;
@@ -0,0 +1,23 @@
+//! # Meta `Llama 3` Model Names
+//!
+//! Maps Llama model names to the [`MetaTokenizer`] they use.
+
+use crate::pretrained::meta::MetaTokenizer;
+
+/// Llama model name prefixes, and their tokenizers.
+///
+/// Covers both the short names and the Hugging Face repository ids.
+pub const META_MODEL_PREFIX_TOKENIZERS: &[(&str, MetaTokenizer)] = {
+    use MetaTokenizer::*;
+    &[
+        ("llama-3.1", Llama3_1),
+        ("llama-3.2", Llama3_1),
+        ("llama-3.3", Llama3_1),
+        ("llama-3", Llama3),
+        ("meta-llama/Llama-3.1-", Llama3_1),
+        ("meta-llama/Llama-3.2-", Llama3_1),
+        ("meta-llama/Llama-3.3-", Llama3_1),
+        ("meta-llama/Meta-Llama-3.1-", Llama3_1),
+        ("meta-llama/Meta-Llama-3-", Llama3),
+    ]
+};
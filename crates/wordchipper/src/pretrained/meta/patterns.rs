@@ -0,0 +1,16 @@
+//! # Meta `Llama 3` Patterns
+
+use crate::{join_patterns, support::regex::ConstRegexPattern};
+
+/// The "`llama3`" pretrained vocabulary word pattern.
+///
+/// Shared by Llama 3, 3.1, 3.2 and 3.3.
+pub const META_LLAMA3_PATTERN: ConstRegexPattern = ConstRegexPattern::Fancy(join_patterns!(
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)",
+    r"[^\r\n\p{L}\p{N}]?\p{L}+",
+    r"\p{N}{1,3}",
+    r" ?[^\s\p{L}\p{N}]+[\r\n]*",
+    r"\s*[\r\n]+",
+    r"\s+(?!\S)",
+    r"\s+",
+));
@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use crate::{
+    UnifiedTokenVocab,
+    WCError,
+    WCResult,
+    alloc::{format, sync::Arc, vec::Vec},
+    prelude::*,
+    pretrained::{
+        VocabAlias,
+        VocabDescription,
+        VocabProvider,
+        VocabProviderInventoryHook,
+        meta::{MetaTokenizer, models::META_MODEL_PREFIX_TOKENIZERS},
+    },
+    support::resources::{KeyedResource, ResourceLoader},
+};
+
+/// Environment variable naming a local Llama 3 `tokenizer.model` file.
+pub const WORDCHIPPER_LLAMA3_TOKENIZER: &str = "WORDCHIPPER_LLAMA3_TOKENIZER";
+
+inventory::submit! {
+    VocabProviderInventoryHook::new(|| Arc::new(MetaVocabProvider::from_env()))
+}
+
+/// [`VocabProvider`] for Meta `Llama 3` models.
+///
+/// The vocabulary is gated; it is read from a user-supplied `tokenizer.model`
+/// file when one is configured, and otherwise requested from the loader as
+/// `meta/llama3/tokenizer.model`.
+///
+/// The default registered provider reads [`WORDCHIPPER_LLAMA3_TOKENIZER`];
+/// to use an explicit path instead, replace it in the global factory:
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+///
+/// use wordchipper::pretrained::{meta::MetaVocabProvider, with_vocab_factory_mut};
+///
+/// with_vocab_factory_mut(&mut |factory| {
+///     factory.remove_provider("meta");
+///     factory.register_provider(Arc::new(MetaVocabProvider::new("/models/tokenizer.model")))
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MetaVocabProvider {
+    path: Option<PathBuf>,
+}
+
+impl MetaVocabProvider {
+    /// Create a provider reading a local `tokenizer.model` file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Create a provider which reads its path from [`WORDCHIPPER_LLAMA3_TOKENIZER`].
+    pub fn from_env() -> Self {
+        Self::default()
+    }
+
+    /// Get the configured `tokenizer.model` path, if any.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path
+            .clone()
+            .or_else(|| std::env::var_os(WORDCHIPPER_LLAMA3_TOKENIZER).map(PathBuf::from))
+    }
+}
+
+impl VocabProvider for MetaVocabProvider {
+    fn id(&self) -> String {
+        "meta".to_string()
+    }
+
+    fn description(&self) -> String {
+        match self.path() {
+            Some(path) => format!("Llama 3 vocabularies from {}", path.display()),
+            None => format!("Llama 3 vocabularies (set ${WORDCHIPPER_LLAMA3_TOKENIZER})"),
+        }
+    }
+
+    fn list_vocabs(&self) -> Vec<VocabDescription> {
+        vec![
+            VocabDescription {
+                id: "llama3".to_string(),
+                context: vec!["meta".to_string(), "llama3".to_string()],
+                description: "Llama 3 `llama3` vocabulary".to_string(),
+            },
+            VocabDescription {
+                id: "llama3_1".to_string(),
+                context: vec!["meta".to_string(), "llama3_1".to_string()],
+                description: "Llama 3.1 - 3.3 `llama3_1` vocabulary".to_string(),
+            },
+        ]
+    }
+
+    fn list_aliases(&self) -> Vec<VocabAlias> {
+        META_MODEL_PREFIX_TOKENIZERS
+            .iter()
+            .map(|(prefix, tokenizer)| VocabAlias::prefix(*prefix, tokenizer.to_string()))
+            .collect()
+    }
+
+    fn list_resources(
+        &self,
+        name: &str,
+    ) -> WCResult<Vec<KeyedResource>> {
+        use core::str::FromStr;
+
+        self.resolve_vocab(name)?;
+
+        match MetaTokenizer::from_str(name) {
+            Ok(tokenizer) => Ok(vec![tokenizer.factory().resource.clone().into()]),
+            Err(_) => Ok(Vec::new()),
+        }
+    }
+
+    fn load_vocab(
+        &self,
+        name: &str,
+        loader: &mut dyn ResourceLoader,
+    ) -> WCResult<(VocabDescription, Arc<UnifiedTokenVocab<u32>>)> {
+        use core::str::FromStr;
+
+        let descr = self.resolve_vocab(name)?;
+        let tokenizer = MetaTokenizer::from_str(name)
+            .map_err(|_| WCError::ResourceNotFound(name.to_string()))?;
+
+        let vocab = match self.path() {
+            Some(path) => tokenizer.load_path(path)?,
+            None => tokenizer.load_vocab(loader).map_err(|e| {
+                WCError::ResourceNotFound(format!(
+                    "{name}: the Llama 3 vocabulary is gated; set ${WORDCHIPPER_LLAMA3_TOKENIZER} to a local tokenizer.model ({e})"
+                ))
+            })?,
+        };
+
+        Ok((descr, vocab.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pretrained::meta::specials::META_LLAMA3_SPECIAL_BASE,
+        support::resources::MemoryResourceLoader,
+        vocab::{SpanTokenMap, io::save_base64_span_map_path},
+    };
+
+    #[test]
+    fn test_meta_provider_load_path() {
+        let dir = tempdir::TempDir::new("meta_provider").unwrap();
+        let path = dir.path().join("tokenizer.model");
+
+        let mut span_map: SpanTokenMap<u32> = Default::default();
+        span_map.extend((0..=255u8).map(|b| (vec![b], b as u32)));
+        save_base64_span_map_path(&span_map, &path).unwrap();
+
+        let provider = MetaVocabProvider::new(&path);
+        assert_eq!(provider.path(), Some(path.clone()));
+
+        let mut loader = MemoryResourceLoader::new();
+        let (descr, vocab) = provider.load_vocab("llama3", &mut loader).unwrap();
+        assert_eq!(descr.id, "llama3");
+        assert_eq!(
+            vocab
+                .special_vocab()
+                .lookup_token("<|begin_of_text|>".as_bytes()),
+            Some(META_LLAMA3_SPECIAL_BASE as u32)
+        );
+
+        assert!(provider.load_vocab("llama2", &mut loader).is_err());
+    }
+
+    #[test]
+    fn test_meta_provider_missing_vocab() {
+        let provider = MetaVocabProvider::default();
+        if provider.path().is_some() {
+            // The environment supplies a tokenizer.
+            return;
+        }
+
+        let mut loader = MemoryResourceLoader::new();
+        let err = match provider.load_vocab("llama3_1", &mut loader) {
+            Ok(_) => panic!("expected a missing vocabulary"),
+            Err(e) => e,
+        };
+        assert!(matches!(err, WCError::ResourceNotFound(_)));
+        assert!(err.to_string().contains(WORDCHIPPER_LLAMA3_TOKENIZER));
+    }
+
+    #[test]
+    fn test_meta_provider_aliases() {
+        let provider = MetaVocabProvider::default();
+        let aliases = provider.list_aliases();
+        assert!(aliases.iter().all(|a| a.prefix));
+        assert!(
+            aliases
+                .iter()
+                .any(|a| a.name == "meta-llama/Meta-Llama-3-" && a.vocab_id == "llama3")
+        );
+
+        assert_eq!(
+            crate::pretrained::resolve_alias("meta-llama/Llama-3.1-8B-Instruct").as_deref(),
+            Some("meta::llama3_1")
+        );
+
+        assert_eq!(
+            provider.list_resources("llama3_1").unwrap()[0].resource_id(),
+            Some("meta/llama3/tokenizer.model".to_string())
+        );
+    }
+}
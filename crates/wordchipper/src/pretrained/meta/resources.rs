@@ -0,0 +1,20 @@
+//! # Meta `Llama 3` Resources
+
+use crate::support::resources::{ConstKeyedResource, ConstUrlResource};
+
+/// The Llama 3 "`tokenizer.model`" vocabulary resource.
+///
+/// The url is gated behind the Llama license on Hugging Face;
+/// anonymous downloads fail, so the file is normally supplied locally.
+pub const META_LLAMA3_TIKTOKEN_RESOURCE: ConstUrlResource = ConstUrlResource {
+    urls: &[
+        "https://huggingface.co/meta-llama/Meta-Llama-3-8B/resolve/main/original/tokenizer.model",
+    ],
+    hash: None,
+};
+
+/// Cache-keyed Llama 3 "`tokenizer.model`" vocabulary resource.
+pub const META_LLAMA3_KEYED_RESOURCE: ConstKeyedResource = ConstKeyedResource {
+    key: &["meta", "llama3"],
+    resource: META_LLAMA3_TIKTOKEN_RESOURCE,
+};
@@ -0,0 +1,19 @@
+//! # Spanning Configuration for Meta `Llama 3` Tokenizers
+
+use crate::{
+    TokenType,
+    pretrained::meta::{META_LLAMA3_PATTERN, specials},
+    spanners::TextSpanningConfig,
+};
+
+/// Get the [`TextSpanningConfig`] for the "`llama3`" pretrained vocabulary.
+pub fn meta_llama3_spanning_config<T: TokenType>() -> TextSpanningConfig<T> {
+    TextSpanningConfig::<T>::from_pattern(META_LLAMA3_PATTERN)
+        .with_special_words(specials::meta_llama3_special_tokens())
+}
+
+/// Get the [`TextSpanningConfig`] for the "`llama3_1`" pretrained vocabulary.
+pub fn meta_llama3_1_spanning_config<T: TokenType>() -> TextSpanningConfig<T> {
+    TextSpanningConfig::<T>::from_pattern(META_LLAMA3_PATTERN)
+        .with_special_words(specials::meta_llama3_1_special_tokens())
+}
@@ -0,0 +1,122 @@
+//! Meta `Llama 3` Tokenizer Special Tokens.
+
+use crate::{
+    TokenType,
+    alloc::{string::String, vec::Vec},
+    declare_carrot_special,
+    vocab::utility::{ToTokenList, format_reserved_special_carrot},
+};
+
+declare_carrot_special!(
+    (BEGIN_OF_TEXT, "begin_of_text"),
+    (END_OF_TEXT, "end_of_text"),
+    (FINETUNE_RIGHT_PAD_ID, "finetune_right_pad_id"),
+    (START_HEADER_ID, "start_header_id"),
+    (END_HEADER_ID, "end_header_id"),
+    (EOM_ID, "eom_id"),
+    (EOT_ID, "eot_id"),
+    (PYTHON_TAG, "python_tag"),
+);
+
+/// The first special token id of the Llama 3 vocabulary.
+pub const META_LLAMA3_SPECIAL_BASE: usize = 128000;
+
+/// The number of special tokens in the Llama 3 vocabulary.
+pub const META_LLAMA3_SPECIAL_COUNT: usize = 256;
+
+/// The "llama3" named special tokens; excluding reserved tokens.
+pub const META_LLAMA3_NAMED_SPECIAL_TOKENS: &[(&str, usize)] = &[
+    (BEGIN_OF_TEXT, 128000),
+    (END_OF_TEXT, 128001),
+    (START_HEADER_ID, 128006),
+    (END_HEADER_ID, 128007),
+    (EOT_ID, 128009),
+];
+
+/// The "llama3.1" named special tokens; excluding reserved tokens.
+///
+/// Also used by Llama 3.2 and 3.3.
+pub const META_LLAMA3_1_NAMED_SPECIAL_TOKENS: &[(&str, usize)] = &[
+    (BEGIN_OF_TEXT, 128000),
+    (END_OF_TEXT, 128001),
+    (FINETUNE_RIGHT_PAD_ID, 128004),
+    (START_HEADER_ID, 128006),
+    (END_HEADER_ID, 128007),
+    (EOM_ID, 128008),
+    (EOT_ID, 128009),
+    (PYTHON_TAG, 128010),
+];
+
+/// Fill the unnamed ids of the special range with reserved tokens.
+///
+/// Reserved tokens are numbered in id order, skipping the named ids.
+fn with_reserved_tokens<T: TokenType>(named: &'static [(&'static str, usize)]) -> Vec<(String, T)> {
+    let mut specials = named.to_token_list::<T>();
+    specials.reserve(META_LLAMA3_SPECIAL_COUNT - named.len());
+
+    let end = META_LLAMA3_SPECIAL_BASE + META_LLAMA3_SPECIAL_COUNT;
+    let reserved = (META_LLAMA3_SPECIAL_BASE..end).filter(|id| !named.iter().any(|(_, t)| t == id));
+    for (idx, id) in reserved.enumerate() {
+        specials.push((
+            format_reserved_special_carrot(idx),
+            T::from_usize(id).unwrap(),
+        ));
+    }
+
+    specials
+}
+
+/// The "llama3" special tokens.
+///
+/// Generated due to the large number of reserved tokens.
+pub fn meta_llama3_special_tokens<T: TokenType>() -> Vec<(String, T)> {
+    with_reserved_tokens(META_LLAMA3_NAMED_SPECIAL_TOKENS)
+}
+
+/// The "llama3.1" special tokens.
+///
+/// Generated due to the large number of reserved tokens.
+pub fn meta_llama3_1_special_tokens<T: TokenType>() -> Vec<(String, T)> {
+    with_reserved_tokens(META_LLAMA3_1_NAMED_SPECIAL_TOKENS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alloc::string::ToString;
+
+    fn lookup(
+        specials: &[(String, usize)],
+        id: usize,
+    ) -> &str {
+        specials.iter().find(|(_, t)| *t == id).unwrap().0.as_str()
+    }
+
+    #[test]
+    fn test_meta_llama3_specials() {
+        let specials = meta_llama3_special_tokens::<usize>();
+        assert_eq!(specials.len(), META_LLAMA3_SPECIAL_COUNT);
+
+        assert_eq!(specials[0], ("<|begin_of_text|>".to_string(), 128000));
+        assert_eq!(lookup(&specials, 128002), "<|reserved_special_token_0|>");
+        assert_eq!(lookup(&specials, 128005), "<|reserved_special_token_3|>");
+        assert_eq!(lookup(&specials, 128008), "<|reserved_special_token_4|>");
+        assert_eq!(lookup(&specials, 128009), "<|eot_id|>");
+        assert_eq!(lookup(&specials, 128010), "<|reserved_special_token_5|>");
+        assert_eq!(lookup(&specials, 128255), "<|reserved_special_token_250|>");
+    }
+
+    #[test]
+    fn test_meta_llama3_1_specials() {
+        let specials = meta_llama3_1_special_tokens::<usize>();
+        assert_eq!(specials.len(), META_LLAMA3_SPECIAL_COUNT);
+
+        assert_eq!(lookup(&specials, 128002), "<|reserved_special_token_0|>");
+        assert_eq!(lookup(&specials, 128004), "<|finetune_right_pad_id|>");
+        assert_eq!(lookup(&specials, 128005), "<|reserved_special_token_2|>");
+        assert_eq!(lookup(&specials, 128008), "<|eom_id|>");
+        assert_eq!(lookup(&specials, 128010), "<|python_tag|>");
+        assert_eq!(lookup(&specials, 128011), "<|reserved_special_token_3|>");
+        assert_eq!(lookup(&specials, 128255), "<|reserved_special_token_247|>");
+    }
+}
@@ -31,8 +31,23 @@
 //!     Ok(tokenizer)
 //! }
 //! ```
+//!
+//! ## Llama 3
+//!
+//! The `meta::` provider covers the Llama 3 family; its vocabulary is gated,
+//! so the `tokenizer.model` file is supplied locally; see `pretrained::meta`.
+//!
+//! ## Local Models
+//!
+//! With the `local` feature, models described by JSON manifests
+//! are listed and loaded under the `local::` prefix; see `pretrained::local`.
 
+pub mod deepseek;
+#[cfg(feature = "local")]
+pub mod local;
+pub mod meta;
 pub mod openai;
+pub mod qwen;
 mod vocab_factory;
 
 #[doc(inline)]
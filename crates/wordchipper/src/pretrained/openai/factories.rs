@@ -40,8 +40,6 @@ use crate::{
 pub fn load_gpt2_vocab<T: TokenType>(
     loader: &mut dyn ResourceLoader
 ) -> crate::WCResult<crate::UnifiedTokenVocab<T>> {
-    use std::io::BufReader;
-
     use crate::{
         pretrained::openai::{
             oa_r50k_base_spanning_config,
@@ -50,11 +48,8 @@ pub fn load_gpt2_vocab<T: TokenType>(
         vocab::{SpanMapVocab, io::read_datagym_vocab},
     };
 
-    let vocab_path = loader.load_resource_path(&OA_GPT2_VOCAB_BPE_KEYED_RESOURCE.into())?;
-    let mut vocab_reader = BufReader::new(std::fs::File::open(vocab_path)?);
-
-    let encoder_path = loader.load_resource_path(&OA_GPT2_ENCODER_JSON_KEYED_RESOURCE.into())?;
-    let mut encoder_reader = BufReader::new(std::fs::File::open(encoder_path)?);
+    let mut vocab_reader = loader.open_resource(&OA_GPT2_VOCAB_BPE_KEYED_RESOURCE.into())?;
+    let mut encoder_reader = loader.open_resource(&OA_GPT2_ENCODER_JSON_KEYED_RESOURCE.into())?;
 
     let span_map = read_datagym_vocab(&mut vocab_reader, &mut encoder_reader, false)?;
 
@@ -1,6 +1,7 @@
 //! Public `OpenAI` Patterns, Constants, and Models.
 
 pub mod factories;
+pub mod models;
 pub mod patterns;
 mod provider;
 pub mod resources;
@@ -0,0 +1,149 @@
+//! # `OpenAI` Model Names
+//!
+//! Maps `OpenAI` model names to the [`OATokenizer`] they use;
+//! mirroring `tiktoken`'s `encoding_for_model`.
+
+use crate::pretrained::openai::OATokenizer;
+
+/// Exact `OpenAI` model names, and their tokenizers.
+pub const OA_MODEL_TOKENIZERS: &[(&str, OATokenizer)] = {
+    use OATokenizer::*;
+    &[
+        // reasoning
+        ("o1", O200kBase),
+        ("o3", O200kBase),
+        ("o4-mini", O200kBase),
+        // chat
+        ("gpt-5", O200kBase),
+        ("gpt-4.1", O200kBase),
+        ("gpt-4o", O200kBase),
+        ("gpt-4", Cl100kBase),
+        ("gpt-3.5-turbo", Cl100kBase),
+        ("gpt-3.5", Cl100kBase),
+        ("gpt-35-turbo", Cl100kBase),
+        // base
+        ("davinci-002", Cl100kBase),
+        ("babbage-002", Cl100kBase),
+        // embeddings
+        ("text-embedding-ada-002", Cl100kBase),
+        ("text-embedding-3-small", Cl100kBase),
+        ("text-embedding-3-large", Cl100kBase),
+        // DEPRECATED MODELS
+        // text (DEPRECATED)
+        ("text-davinci-003", P50kBase),
+        ("text-davinci-002", P50kBase),
+        ("text-davinci-001", R50kBase),
+        ("text-curie-001", R50kBase),
+        ("text-babbage-001", R50kBase),
+        ("text-ada-001", R50kBase),
+        ("davinci", R50kBase),
+        ("curie", R50kBase),
+        ("babbage", R50kBase),
+        ("ada", R50kBase),
+        // code (DEPRECATED)
+        ("code-davinci-002", P50kBase),
+        ("code-davinci-001", P50kBase),
+        ("code-cushman-002", P50kBase),
+        ("code-cushman-001", P50kBase),
+        ("davinci-codex", P50kBase),
+        ("cushman-codex", P50kBase),
+        // edit (DEPRECATED)
+        ("text-davinci-edit-001", P50kEdit),
+        ("code-davinci-edit-001", P50kEdit),
+        // old embeddings (DEPRECATED)
+        ("text-similarity-davinci-001", R50kBase),
+        ("text-similarity-curie-001", R50kBase),
+        ("text-similarity-babbage-001", R50kBase),
+        ("text-similarity-ada-001", R50kBase),
+        ("text-search-davinci-doc-001", R50kBase),
+        ("text-search-curie-doc-001", R50kBase),
+        ("text-search-babbage-doc-001", R50kBase),
+        ("text-search-ada-doc-001", R50kBase),
+        ("code-search-babbage-code-001", R50kBase),
+        ("code-search-ada-code-001", R50kBase),
+        // open source
+        ("gpt-2", R50kBase),
+    ]
+};
+
+/// `OpenAI` model name prefixes, and their tokenizers.
+///
+/// Longer prefixes take precedence over shorter ones.
+pub const OA_MODEL_PREFIX_TOKENIZERS: &[(&str, OATokenizer)] = {
+    use OATokenizer::*;
+    &[
+        ("o1-", O200kBase),
+        ("o3-", O200kBase),
+        ("o4-mini-", O200kBase),
+        // chat
+        ("gpt-5-", O200kBase),
+        ("gpt-4.5-", O200kBase),
+        ("gpt-4.1-", O200kBase),
+        ("chatgpt-4o-", O200kBase),
+        ("gpt-4o-", O200kBase),
+        ("gpt-4-", Cl100kBase),
+        ("gpt-3.5-turbo-", Cl100kBase),
+        ("gpt-35-turbo-", Cl100kBase),
+        ("gpt-oss-", O200kHarmony),
+        // fine-tuned
+        ("ft:gpt-4o", O200kBase),
+        ("ft:gpt-4", Cl100kBase),
+        ("ft:gpt-3.5-turbo", Cl100kBase),
+        ("ft:davinci-002", Cl100kBase),
+        ("ft:babbage-002", Cl100kBase),
+    ]
+};
+
+impl OATokenizer {
+    /// Find the tokenizer used by an `OpenAI` model.
+    ///
+    /// Exact model names take precedence over name prefixes;
+    /// see [`OA_MODEL_TOKENIZERS`] and [`OA_MODEL_PREFIX_TOKENIZERS`].
+    ///
+    /// ## Arguments
+    /// * `model` - The model name; e.g. `"gpt-4o"` or `"gpt-4-0613"`.
+    ///
+    /// ## Returns
+    /// The tokenizer, if the model is known.
+    pub fn for_model(model: &str) -> Option<Self> {
+        if let Some((_, tokenizer)) = OA_MODEL_TOKENIZERS.iter().find(|(name, _)| *name == model) {
+            return Some(*tokenizer);
+        }
+
+        OA_MODEL_PREFIX_TOKENIZERS
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, tokenizer)| *tokenizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_model() {
+        use OATokenizer::*;
+
+        assert_eq!(OATokenizer::for_model("gpt-4o"), Some(O200kBase));
+        assert_eq!(OATokenizer::for_model("gpt-4o-2024-05-13"), Some(O200kBase));
+        assert_eq!(OATokenizer::for_model("gpt-4"), Some(Cl100kBase));
+        assert_eq!(OATokenizer::for_model("gpt-4-0613"), Some(Cl100kBase));
+        assert_eq!(OATokenizer::for_model("gpt-3.5-turbo"), Some(Cl100kBase));
+        assert_eq!(OATokenizer::for_model("text-davinci-003"), Some(P50kBase));
+        assert_eq!(
+            OATokenizer::for_model("text-davinci-edit-001"),
+            Some(P50kEdit)
+        );
+        assert_eq!(OATokenizer::for_model("davinci"), Some(R50kBase));
+        assert_eq!(OATokenizer::for_model("gpt-oss-120b"), Some(O200kHarmony));
+
+        // The longest prefix wins.
+        assert_eq!(OATokenizer::for_model("ft:gpt-4o:org::id"), Some(O200kBase));
+        assert_eq!(OATokenizer::for_model("ft:gpt-4:org::id"), Some(Cl100kBase));
+
+        assert_eq!(OATokenizer::for_model("gpt"), None);
+        assert_eq!(OATokenizer::for_model("llama-3"), None);
+    }
+}
@@ -4,8 +4,14 @@ use crate::{
     WCResult,
     alloc::{sync::Arc, vec::Vec},
     prelude::*,
-    pretrained::{VocabDescription, VocabProvider, VocabProviderInventoryHook},
-    support::resources::ResourceLoader,
+    pretrained::{
+        VocabAlias,
+        VocabDescription,
+        VocabProvider,
+        VocabProviderInventoryHook,
+        openai::models::{OA_MODEL_PREFIX_TOKENIZERS, OA_MODEL_TOKENIZERS},
+    },
+    support::resources::{KeyedResource, ResourceLoader},
 };
 
 inventory::submit! {
@@ -72,6 +78,56 @@ impl VocabProvider for OpenaiVocabProvider {
         vs
     }
 
+    fn list_aliases(&self) -> Vec<VocabAlias> {
+        let exact = OA_MODEL_TOKENIZERS
+            .iter()
+            .map(|(name, tokenizer)| VocabAlias::exact(*name, tokenizer.to_string()));
+        let prefix = OA_MODEL_PREFIX_TOKENIZERS
+            .iter()
+            .map(|(prefix, tokenizer)| VocabAlias::prefix(*prefix, tokenizer.to_string()));
+
+        exact.chain(prefix).collect()
+    }
+
+    fn is_downloadable(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.resolve_vocab(name).is_ok()
+    }
+
+    fn list_resources(
+        &self,
+        name: &str,
+    ) -> WCResult<Vec<KeyedResource>> {
+        self.resolve_vocab(name)?;
+
+        #[cfg(feature = "datagym")]
+        if name == "gpt2" {
+            use crate::pretrained::openai::resources::{
+                OA_GPT2_ENCODER_JSON_KEYED_RESOURCE,
+                OA_GPT2_VOCAB_BPE_KEYED_RESOURCE,
+            };
+
+            return Ok(vec![
+                OA_GPT2_VOCAB_BPE_KEYED_RESOURCE.into(),
+                OA_GPT2_ENCODER_JSON_KEYED_RESOURCE.into(),
+            ]);
+        }
+
+        #[cfg(feature = "std")]
+        {
+            use core::str::FromStr;
+
+            use crate::pretrained::openai::OATokenizer;
+            if let Ok(oat) = OATokenizer::from_str(name) {
+                return Ok(vec![oat.factory().resource.clone().into()]);
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
     fn load_vocab(
         &self,
         name: &str,
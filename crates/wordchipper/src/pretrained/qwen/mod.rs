@@ -0,0 +1,6 @@
+//! Public Alibaba `Qwen` Patterns.
+
+pub mod patterns;
+
+#[doc(inline)]
+pub use patterns::*;
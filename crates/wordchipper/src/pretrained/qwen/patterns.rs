@@ -0,0 +1,17 @@
+//! # Alibaba `Qwen` Patterns
+
+use crate::{join_patterns, support::regex::ConstRegexPattern};
+
+/// The "`qwen2`" pretrained vocabulary word pattern.
+///
+/// The `cl100k_base` pattern, with numbers split into single digits.
+/// Shared by Qwen2, Qwen2.5 and Qwen3.
+pub const QWEN2_PATTERN: ConstRegexPattern = ConstRegexPattern::Fancy(join_patterns!(
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)",
+    r"[^\r\n\p{L}\p{N}]?\p{L}+",
+    r"\p{N}",
+    r" ?[^\s\p{L}\p{N}]+[\r\n]*",
+    r"\s*[\r\n]+",
+    r"\s+(?!\S)",
+    r"\s+",
+));
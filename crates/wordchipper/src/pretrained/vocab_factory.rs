@@ -9,7 +9,7 @@ use crate::{
     WCResult,
     alloc::{format, string::String, sync::Arc},
     prelude::*,
-    support::resources::ResourceLoader,
+    support::resources::{KeyedResource, ResourceLoader},
 };
 
 /// Global vocabulary factory.
@@ -87,6 +87,109 @@ pub fn load_vocab(
     with_vocab_factory(&mut move |f: &VocabFactory| f.load_vocab(name, loader))
 }
 
+/// Resolve a model-name alias to a fully qualified vocabulary name.
+///
+/// See [`VocabFactory::resolve_alias`].
+pub fn resolve_alias(name: &str) -> Option<String> {
+    with_vocab_factory(&mut |f: &VocabFactory| f.resolve_alias(name))
+}
+
+/// List the resources a vocabulary is loaded from, by name.
+///
+/// See [`VocabFactory::list_resources`].
+pub fn list_vocab_resources(name: &str) -> WCResult<Vec<KeyedResource>> {
+    with_vocab_factory(&mut |f: &VocabFactory| f.list_resources(name))
+}
+
+/// Is the vocabulary publicly downloadable, by name?
+///
+/// See [`VocabFactory::is_downloadable`].
+pub fn is_downloadable(name: &str) -> bool {
+    with_vocab_factory(&mut |f: &VocabFactory| f.is_downloadable(name))
+}
+
+/// List the publicly downloadable models.
+///
+/// The subset of [`list_models`] which [`is_downloadable`]; gated and
+/// local-only models are skipped.
+pub fn list_downloadable_models() -> Vec<String> {
+    list_models()
+        .into_iter()
+        .filter(|name| is_downloadable(name))
+        .collect()
+}
+
+/// List the resources of every registered model.
+///
+/// Resources shared between models are listed once.
+pub fn list_known_resources() -> Vec<KeyedResource> {
+    let mut res: Vec<KeyedResource> = Vec::new();
+    for name in list_models() {
+        for resource in list_vocab_resources(&name).unwrap_or_default() {
+            if !res.contains(&resource) {
+                res.push(resource);
+            }
+        }
+    }
+    res
+}
+
+/// Fetch the resources of a list of vocabularies ahead of time.
+///
+/// Each resource is opened through the `loader`; for a download cache,
+/// this downloads (and verifies) anything which is missing.
+///
+/// ## Arguments
+/// * `names` - the vocabulary or model names to prefetch.
+/// * `loader` - the loader to fetch through.
+///
+/// ## Returns
+/// The resources fetched, in order.
+#[cfg(feature = "std")]
+pub fn prefetch_vocabs<S: AsRef<str>>(
+    names: &[S],
+    loader: &mut dyn ResourceLoader,
+) -> WCResult<Vec<KeyedResource>> {
+    let mut res = Vec::new();
+    for name in names {
+        for resource in list_vocab_resources(name.as_ref())? {
+            loader.open_resource(&resource)?;
+            res.push(resource);
+        }
+    }
+    Ok(res)
+}
+
+/// Verify the files in a disk cache against the known resource hashes.
+///
+/// Files which do not belong to a known resource, or whose resource
+/// has no hash, are reported as [`CacheEntryStatus::Unverified`](crate::disk_cache::CacheEntryStatus::Unverified).
+///
+/// ## Returns
+/// Every cache entry, with its status.
+#[cfg(feature = "download")]
+pub fn verify_disk_cache(
+    cache: &crate::disk_cache::WordchipperDiskCache
+) -> WCResult<
+    Vec<(
+        crate::disk_cache::CacheEntry,
+        crate::disk_cache::CacheEntryStatus,
+    )>,
+> {
+    let hashes: crate::types::WCHashMap<String, String> = list_known_resources()
+        .into_iter()
+        .filter_map(|r| Some((r.resource_id()?, r.resource.hash?)))
+        .collect();
+
+    let mut res = Vec::new();
+    for entry in cache.list_entries()? {
+        let hash = hashes.get(&entry.resource_id()).map(|h| h.as_str());
+        let status = cache.verify_entry(&entry, hash)?;
+        res.push((entry, status));
+    }
+    Ok(res)
+}
+
 /// List the available pretrained models.
 ///
 /// ## Arguments
@@ -129,6 +232,60 @@ pub struct VocabListing {
     pub vocabs: Vec<VocabDescription>,
 }
 
+/// A model-name alias for a vocabulary.
+///
+/// Aliases map the names services are configured by (`"gpt-4o"`, `"gpt-4-0613"`)
+/// to the id of the vocabulary they use (`"o200k_base"`, `"cl100k_base"`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VocabAlias {
+    /// The model name, or model name prefix.
+    pub name: String,
+
+    /// Does `name` match as a prefix?
+    pub prefix: bool,
+
+    /// The id of the vocabulary, within the providing [`VocabProvider`].
+    pub vocab_id: String,
+}
+
+impl VocabAlias {
+    /// An alias matching exactly `name`.
+    pub fn exact(
+        name: impl Into<String>,
+        vocab_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            prefix: false,
+            vocab_id: vocab_id.into(),
+        }
+    }
+
+    /// An alias matching every name starting with `prefix`.
+    pub fn prefix(
+        prefix: impl Into<String>,
+        vocab_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: prefix.into(),
+            prefix: true,
+            vocab_id: vocab_id.into(),
+        }
+    }
+
+    /// Does this alias match the model name?
+    pub fn matches(
+        &self,
+        name: &str,
+    ) -> bool {
+        if self.prefix {
+            name.starts_with(self.name.as_str())
+        } else {
+            name == self.name
+        }
+    }
+}
+
 /// A factory for searching for and loading.
 pub trait VocabProvider: Sync + Send {
     /// The name of the factory.
@@ -140,6 +297,42 @@ pub trait VocabProvider: Sync + Send {
     /// Get a listing of known vocabularies.
     fn list_vocabs(&self) -> Vec<VocabDescription>;
 
+    /// Get the model-name aliases for this provider's vocabularies.
+    ///
+    /// Aliases are only consulted when a name does not resolve directly.
+    fn list_aliases(&self) -> Vec<VocabAlias> {
+        Vec::new()
+    }
+
+    /// List the resources a vocabulary is loaded from.
+    ///
+    /// Used to prefetch and verify downloads; the default lists none,
+    /// for providers which do not load through a [`ResourceLoader`].
+    ///
+    /// ## Returns
+    /// * `Ok(resources)` - on success.
+    /// * `Err(WCError::ResourceNotFound)` - if the vocabulary is not found.
+    fn list_resources(
+        &self,
+        name: &str,
+    ) -> WCResult<Vec<KeyedResource>> {
+        self.resolve_vocab(name)?;
+        Ok(Vec::new())
+    }
+
+    /// Can the vocabulary's resources be downloaded without credentials?
+    ///
+    /// Prefetching all models only fetches these. The default is `false`;
+    /// providers with public downloads override it. Gated and local-only
+    /// vocabularies are not downloadable.
+    fn is_downloadable(
+        &self,
+        name: &str,
+    ) -> bool {
+        let _ = name;
+        false
+    }
+
     /// Resolve a vocabulary description.
     ///
     /// ## Returns
@@ -242,42 +435,145 @@ impl VocabFactory {
         res
     }
 
-    /// Resolve a [`VocabDescription`] by name.
+    /// Resolve a model-name alias to a fully qualified vocabulary name.
+    ///
+    /// Names may be scoped to a provider (`"openai::gpt-4o"`).
+    /// Exact aliases take precedence over prefix aliases;
+    /// and longer prefixes take precedence over shorter ones.
     ///
     /// ## Returns
-    /// * `Ok(description)` - on success.
-    /// * `Err(WCError::ResourceNotFound)` - if the vocabulary is not found.
-    /// * `Err(e)` - on any other error.
-    pub fn resolve_vocab(
+    /// `Some("{provider}::{vocab_id}")` if an alias matches.
+    pub fn resolve_alias(
         &self,
         name: &str,
-    ) -> WCResult<VocabDescription> {
-        if name.contains("::") {
+    ) -> Option<String> {
+        self.find_alias(name)
+            .map(|(provider, vocab_id)| format!("{}::{vocab_id}", provider.id()))
+    }
+
+    fn find_alias(
+        &self,
+        name: &str,
+    ) -> Option<(&Arc<dyn VocabProvider>, String)> {
+        let (providers, name): (Vec<&Arc<dyn VocabProvider>>, &str) = match name.split_once("::") {
+            Some((provider_name, name)) => (
+                self.find_provider(provider_name).into_iter().collect(),
+                name,
+            ),
+            None => (self.providers.iter().collect(), name),
+        };
+
+        let mut best: Option<(&Arc<dyn VocabProvider>, VocabAlias)> = None;
+        for provider in providers {
+            for alias in provider.list_aliases() {
+                if !alias.matches(name) {
+                    continue;
+                }
+                if !alias.prefix {
+                    return Some((provider, alias.vocab_id));
+                }
+                if best
+                    .as_ref()
+                    .is_none_or(|(_, b)| alias.name.len() > b.name.len())
+                {
+                    best = Some((provider, alias));
+                }
+            }
+        }
+        best.map(|(provider, alias)| (provider, alias.vocab_id))
+    }
+
+    /// Dispatch a name to its provider; falling back to aliases when not found.
+    fn dispatch<V>(
+        &self,
+        name: &str,
+        f: &mut dyn FnMut(&dyn VocabProvider, &str) -> WCResult<V>,
+    ) -> WCResult<V> {
+        let not_found = || Err(WCError::ResourceNotFound(name.to_string()));
+
+        let direct = if name.contains("::") {
             let (provider_name, vocab_name) = name.split_once("::").unwrap();
 
-            if let Some(provider) = self.find_provider(provider_name) {
-                return match provider.resolve_vocab(vocab_name) {
-                    Ok(vocab) => Ok(vocab),
-                    Err(WCError::ResourceNotFound(_)) => {
-                        Err(WCError::ResourceNotFound(name.to_string()))
-                    }
-                    Err(err) => Err(err),
-                };
+            match self.find_provider(provider_name) {
+                Some(provider) => f(provider.as_ref(), vocab_name),
+                None => not_found(),
             }
         } else {
+            let mut res = not_found();
             for provider in &self.providers {
-                match provider.resolve_vocab(name) {
-                    Ok(vocab) => return Ok(vocab),
+                match f(provider.as_ref(), name) {
                     Err(WCError::ResourceNotFound(_)) => (),
-                    Err(err) => return Err(err),
+                    other => {
+                        res = other;
+                        break;
+                    }
                 }
             }
+            res
+        };
+
+        match direct {
+            Err(WCError::ResourceNotFound(_)) => match self.find_alias(name) {
+                Some((provider, vocab_id)) => match f(provider.as_ref(), &vocab_id) {
+                    Err(WCError::ResourceNotFound(_)) => not_found(),
+                    other => other,
+                },
+                None => not_found(),
+            },
+            other => other,
         }
-        Err(WCError::ResourceNotFound(name.to_string()))
+    }
+
+    /// Resolve a [`VocabDescription`] by name.
+    ///
+    /// Names which do not resolve directly are looked up as model-name aliases;
+    /// see [`resolve_alias`](Self::resolve_alias).
+    ///
+    /// ## Returns
+    /// * `Ok(description)` - on success.
+    /// * `Err(WCError::ResourceNotFound)` - if the vocabulary is not found.
+    /// * `Err(e)` - on any other error.
+    pub fn resolve_vocab(
+        &self,
+        name: &str,
+    ) -> WCResult<VocabDescription> {
+        self.dispatch(name, &mut |provider, vocab_name| {
+            provider.resolve_vocab(vocab_name)
+        })
+    }
+
+    /// List the resources a vocabulary is loaded from, by name.
+    ///
+    /// Names which do not resolve directly are looked up as model-name aliases;
+    /// see [`resolve_alias`](Self::resolve_alias).
+    pub fn list_resources(
+        &self,
+        name: &str,
+    ) -> WCResult<Vec<KeyedResource>> {
+        self.dispatch(name, &mut |provider, vocab_name| {
+            provider.list_resources(vocab_name)
+        })
+    }
+
+    /// Is the vocabulary publicly downloadable, by name?
+    ///
+    /// See [`VocabProvider::is_downloadable`]; unknown names are not.
+    pub fn is_downloadable(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.dispatch(name, &mut |provider, vocab_name| {
+            provider.resolve_vocab(vocab_name)?;
+            Ok(provider.is_downloadable(vocab_name))
+        })
+        .unwrap_or(false)
     }
 
     /// Load a [`UnifiedTokenVocab`] by name.
     ///
+    /// Names which do not resolve directly are looked up as model-name aliases;
+    /// see [`resolve_alias`](Self::resolve_alias).
+    ///
     /// ## Returns
     /// * `Ok((desc, vocab))` - on success.
     /// * `Err(WCError::ResourceNotFound)` - if the vocabulary is not found.
@@ -287,27 +583,190 @@ impl VocabFactory {
         name: &str,
         loader: &mut dyn ResourceLoader,
     ) -> WCResult<(VocabDescription, Arc<UnifiedTokenVocab<u32>>)> {
-        if name.contains("::") {
-            let (provider_name, vocab_name) = name.split_once("::").unwrap();
+        self.dispatch(name, &mut |provider, vocab_name| {
+            provider.load_vocab(vocab_name, loader)
+        })
+    }
+}
 
-            if let Some(provider) = self.find_provider(provider_name) {
-                return match provider.load_vocab(vocab_name, loader) {
-                    Ok(vocab) => Ok(vocab),
-                    Err(WCError::ResourceNotFound(_)) => {
-                        Err(WCError::ResourceNotFound(name.to_string()))
-                    }
-                    Err(err) => Err(err),
-                };
-            }
-        } else {
-            for provider in &self.providers {
-                match provider.load_vocab(name, loader) {
-                    Ok(vocab) => return Ok(vocab),
-                    Err(WCError::ResourceNotFound(_)) => (),
-                    Err(err) => return Err(err),
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ToyProvider;
+
+    impl VocabProvider for ToyProvider {
+        fn id(&self) -> String {
+            "toy".to_string()
         }
-        Err(WCError::ResourceNotFound(name.to_string()))
+
+        fn description(&self) -> String {
+            "toy vocabularies".to_string()
+        }
+
+        fn list_vocabs(&self) -> Vec<VocabDescription> {
+            ["small", "large"]
+                .iter()
+                .map(|id| VocabDescription {
+                    id: id.to_string(),
+                    context: vec!["toy".to_string(), id.to_string()],
+                    description: id.to_string(),
+                })
+                .collect()
+        }
+
+        fn list_aliases(&self) -> Vec<VocabAlias> {
+            vec![
+                VocabAlias::prefix("toy-", "small"),
+                VocabAlias::prefix("toy-xl-", "large"),
+                VocabAlias::exact("toy-xl-legacy", "small"),
+                VocabAlias::exact("broken", "missing"),
+            ]
+        }
+
+        fn load_vocab(
+            &self,
+            name: &str,
+            _loader: &mut dyn ResourceLoader,
+        ) -> WCResult<(VocabDescription, Arc<UnifiedTokenVocab<u32>>)> {
+            Err(WCError::ResourceNotFound(name.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_vocab_alias() {
+        let alias = VocabAlias::exact("gpt-4", "cl100k_base");
+        assert!(alias.matches("gpt-4"));
+        assert!(!alias.matches("gpt-4-0613"));
+
+        let alias = VocabAlias::prefix("gpt-4-", "cl100k_base");
+        assert!(alias.matches("gpt-4-0613"));
+        assert!(!alias.matches("gpt-4"));
+    }
+
+    #[test]
+    fn test_resolve_alias() {
+        let mut factory = VocabFactory::default();
+        factory.register_provider(Arc::new(ToyProvider)).unwrap();
+
+        assert_eq!(
+            factory.resolve_alias("toy-1"),
+            Some("toy::small".to_string())
+        );
+        assert_eq!(
+            factory.resolve_alias("toy-xl-2"),
+            Some("toy::large".to_string())
+        );
+        assert_eq!(
+            factory.resolve_alias("toy-xl-legacy"),
+            Some("toy::small".to_string())
+        );
+        assert_eq!(
+            factory.resolve_alias("toy::toy-xl-2"),
+            Some("toy::large".to_string())
+        );
+        assert_eq!(factory.resolve_alias("other::toy-1"), None);
+        assert_eq!(factory.resolve_alias("small"), None);
+
+        // Direct names are not shadowed by aliases.
+        assert_eq!(factory.resolve_vocab("small").unwrap().id, "small");
+        assert_eq!(factory.resolve_vocab("toy-xl-2").unwrap().id, "large");
+        assert_eq!(factory.resolve_vocab("toy::toy-1").unwrap().id, "small");
+
+        assert!(matches!(
+            factory.resolve_vocab("broken"),
+            Err(WCError::ResourceNotFound(name)) if name == "broken"
+        ));
+        assert!(matches!(
+            factory.resolve_vocab("nope"),
+            Err(WCError::ResourceNotFound(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_openai_aliases() {
+        assert_eq!(
+            resolve_alias("gpt-4o-mini"),
+            Some("openai::o200k_base".to_string())
+        );
+        assert_eq!(resolve_vocab("gpt-4").unwrap().id, "cl100k_base");
+        assert_eq!(resolve_vocab("openai::gpt-4o").unwrap().id, "o200k_base");
+        assert_eq!(resolve_vocab("text-davinci-003").unwrap().id, "p50k_base");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_resources_and_prefetch() {
+        use crate::support::resources::MemoryResourceLoader;
+
+        let resources = list_vocab_resources("gpt-4o").unwrap();
+        assert_eq!(resources.len(), 1);
+        let id = resources[0].resource_id().unwrap();
+        assert_eq!(id, "openai/o200k_base/o200k_base.tiktoken");
+
+        assert!(matches!(
+            list_vocab_resources("nope"),
+            Err(WCError::ResourceNotFound(_))
+        ));
+
+        assert!(is_downloadable("gpt-4o"));
+        assert!(!is_downloadable("nope"));
+        let downloadable = list_downloadable_models();
+        assert!(downloadable.contains(&"openai::o200k_base".to_string()));
+        // The Llama 3 vocabulary is gated.
+        assert!(list_models().iter().any(|name| name.starts_with("meta::")));
+        assert!(!downloadable.iter().any(|name| name.starts_with("meta::")));
+
+        let known = list_known_resources();
+        assert!(known.contains(&resources[0]));
+        // `p50k_edit` shares the `p50k_base` file.
+        assert_eq!(
+            known
+                .iter()
+                .filter(|r| r.file_name() == Some("p50k_base.tiktoken"))
+                .count(),
+            1
+        );
+
+        let mut loader = MemoryResourceLoader::new().with_resource(id, b"".as_slice());
+        assert_eq!(
+            prefetch_vocabs(&["o200k_base"], &mut loader).unwrap(),
+            resources
+        );
+        assert!(matches!(
+            prefetch_vocabs(&["cl100k_base"], &mut loader),
+            Err(WCError::ResourceNotFound(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "download")]
+    fn test_verify_disk_cache() {
+        use crate::disk_cache::{
+            CacheEntryStatus,
+            WordchipperDiskCache,
+            WordchipperDiskCacheOptions,
+        };
+
+        let dir = tempdir::TempDir::new("verify_cache").unwrap();
+        let cache = WordchipperDiskCache::new(
+            WordchipperDiskCacheOptions::default().with_cache_dir(Some(dir.path())),
+        )
+        .unwrap();
+
+        let known = cache.cache_path(&["openai", "cl100k_base"], "cl100k_base.tiktoken");
+        let unknown = cache.cache_path(&["misc"], "notes.txt");
+        for path in [&known, &unknown] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "truncated").unwrap();
+        }
+
+        let report = verify_disk_cache(&cache).unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].0.path, unknown);
+        assert_eq!(report[0].1, CacheEntryStatus::Unverified);
+        assert_eq!(report[1].0.path, known);
+        assert!(report[1].1.is_mismatch());
     }
 }
@@ -0,0 +1,373 @@
+//! # Document Splitter
+//!
+//! Splits one large document into pieces which encode independently;
+//! so the pieces can be encoded in parallel, and their tokens concatenated.
+//!
+//! A split is only taken at a line start: after a newline which follows a
+//! non-whitespace char, and before a non-whitespace ASCII char. Which line
+//! starts are safe for a spanner and normalizer is decided once, when the
+//! [`DocumentSplitter`] is built; splitting then only checks bytes:
+//! * no special word may contain a newline;
+//! * for each ASCII char, at sampled line starts before it, normalizing the
+//!   two sides separately must match normalizing them together;
+//! * and spanning the two (normalized) sides separately must match spanning
+//!   them together.
+//!
+//! This is a heuristic: the samples are short lines, checked on a window
+//! of [`SEAM_WINDOW_BYTES`] either side of the split. Patterns whose spans
+//! never cross a line start, and whose matches do not depend on text
+//! further than the window from the split, pass and encode exactly as the
+//! whole text (all the pretrained patterns and normalizers). Patterns which
+//! merge lines, or normalizers which rewrite the ends of text (e.g. a lone
+//! `Trim`), fail the check and are not split. A pattern or normalizer with
+//! longer-range context could pass the check and still encode differently;
+//! don't enable splitting for those.
+
+use crate::{
+    TokenType,
+    alloc::{string::String, vec::Vec},
+    spanners::{SpanRef, TextNormalizer, TextSpanner},
+    vocab::{SpecialVocab, VocabIndex},
+};
+
+/// The bytes checked either side of a split; see [`is_safe_document_split`].
+pub const SEAM_WINDOW_BYTES: usize = 256;
+
+/// Sample line ends for [`DocumentSplitter::new`].
+const SAMPLE_LINE_ENDS: &[&str] = &["word", "it's.", "1234", "naïve", "日本語", "a b;", "<x>"];
+
+/// Sample line tails for [`DocumentSplitter::new`];
+/// each follows the checked line start char.
+const SAMPLE_LINE_TAILS: &[&str] = &["bc d", "s 12", "1", "ll."];
+
+/// Floor `idx` to a char boundary of `text`.
+fn floor_char_boundary(
+    text: &str,
+    mut idx: usize,
+) -> usize {
+    while !text.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// Ceil `idx` to a char boundary of `text`.
+fn ceil_char_boundary(
+    text: &str,
+    mut idx: usize,
+) -> usize {
+    while !text.is_char_boundary(idx) {
+        idx += 1;
+    }
+    idx
+}
+
+/// Normalize and span `text`; shifting the spans by `offset`.
+fn normalized_spans(
+    spanner: &dyn TextSpanner,
+    normalizer: &TextNormalizer,
+    text: &str,
+    offset: usize,
+    spans: &mut Vec<SpanRef>,
+) -> usize {
+    let text = normalizer.normalize_str_around_specials(text, spanner.special_lexer());
+    spanner.for_each_split_span(&text, &mut |span_ref| {
+        let range = span_ref.range().start + offset..span_ref.range().end + offset;
+        spans.push(match span_ref {
+            SpanRef::Word(_) => SpanRef::Word(range),
+            SpanRef::Special(_) => SpanRef::Special(range),
+            SpanRef::Gap(_) => SpanRef::Gap(range),
+        });
+        true
+    });
+    text.len()
+}
+
+/// Can `text` be split at `pos`, and the sides encoded independently?
+///
+/// The split must follow a newline which follows a non-whitespace char,
+/// and precede a non-whitespace ASCII char; and normalizing and spanning
+/// the two sides separately must match doing so together.
+///
+/// This is a heuristic: the normalizing and spanning are only checked on a
+/// window of [`SEAM_WINDOW_BYTES`] either side of `pos`. It is exact for
+/// patterns whose spans never cross a line start, and whose matches do not
+/// depend on text beyond the window (all the pretrained patterns and
+/// normalizers). A pattern or normalizer with longer-range context could
+/// pass the check and still encode differently.
+///
+/// ## Arguments
+/// * `spanner` - the spanner of the encoder.
+/// * `normalizer` - the normalizer of the encoder.
+/// * `text` - the document.
+/// * `pos` - the candidate split; a byte offset in `text`.
+pub fn is_safe_document_split(
+    spanner: &dyn TextSpanner,
+    normalizer: &TextNormalizer,
+    text: &str,
+    pos: usize,
+) -> bool {
+    if !is_line_start(text.as_bytes(), pos) || !text.as_bytes()[pos].is_ascii_graphic() {
+        return false;
+    }
+
+    let start = floor_char_boundary(text, pos.saturating_sub(SEAM_WINDOW_BYTES));
+    let end = ceil_char_boundary(text, (pos + SEAM_WINDOW_BYTES).min(text.len()));
+
+    let whole =
+        normalizer.normalize_str_around_specials(&text[start..end], spanner.special_lexer());
+    let left = normalizer.normalize_str_around_specials(&text[start..pos], spanner.special_lexer());
+    let right = normalizer.normalize_str_around_specials(&text[pos..end], spanner.special_lexer());
+    if whole.len() != left.len() + right.len()
+        || !whole.starts_with(left.as_ref())
+        || !whole.ends_with(right.as_ref())
+    {
+        return false;
+    }
+
+    let mut joined = Vec::new();
+    normalized_spans(spanner, normalizer, &text[start..end], 0, &mut joined);
+
+    let mut split = Vec::with_capacity(joined.len() + 1);
+    let left_len = normalized_spans(spanner, normalizer, &text[start..pos], 0, &mut split);
+    normalized_spans(spanner, normalizer, &text[pos..end], left_len, &mut split);
+
+    joined == split
+}
+
+/// Does `pos` follow a newline which follows a non-whitespace char?
+fn is_line_start(
+    bytes: &[u8],
+    pos: usize,
+) -> bool {
+    pos >= 2
+        && pos < bytes.len()
+        && bytes[pos - 1] == b'\n'
+        && !bytes[pos - 2].is_ascii_whitespace()
+}
+
+/// Splits documents into pieces which encode independently.
+///
+/// Built once per spanner and normalizer; it records which ASCII chars
+/// may start a split line. Splitting then only checks bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentSplitter {
+    /// Bit `c` is set when a line starting with ASCII char `c` may be split off.
+    line_starts: u128,
+}
+
+impl DocumentSplitter {
+    /// Check which line starts are safe splits.
+    ///
+    /// No special word may contain a newline; and a char is a safe line
+    /// start when [`is_safe_document_split`] holds for each sample line end,
+    /// then a newline, then the char and each sample line tail.
+    ///
+    /// This checks a few thousand short texts; build once per encoder,
+    /// not per document.
+    ///
+    /// ## Arguments
+    /// * `spanner` - the spanner of the encoder.
+    /// * `normalizer` - the normalizer of the encoder.
+    /// * `specials` - the special words of the encoder.
+    pub fn new<T: TokenType>(
+        spanner: &dyn TextSpanner,
+        normalizer: &TextNormalizer,
+        specials: &SpecialVocab<T>,
+    ) -> Self {
+        if specials.span_pairs().any(|(span, _)| span.contains(&b'\n')) {
+            return Self::default();
+        }
+
+        let mut line_starts = 0;
+        let mut text = String::new();
+        for c in b'!'..=b'~' {
+            let safe = SAMPLE_LINE_ENDS.iter().all(|line_end| {
+                SAMPLE_LINE_TAILS.iter().all(|tail| {
+                    text.clear();
+                    text.push_str(line_end);
+                    text.push('\n');
+                    text.push(c as char);
+                    text.push_str(tail);
+                    is_safe_document_split(spanner, normalizer, &text, line_end.len() + 1)
+                })
+            });
+            if safe {
+                line_starts |= 1 << c;
+            }
+        }
+        Self { line_starts }
+    }
+
+    /// Can any document be split?
+    pub fn can_split(&self) -> bool {
+        self.line_starts != 0
+    }
+
+    /// Can `text` be split at `pos`?
+    ///
+    /// A byte check only: `pos` must follow a newline which follows a
+    /// non-whitespace char, and precede a safe line start char.
+    ///
+    /// ## Arguments
+    /// * `text` - the document.
+    /// * `pos` - the candidate split; a byte offset in `text`.
+    pub fn is_split_point(
+        &self,
+        text: &str,
+        pos: usize,
+    ) -> bool {
+        let bytes = text.as_bytes();
+        is_line_start(bytes, pos) && bytes[pos] < 128 && self.line_starts & (1 << bytes[pos]) != 0
+    }
+
+    /// Split a document into pieces which encode independently.
+    ///
+    /// Each piece but the last is at least `piece_bytes` long; ending at the
+    /// first split point (see [`is_split_point`](Self::is_split_point)) after
+    /// that. A document with no split point is one piece.
+    ///
+    /// ## Arguments
+    /// * `text` - the document.
+    /// * `piece_bytes` - the min piece length, in bytes.
+    ///
+    /// ## Returns
+    /// The pieces, in order; they concatenate to `text`.
+    pub fn split<'a>(
+        &self,
+        text: &'a str,
+        piece_bytes: usize,
+    ) -> Vec<&'a str> {
+        let piece_bytes = piece_bytes.max(1);
+        let mut pieces = Vec::with_capacity(text.len() / piece_bytes + 1);
+
+        let mut start = 0;
+        let mut search = piece_bytes;
+        while self.can_split() && search < text.len() {
+            let Some(newline) = text.as_bytes()[search..].iter().position(|&b| b == b'\n') else {
+                break;
+            };
+            let pos = search + newline + 1;
+            if self.is_split_point(text, pos) {
+                pieces.push(&text[start..pos]);
+                start = pos;
+                search = pos + piece_bytes;
+            } else {
+                search = pos;
+            }
+        }
+        pieces.push(&text[start..]);
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{sync::Arc, vec},
+        pretrained::openai::{OA_CL100K_BASE_PATTERN, OA_O200K_BASE_PATTERN, OA_R50K_BASE_PATTERN},
+        spanners::{NormalizerStep, TextSpannerBuilder, TextSpanningConfig},
+        vocab::SENTENCEPIECE_PATTERN,
+    };
+
+    fn spanner(pattern: &str) -> Arc<dyn TextSpanner> {
+        TextSpannerBuilder::new(
+            TextSpanningConfig::<u32>::from_pattern(pattern).with_special_words([("<|x\ny|>", 99)]),
+        )
+        .build()
+    }
+
+    #[test]
+    fn test_is_safe_document_split() {
+        let normalizer = TextNormalizer::default();
+        let r50k = spanner(OA_R50K_BASE_PATTERN.as_str());
+        let text = "foo.\nBar  \n\nbaz <|x\ny|>";
+
+        // After a newline, before a non-whitespace char.
+        assert!(is_safe_document_split(&*r50k, &normalizer, text, 5));
+        assert!(!is_safe_document_split(&*r50k, &normalizer, text, 4));
+        assert!(!is_safe_document_split(&*r50k, &normalizer, text, 11));
+        // After a whitespace run; r50k spans `\s+(?!\S)` differently at the end of text.
+        assert!(!is_safe_document_split(&*r50k, &normalizer, "a  \n\nb", 5));
+        // Inside a special word.
+        assert!(!is_safe_document_split(&*r50k, &normalizer, text, 20));
+
+        // Spans which cross lines.
+        let sp = spanner(SENTENCEPIECE_PATTERN);
+        assert!(!is_safe_document_split(&*sp, &normalizer, text, 5));
+
+        // Normalizers which are not piecewise.
+        let cl100k = spanner(OA_CL100K_BASE_PATTERN.as_str());
+        assert!(is_safe_document_split(&*cl100k, &normalizer, text, 5));
+        let lowercase = TextNormalizer::new([NormalizerStep::Lowercase]);
+        assert!(is_safe_document_split(&*cl100k, &lowercase, text, 5));
+        let prepend = TextNormalizer::new([NormalizerStep::prepend(" ")]);
+        assert!(!is_safe_document_split(&*cl100k, &prepend, text, 5));
+        let trim = TextNormalizer::new([NormalizerStep::Trim]);
+        assert!(!is_safe_document_split(&*cl100k, &trim, text, 5));
+    }
+
+    #[test]
+    fn test_document_splitter() {
+        let normalizer = TextNormalizer::default();
+        let specials = SpecialVocab::<u32>::default();
+        let r50k = spanner(OA_R50K_BASE_PATTERN.as_str());
+        let cl100k = spanner(OA_CL100K_BASE_PATTERN.as_str());
+        let o200k = spanner(OA_O200K_BASE_PATTERN.as_str());
+
+        let splitter = DocumentSplitter::new(&*r50k, &normalizer, &specials);
+        assert!(splitter.is_split_point("foo.\nBar", 5));
+        assert!(!splitter.is_split_point("foo.\nBar", 4));
+        assert!(!splitter.is_split_point("foo.\n Bar", 5));
+        assert!(!splitter.is_split_point("foo \nBar", 5));
+        assert!(!splitter.is_split_point("foo.\nBar", 9));
+        assert!(DocumentSplitter::new(&*cl100k, &normalizer, &specials).can_split());
+
+        // o200k spans `.\n/` as one word.
+        let splitter = DocumentSplitter::new(&*o200k, &normalizer, &specials);
+        assert!(splitter.is_split_point("foo.\nBar", 5));
+        assert!(!splitter.is_split_point("foo.\n/Bar", 5));
+
+        // Special words which span lines.
+        let newline_specials =
+            SpecialVocab::<u32>::default().with_special_words([("<|x\ny|>", 99)]);
+        let splitter = DocumentSplitter::new(&*cl100k, &normalizer, &newline_specials);
+        assert!(!splitter.can_split());
+        assert_eq!(splitter.split("foo.\nBar", 1), vec!["foo.\nBar"]);
+
+        // Spans which cross lines.
+        let sp = spanner(SENTENCEPIECE_PATTERN);
+        assert!(!DocumentSplitter::new(&*sp, &normalizer, &specials).can_split());
+
+        // Normalizers which are not piecewise.
+        let lowercase = TextNormalizer::new([NormalizerStep::Lowercase]);
+        assert!(DocumentSplitter::new(&*cl100k, &lowercase, &specials).can_split());
+        let trim = TextNormalizer::new([NormalizerStep::Trim]);
+        assert!(!DocumentSplitter::new(&*cl100k, &trim, &specials).can_split());
+    }
+
+    #[test]
+    fn test_split_document() {
+        let normalizer = TextNormalizer::default();
+        let cl100k = spanner(OA_CL100K_BASE_PATTERN.as_str());
+        let splitter =
+            DocumentSplitter::new(&*cl100k, &normalizer, &SpecialVocab::<u32>::default());
+
+        let text = "line one\n  indented\nline two\n".repeat(3);
+        let pieces = splitter.split(&text, 10);
+        assert_eq!(pieces.concat(), text);
+        assert_eq!(
+            pieces,
+            vec![
+                "line one\n  indented\n",
+                "line two\nline one\n  indented\n",
+                "line two\nline one\n  indented\n",
+                "line two\n",
+            ]
+        );
+
+        assert_eq!(splitter.split("", 10), vec![""]);
+        assert_eq!(splitter.split(&text, text.len()), vec![text.as_str()]);
+    }
+}
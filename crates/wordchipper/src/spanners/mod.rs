@@ -5,18 +5,29 @@
 //! [`TextSpanningConfig`] describes the declarative needs of a tokenizer:
 //! * `pattern` - the word/span split pattern.
 //! * `specials` - a map of `{ Vec<u8> -> T }` special tokens to handle out-of-band.
+//! * `normalizer` - a [`TextNormalizer`] applied to text before spanning.
+//! * `stages` - [`SpanningStage`]s chained after the word pattern.
 //!
 //! Most users will want to use the [`TextSpannerBuilder`] to construct a [`TextSpanner`].
 
 pub mod span_lexers;
 
+mod document_splitter;
 mod spanner_builder;
 mod spanning_config;
+mod spanning_stage;
+mod text_normalizer;
 mod text_spanner;
 
+#[doc(inline)]
+pub use document_splitter::*;
 #[doc(inline)]
 pub use spanner_builder::*;
 #[doc(inline)]
 pub use spanning_config::*;
 #[doc(inline)]
+pub use spanning_stage::*;
+#[doc(inline)]
+pub use text_normalizer::*;
+#[doc(inline)]
 pub use text_spanner::*;
@@ -0,0 +1,341 @@
+//! # Cross-Checked `SpanLexer`
+//!
+//! Differential comparison of a candidate [`SpanLexer`] (e.g. a logos
+//! accelerator) against a reference lexer (e.g. the compiled regex).
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    alloc::{string::String, sync::Arc, vec::Vec},
+    prelude::*,
+    spanners::{SpanRef, span_lexers::SpanLexer},
+    support::ranges::offset_range,
+};
+
+/// The first span at which two lexers disagree on a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexerDivergence {
+    /// The input text.
+    pub text: String,
+
+    /// The index of the first differing span.
+    pub index: usize,
+
+    /// The reference span; `None` if the reference ended first.
+    pub expected: Option<SpanRef>,
+
+    /// The candidate span; `None` if the candidate ended first.
+    pub actual: Option<SpanRef>,
+}
+
+impl LexerDivergence {
+    /// The byte offset in `text` where the lexers diverge.
+    pub fn offset(&self) -> usize {
+        [&self.expected, &self.actual]
+            .into_iter()
+            .flatten()
+            .map(|span| span.range().start)
+            .min()
+            .unwrap_or(self.text.len())
+    }
+
+    /// The text surrounding the divergence.
+    ///
+    /// ## Arguments
+    /// * `radius` - the max bytes of context on either side;
+    ///   widened to the nearest char boundaries.
+    pub fn context(
+        &self,
+        radius: usize,
+    ) -> &str {
+        let offset = self.offset();
+        let mut start = offset.saturating_sub(radius);
+        while !self.text.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = (offset + radius).min(self.text.len());
+        while !self.text.is_char_boundary(end) {
+            end += 1;
+        }
+        &self.text[start..end]
+    }
+
+    fn span_text(
+        &self,
+        span: &Option<SpanRef>,
+    ) -> Option<&str> {
+        span.as_ref()
+            .and_then(|span| self.text.get(span.range().clone()))
+    }
+}
+
+impl fmt::Display for LexerDivergence {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "span {} diverges at byte {}: expected {:?} {:?}, got {:?} {:?}; context: {:?}",
+            self.index,
+            self.offset(),
+            self.expected,
+            self.span_text(&self.expected),
+            self.actual,
+            self.span_text(&self.actual),
+            self.context(16),
+        )
+    }
+}
+
+/// Collect the [`SpanLexer::for_each_word`] spans of a text.
+fn collect_words(
+    lexer: &dyn SpanLexer,
+    text: &str,
+) -> Vec<SpanRef> {
+    let mut spans = Vec::new();
+    lexer.for_each_word(text, 0, &mut |span| {
+        spans.push(span);
+        true
+    });
+    spans
+}
+
+/// The index of the first differing span, if any.
+fn first_difference(
+    expected: &[SpanRef],
+    actual: &[SpanRef],
+) -> Option<usize> {
+    let common = expected.len().min(actual.len());
+    (0..common)
+        .find(|&idx| expected[idx] != actual[idx])
+        .or((expected.len() != actual.len()).then_some(common))
+}
+
+/// Find the first span at which two lexers disagree on a text.
+///
+/// ## Arguments
+/// * `reference` - the trusted lexer; e.g. the compiled regex.
+/// * `candidate` - the lexer under test; e.g. an accelerator.
+/// * `text` - the text to scan.
+///
+/// ## Returns
+/// The first divergence; or `None` if the lexers agree.
+pub fn find_lexer_divergence(
+    reference: &dyn SpanLexer,
+    candidate: &dyn SpanLexer,
+    text: &str,
+) -> Option<LexerDivergence> {
+    let expected = collect_words(reference, text);
+    let actual = collect_words(candidate, text);
+    first_difference(&expected, &actual).map(|index| LexerDivergence {
+        text: text.to_string(),
+        index,
+        expected: expected.get(index).cloned(),
+        actual: actual.get(index).cloned(),
+    })
+}
+
+/// A [`SpanLexer`] which cross-checks a candidate lexer against a reference.
+///
+/// Every scan runs both lexers. On a divergence, a warning is logged and the
+/// reference spans are used; so the output always matches the reference.
+///
+/// This roughly doubles spanning cost; it is meant for gaining confidence in
+/// an accelerator on live traffic, not for production use.
+///
+/// Only [`for_each_word`](SpanLexer::for_each_word) is checked; each call
+/// scans its whole text once with both lexers.
+/// [`next_span`](SpanLexer::next_span) is left at its default (`None`).
+/// See [`TextSpannerBuilder::set_cross_check_lexers`](crate::spanners::TextSpannerBuilder::set_cross_check_lexers).
+pub struct CrossCheckLexer {
+    reference: Arc<dyn SpanLexer>,
+    candidate: Arc<dyn SpanLexer>,
+    divergences: AtomicUsize,
+}
+
+impl CrossCheckLexer {
+    /// Create a new cross-checking lexer.
+    ///
+    /// ## Arguments
+    /// * `reference` - the trusted lexer; e.g. the compiled regex.
+    /// * `candidate` - the lexer under test; e.g. an accelerator.
+    pub fn new(
+        reference: Arc<dyn SpanLexer>,
+        candidate: Arc<dyn SpanLexer>,
+    ) -> Self {
+        Self {
+            reference,
+            candidate,
+            divergences: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of scans on which the lexers have diverged.
+    pub fn divergence_count(&self) -> usize {
+        self.divergences.load(Ordering::Relaxed)
+    }
+}
+
+impl SpanLexer for CrossCheckLexer {
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        let expected = collect_words(&*self.reference, text);
+        let actual = collect_words(&*self.candidate, text);
+
+        let spans = match first_difference(&expected, &actual) {
+            None => actual,
+            Some(index) => {
+                self.divergences.fetch_add(1, Ordering::Relaxed);
+                let divergence = LexerDivergence {
+                    text: text.to_string(),
+                    index,
+                    expected: expected.get(index).cloned(),
+                    actual: actual.get(index).cloned(),
+                };
+                log::warn!("accelerated lexer diverges from reference; {divergence}");
+                expected
+            }
+        };
+
+        let mut last = 0;
+        for span in spans {
+            let end = span.range().end;
+            let span = match span {
+                SpanRef::Word(r) => SpanRef::Word(offset_range::<usize>(r, offset)),
+                SpanRef::Special(r) => SpanRef::Special(offset_range::<usize>(r, offset)),
+                SpanRef::Gap(r) => SpanRef::Gap(offset_range::<usize>(r, offset)),
+            };
+            if !f(span) {
+                return (false, last);
+            }
+            last = end;
+        }
+        (true, last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::vec,
+        pretrained::openai::OA_R50K_BASE_PATTERN,
+        spanners::span_lexers::logos::R50kLexer,
+        support::regex::RegexWrapper,
+    };
+
+    /// Splits on every byte; wrong for any real pattern.
+    struct ByteLexer;
+
+    impl SpanLexer for ByteLexer {
+        fn next_span(
+            &self,
+            text: &str,
+            offset: usize,
+        ) -> Option<(usize, usize)> {
+            (offset < text.len()).then_some((offset, offset + 1))
+        }
+    }
+
+    fn regex() -> Arc<dyn SpanLexer> {
+        let re: RegexWrapper = OA_R50K_BASE_PATTERN.compile().unwrap();
+        Arc::new(re)
+    }
+
+    #[test]
+    fn test_find_lexer_divergence() {
+        let text = "hello world";
+        assert_eq!(find_lexer_divergence(&*regex(), &R50kLexer, text), None);
+
+        let divergence = find_lexer_divergence(&*regex(), &ByteLexer, text).unwrap();
+        assert_eq!(divergence.index, 0);
+        assert_eq!(divergence.expected, Some(SpanRef::Word(0..5)));
+        assert_eq!(divergence.actual, Some(SpanRef::Word(0..1)));
+        assert_eq!(divergence.offset(), 0);
+        assert_eq!(divergence.context(4), "hell");
+
+        let msg = divergence.to_string();
+        assert!(
+            msg.contains("expected Some(Word(0..5)) Some(\"hello\")"),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn test_divergence_length_mismatch() {
+        assert_eq!(
+            first_difference(&[SpanRef::Word(0..1)], &[SpanRef::Word(0..1)]),
+            None
+        );
+        assert_eq!(
+            first_difference(
+                &[SpanRef::Word(0..1)],
+                &[SpanRef::Word(0..1), SpanRef::Gap(1..2)]
+            ),
+            Some(1)
+        );
+
+        let divergence = LexerDivergence {
+            text: "ab\u{00e9}cd".to_string(),
+            index: 1,
+            expected: None,
+            actual: Some(SpanRef::Gap(4..5)),
+        };
+        assert_eq!(divergence.offset(), 4);
+        // Widened to the char boundary.
+        assert_eq!(divergence.context(1), "\u{00e9}c");
+    }
+
+    #[test]
+    fn test_cross_check_lexer() {
+        let agree = CrossCheckLexer::new(regex(), Arc::new(R50kLexer));
+        let mut spans = vec![];
+        let (completed, consumed) = agree.for_each_word("hi there", 10, &mut |span| {
+            spans.push(span);
+            true
+        });
+        assert_eq!((completed, consumed), (true, 8));
+        assert_eq!(spans, vec![SpanRef::Word(10..12), SpanRef::Word(12..18)]);
+        assert_eq!(agree.divergence_count(), 0);
+
+        // Divergent candidates fall back to the reference.
+        let disagree = CrossCheckLexer::new(regex(), Arc::new(ByteLexer));
+        let mut spans = vec![];
+        disagree.for_each_word("hi there", 0, &mut |span| {
+            spans.push(span);
+            true
+        });
+        assert_eq!(spans, vec![SpanRef::Word(0..2), SpanRef::Word(2..8)]);
+        assert_eq!(disagree.divergence_count(), 1);
+
+        // Early halt.
+        let (completed, consumed) = disagree.for_each_word("hi there", 0, &mut |_| false);
+        assert_eq!((completed, consumed), (false, 0));
+
+        assert_eq!(disagree.next_span("hi there", 0), None);
+    }
+
+    #[test]
+    fn test_builder_cross_check_lexers() {
+        use crate::spanners::{TextSpannerBuilder, TextSpanningConfig};
+
+        let config = TextSpanningConfig::<u32>::from_pattern(OA_R50K_BASE_PATTERN);
+        let builder = TextSpannerBuilder::new(config).with_cross_check_lexers(true);
+        assert!(builder.cross_check_lexers());
+
+        let text = "hello  world's 123";
+        let expected = TextSpannerBuilder::new(builder.config().clone())
+            .with_accelerated_lexers(false)
+            .build()
+            .split_spans(text);
+        assert_eq!(builder.build().split_spans(text), expected);
+    }
+}
@@ -4,7 +4,10 @@ use core::num::NonZeroUsize;
 
 use crate::{
     alloc::sync::Arc,
-    spanners::span_lexers::{SpanLexer, accelerators},
+    spanners::{
+        SpanningStage,
+        span_lexers::{CharClass, CharClassLexer, ChunkLexer, SpanLexer, accelerators},
+    },
     support::regex::{RegexPattern, RegexWrapper},
 };
 
@@ -38,3 +41,34 @@ pub fn build_regex_lexer(
 
     Arc::new(re)
 }
+
+/// Build a [`SpanLexer`] for a [`SpanningStage`].
+///
+/// ## Arguments
+/// * `stage` - the stage.
+/// * `accelerated` - whether to use accelerated lexers for pattern stages.
+/// * `concurrent` - whether to use a concurrent pool for pattern stages.
+/// * `max_pool` - the max size of the concurrent pool;
+///   `None` will use system/environment defaults.
+pub fn build_stage_lexer(
+    stage: SpanningStage,
+    accelerated: bool,
+    concurrent: bool,
+    max_pool: Option<NonZeroUsize>,
+) -> Arc<dyn SpanLexer> {
+    match stage {
+        SpanningStage::Pattern(pattern) => {
+            build_regex_lexer(pattern, accelerated, concurrent, max_pool)
+        }
+        SpanningStage::Digits { individual } => {
+            Arc::new(CharClassLexer::new(CharClass::Digit, individual, false))
+        }
+        SpanningStage::Punctuation => {
+            Arc::new(CharClassLexer::new(CharClass::Punctuation, true, false))
+        }
+        SpanningStage::Whitespace { remove } => {
+            Arc::new(CharClassLexer::new(CharClass::Whitespace, false, remove))
+        }
+        SpanningStage::Chunk { max_bytes } => Arc::new(ChunkLexer::new(max_bytes)),
+    }
+}
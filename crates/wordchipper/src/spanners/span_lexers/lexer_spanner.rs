@@ -13,8 +13,8 @@ use crate::{
 /// the special lexer finds special tokens that split the input into
 /// those segments.
 ///
-/// The word lexer is pluggable (e.g. regex-based or logos DFA). The special
-/// lexer is always regex-based, built from the special token patterns.
+/// Both lexers are pluggable; the word lexer is typically regex-based or a
+/// logos DFA, and the special lexer a [`SpecialWordsLexer`](super::SpecialWordsLexer).
 #[derive(Clone)]
 pub struct LexerTextSpanner {
     word_lexer: Arc<dyn SpanLexer>,
@@ -48,6 +48,10 @@ impl LexerTextSpanner {
 }
 
 impl TextSpanner for LexerTextSpanner {
+    fn special_lexer(&self) -> Option<&dyn SpanLexer> {
+        self.special_lexer.as_deref()
+    }
+
     fn for_each_split_span(
         &self,
         text: &str,
@@ -52,7 +52,7 @@ pub(crate) enum Cl100kToken {
 }
 
 impl Cl100kToken {
-    fn role(&self) -> TokenRole {
+    pub(crate) fn role(&self) -> TokenRole {
         match self {
             Self::Whitespace => TokenRole::Whitespace,
             Self::Letters => TokenRole::Word {
@@ -99,18 +99,34 @@ impl SpanLexer for Cl100kLexer {
         offset: usize,
         f: &mut dyn FnMut(SpanRef) -> bool,
     ) -> (bool, usize) {
-        for_each_classified_span(
-            Cl100kToken::lexer(text).spanned().map(|(res, range)| {
-                let role = match res {
-                    Ok(tok) => tok.role(),
-                    Err(()) => TokenRole::Gap,
-                };
-                (role, range)
-            }),
-            text,
-            offset,
-            f,
-        )
+        let tokens = Cl100kToken::lexer(text).spanned().map(|(res, range)| {
+            let role = match res {
+                Ok(tok) => tok.role(),
+                Err(()) => TokenRole::Gap,
+            };
+            (role, range)
+        });
+
+        // The `\s++$` branch matches all trailing whitespace as one span.
+        let tail = text.trim_end().len();
+        if tail == text.len() {
+            return for_each_classified_span(tokens, text, offset, f);
+        }
+
+        let mut merged: Option<(usize, bool)> = None;
+        let result = for_each_classified_span(tokens, text, offset, &mut |span| match span {
+            SpanRef::Word(r) if r.start - offset >= tail => {
+                let accepted = f(SpanRef::Word(r.start..offset + text.len()));
+                merged = Some((r.start - offset, accepted));
+                false
+            }
+            span => f(span),
+        });
+        match merged {
+            Some((_, true)) => (true, text.len()),
+            Some((start, false)) => (false, start),
+            None => result,
+        }
     }
 }
 
@@ -257,6 +273,10 @@ mod tests {
             "price is 100 dollars",
             "caf\u{00e9} na\u{00ef}ve r\u{00e9}sum\u{00e9}",
             "Hello \u{4e16}\u{754c} 123",
+            // Trailing whitespace runs are one span.
+            "foo\n ",
+            "foo\r\u{a0}",
+            "foo\n\u{2000}",
         ];
 
         for text in cases {
@@ -0,0 +1,298 @@
+//! # `DeepSeek` V3 Logos Lexer
+//!
+//! Compile-time DFA lexer for the `DeepSeek` V3 pattern (V3, R1).
+//!
+//! Digit and CJK runs are split out ahead of the main pattern; they map to
+//! [`TokenRole::Isolated`], so the whitespace before them stays whole.
+
+use core::ops::Range;
+
+use logos::Logos;
+
+use super::{engine::for_each_classified_span, token_role::TokenRole};
+use crate::{
+    alloc::sync::Arc,
+    pretrained::deepseek::DEEPSEEK_V3_PATTERN,
+    spanners::{
+        SpanRef,
+        span_lexers::{SpanLexer, accelerators::RegexAcceleratorHook},
+    },
+};
+
+/// Logos token for the `DeepSeek` V3 pattern.
+///
+/// `CJK` is `[一-龥぀-ゟ゠-ヿ]`; it is excluded from the later classes.
+///
+/// | Regex branch                                  | Logos variant      |
+/// |-----------------------------------------------|--------------------|
+/// | `\p{N}{1,3}`                                  | Digits             |
+/// | `[CJK]+`                                      | Cjk                |
+/// | `[!-/:-@\[-`{-~][A-Za-z]+`                    | AsciiPunctLetters  |
+/// | `[^\r\n\p{L}\p{P}\p{S}]?[\p{L}\p{M}]+`        | Letters / OtherLetters |
+/// | ` ?[\p{P}\p{S}]+[\r\n]*`                      | Punctuation        |
+/// | `\s*[\r\n]+`                                  | Newline            |
+/// | `\s+(?![^\s\p{N}CJK])` / `\s+`                | Whitespace         |
+/// | `(?:[^\s\p{L}\p{M}\p{N}\p{P}\p{S}CJK](?!...))+` | Other              |
+#[derive(Logos, Debug, PartialEq, Clone)]
+enum DeepSeekV3Token {
+    #[regex(r"\p{Number}{1,3}")]
+    Digits,
+
+    #[regex(r"[一-龥぀-ゟ゠-ヿ]+")]
+    Cjk,
+
+    #[regex(r"[!-/:-@\[-`{-~][A-Za-z]+")]
+    AsciiPunctLetters,
+
+    // The letter prefix, split by kind: whitespace may be absorbed from a
+    // preceding run; other prefixes (format, private use) never absorb.
+    // Digits, marks and CJK never act as a prefix: digits and CJK match
+    // first, and marks are already in the letter class.
+    #[regex(r"[^\S\r\n]?[\p{Letter}\p{Mark}--一-龥぀-ゟ゠-ヿ]+")]
+    Letters,
+
+    #[regex(
+        r"[^\s\p{Letter}\p{Mark}\p{Number}\p{Punctuation}\p{Symbol}一-龥぀-ゟ゠-ヿ][\p{Letter}\p{Mark}--一-龥぀-ゟ゠-ヿ]+"
+    )]
+    OtherLetters,
+
+    #[regex(r" ?[\p{Punctuation}\p{Symbol}--一-龥぀-ゟ゠-ヿ]+[\r\n]*")]
+    Punctuation,
+
+    #[regex(r"\s*[\r\n]+")]
+    Newline,
+
+    #[regex(r"[^\S\r\n]+")]
+    Whitespace,
+
+    // Text the split keeps but no branch matches; up to a letter prefix.
+    #[regex(r"[^\s\p{Letter}\p{Mark}\p{Number}\p{Punctuation}\p{Symbol}一-龥぀-ゟ゠-ヿ]+")]
+    Other,
+}
+
+impl DeepSeekV3Token {
+    fn role(&self) -> TokenRole {
+        match self {
+            Self::Whitespace => TokenRole::Whitespace,
+            Self::Letters | Self::AsciiPunctLetters => TokenRole::Word {
+                check_contraction: false,
+            },
+            Self::Punctuation => TokenRole::Punctuation,
+            Self::Digits | Self::Cjk => TokenRole::Isolated,
+            Self::OtherLetters | Self::Newline | Self::Other => TokenRole::Standalone,
+        }
+    }
+}
+
+/// The logos token stream, with tokens cut to the regex match.
+///
+/// * Logos can report an error spanning the chars it consumed trying a
+///   branch; e.g. an unassigned prefix char and the CJK char after it. The
+///   regex leaves only the first char unmatched, and restarts after it.
+/// * After an ASCII space, the regex matches the space and punctuation as
+///   ` ?[\p{P}\p{S}]+`, not as `AsciiPunctLetters`; the letters then
+///   continue as `\p{L}+`.
+/// * An `Other` run followed by letters leaves its last char to prefix
+///   them; logos only finds that prefix when the run is one char long.
+struct DeepSeekV3Tokens<'a> {
+    text: &'a str,
+    base: usize,
+    lexer: logos::Lexer<'a, DeepSeekV3Token>,
+}
+
+impl<'a> DeepSeekV3Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            base: 0,
+            lexer: DeepSeekV3Token::lexer(text),
+        }
+    }
+}
+
+impl Iterator for DeepSeekV3Tokens<'_> {
+    type Item = (TokenRole, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.lexer.next()?;
+        let span = self.lexer.span();
+        let range = self.base + span.start..self.base + span.end;
+
+        match res {
+            Ok(DeepSeekV3Token::AsciiPunctLetters)
+                if self.text.as_bytes()[..range.start].last() == Some(&b' ') =>
+            {
+                let split = range.start + 1;
+                self.base = split;
+                self.lexer = DeepSeekV3Token::lexer(&self.text[split..]);
+                Some((TokenRole::Punctuation, range.start..split))
+            }
+            Ok(DeepSeekV3Token::Other) => {
+                let rest = &self.text[range.end..];
+                let last = self.text[..range.end]
+                    .char_indices()
+                    .next_back()
+                    .map_or(range.start, |(idx, _)| idx);
+                if last > range.start
+                    && rest.starts_with(|c: char| !c.is_whitespace())
+                    && matches!(
+                        DeepSeekV3Token::lexer(rest).next(),
+                        Some(Ok(DeepSeekV3Token::Letters))
+                    )
+                {
+                    self.base = last;
+                    self.lexer = DeepSeekV3Token::lexer(&self.text[last..]);
+                    return Some((TokenRole::Standalone, range.start..last));
+                }
+                Some((TokenRole::Standalone, range))
+            }
+            Ok(tok) => Some((tok.role(), range)),
+            Err(()) => {
+                let first = self.text[range.start..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+                let split = range.start + first;
+                if split < range.end {
+                    self.base = split;
+                    self.lexer = DeepSeekV3Token::lexer(&self.text[split..]);
+                }
+                Some((TokenRole::Gap, range.start..split))
+            }
+        }
+    }
+}
+
+/// A [`SpanLexer`] for the `DeepSeek` V3 pattern (V3, R1).
+///
+/// Uses a compile-time logos DFA for word scanning.
+///
+/// Only matches the regex spans; does not match the special tokens.
+#[derive(Clone, Debug)]
+pub struct DeepSeekV3Lexer;
+
+inventory::submit! {
+    RegexAcceleratorHook::new(DEEPSEEK_V3_PATTERN, || Arc::new(DeepSeekV3Lexer))
+}
+
+impl SpanLexer for DeepSeekV3Lexer {
+    fn next_span(
+        &self,
+        text: &str,
+        offset: usize,
+    ) -> Option<(usize, usize)> {
+        let mut next_span: Option<(usize, usize)> = None;
+        self.for_each_word(text, offset, &mut |span_ref| match span_ref {
+            SpanRef::Word(r) => {
+                next_span = Some((r.start, r.end));
+                false
+            }
+            _ => true,
+        });
+        next_span
+    }
+
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        for_each_classified_span(DeepSeekV3Tokens::new(text), text, offset, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{sync::Arc, vec, vec::Vec},
+        spanners::{TextSpanner, span_lexers::LexerTextSpanner},
+    };
+
+    fn spanner() -> LexerTextSpanner {
+        LexerTextSpanner::new(Arc::new(DeepSeekV3Lexer), None)
+    }
+
+    #[test]
+    fn test_logos_deepseek_v3_isolated() {
+        let text = "a  12345 \u{4e2d}\u{6587}abc";
+        let words: Vec<&str> = spanner()
+            .split_spans(text)
+            .into_iter()
+            .map(|s| &text[s.range().clone()])
+            .collect();
+        assert_eq!(
+            words,
+            vec!["a", "  ", "123", "45", " ", "\u{4e2d}\u{6587}", "abc"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_logos_deepseek_v3_accelerated() {
+        use crate::spanners::span_lexers::accelerators::get_regex_accelerator;
+
+        assert!(get_regex_accelerator(DEEPSEEK_V3_PATTERN.as_str()).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_logos_deepseek_v3_matches_regex() {
+        let regex_spanner =
+            LexerTextSpanner::new(Arc::new(DEEPSEEK_V3_PATTERN.compile().unwrap()), None);
+        let logos_spanner = spanner();
+
+        let cases = [
+            "Hello world",
+            "don't I'LL she's",
+            "price is 1234567 dollars, \u{0663}\u{0664} \u{00bd}",
+            "caf\u{00e9} na\u{00ef}ve \u{4f60}\u{597d}\u{4e16}\u{754c}",
+            "\u{3053}\u{3093}\u{306b}\u{3061}\u{306f}\u{30ab}\u{30bf}\u{30ab}\u{30ca}!",
+            "  \nfoo  \n\n bar   ",
+            "x.y (abc) !!abc \t!abc",
+            "Shakespeare's \"sources,\" then read",
+            "  $400 dollars",
+            "\u{200b}abc \u{200b}abc",
+            // Punctuation and letters after a space.
+            "  $l\u{2cec0}",
+            // Unmatched runs, and the whitespace before them.
+            "\u{10bfd}\u{4f60}",
+            "\u{11792}\u{15351}\u{1ff18}[",
+            " \u{2000}\u{b49} ",
+            "  \u{19bd6}",
+            "\u{200d}\u{e000}\u{e000}abc \u{200d}\u{3040}",
+        ];
+
+        for text in cases {
+            let regex_spans = regex_spanner.split_spans(text);
+            let logos_spans = logos_spanner.split_spans(text);
+            assert_eq!(
+                regex_spans, logos_spans,
+                "deepseek_v3 mismatch for {:?}:\n  regex: {:?}\n  logos: {:?}",
+                text, regex_spans, logos_spans
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn proptest_deepseek_v3_logos_matches_regex() {
+        use proptest::prelude::*;
+
+        let regex_spanner =
+            LexerTextSpanner::new(Arc::new(DEEPSEEK_V3_PATTERN.compile().unwrap()), None);
+        let logos_spanner = spanner();
+
+        let config = proptest::test_runner::Config::with_cases(2000);
+        proptest!(config, |(text in "\\PC{0,200}")| {
+            let regex_spans = regex_spanner.split_spans(&text);
+            let logos_spans = logos_spanner.split_spans(&text);
+            prop_assert_eq!(
+                &regex_spans, &logos_spans,
+                "deepseek_v3 mismatch for {:?}",
+                text
+            );
+        });
+    }
+}
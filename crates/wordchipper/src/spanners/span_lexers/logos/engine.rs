@@ -9,9 +9,52 @@
 
 use core::ops::Range;
 
+use logos::Logos;
+
 use super::token_role::{TokenRole, contraction_split};
 use crate::spanners::SpanRef;
 
+/// Probe for the first char of a word token.
+#[derive(Logos, Debug, PartialEq, Clone)]
+enum FirstChar {
+    #[regex(r"\p{Letter}")]
+    Letter,
+
+    #[regex(r"\p{Mark}")]
+    Mark,
+}
+
+/// Does a word token start with a word char, rather than a prefix?
+///
+/// Contraction-checking words (the cl100k family) are `\p{L}+`; the o200k
+/// words also admit `\p{M}`. `char::is_alphabetic` matches neither exactly.
+fn starts_with_word_char(
+    token: &[u8],
+    check_contraction: bool,
+) -> bool {
+    // Decode only the first UTF-8 char from bytes to avoid
+    // validating the entire tail (which would be O(n^2) overall).
+    let char_len = match token.first() {
+        Some(&b) if b < 0x80 => 1,
+        Some(&b) if b < 0xE0 => 2,
+        Some(&b) if b < 0xF0 => 3,
+        Some(_) => 4,
+        None => return false,
+    };
+    let Some(first) = token
+        .get(..char_len)
+        .and_then(|b| core::str::from_utf8(b).ok())
+    else {
+        return false;
+    };
+
+    match FirstChar::lexer(first).next() {
+        Some(Ok(FirstChar::Letter)) => true,
+        Some(Ok(FirstChar::Mark)) => !check_contraction,
+        _ => false,
+    }
+}
+
 /// Iterate classified logos tokens and emit Word/Gap spans with
 /// post-processing corrections for regex compatibility:
 ///
@@ -19,10 +62,11 @@ use crate::spanners::SpanRef;
 ///    whitespace byte becomes a prefix of the next word. We buffer Whitespace
 ///    tokens and split off the last byte when followed by certain tokens.
 ///
-/// 2. **Prefix handling**: with 2+ whitespace chars before a token starting
-///    with a non-letter, we merge the last whitespace byte + the non-letter
-///    prefix into one span (matching how Punctuation's ` ?` absorbs a space
-///    in the regex). With 1 whitespace char, it stays standalone.
+/// 2. **Prefix handling**: when the last whitespace char before a token
+///    starting with a non-letter is an ASCII space, we merge it with the
+///    non-letter prefix into one span (matching how Punctuation's ` ?`
+///    absorbs a space in the regex). Other whitespace stays standalone.
+///    Contractions preceded by a space split the same way (` '` + `s`).
 ///
 /// 3. **Contraction splitting** (when `check_contraction` is true): regex
 ///    first-match picks Contraction `'T` over Letters `'The`, but logos
@@ -83,6 +127,8 @@ pub fn for_each_classified_span(
     let text = text.as_bytes();
     let mut last = 0;
     let mut pending_ws: Option<Range<usize>> = None;
+    // Letters left after splitting ` '` off a contraction; see `Standalone`.
+    let mut pending_suffix: Option<Range<usize>> = None;
 
     macro_rules! emit {
         (word $r:expr) => {
@@ -135,9 +181,24 @@ pub fn for_each_classified_span(
         };
     }
 
-    for (kind, span) in iter {
+    let mut iter = iter.peekable();
+    while let Some((kind, span)) = iter.next() {
         let Range { start, end } = span;
 
+        if let Some(suffix) = pending_suffix.take() {
+            if suffix.end == start
+                && matches!(kind, TokenRole::Word { .. } | TokenRole::Punctuation)
+                && starts_with_word_char(&text[start..end], true)
+            {
+                // The regex matches the suffix and the following letters
+                // as one word.
+                emit!(word(suffix.start..end));
+                last = end;
+                continue;
+            }
+            emit!(word suffix);
+        }
+
         if last < start {
             if let Some(ws) = pending_ws.take() {
                 emit!(word ws);
@@ -175,40 +236,19 @@ pub fn for_each_classified_span(
             }
             TokenRole::Word { check_contraction } => {
                 if let Some(ws) = pending_ws.take() {
-                    let ws_start = ws.start;
                     let ws_end = ws.end;
                     let trim = flush_ws_split!(ws);
-                    let single_char = trim == ws_start;
-
-                    // Decode only the first UTF-8 char from bytes to avoid
-                    // validating the entire tail (which would be O(n^2) overall).
-                    let first_is_letter = {
-                        let tail = &text[start..];
-                        let char_len = match tail.first() {
-                            Some(&b) if b < 0x80 => 1,
-                            Some(&b) if b < 0xE0 => 2,
-                            Some(&b) if b < 0xF0 => 3,
-                            Some(_) => 4,
-                            None => 0,
-                        };
-                        char_len > 0
-                            && core::str::from_utf8(&tail[..char_len])
-                                .ok()
-                                .and_then(|s| s.chars().next())
-                                .is_some_and(char::is_alphabetic)
-                    };
+
+                    let first_is_letter =
+                        starts_with_word_char(&text[start..end], check_contraction);
 
                     if first_is_letter {
                         // Token has no existing prefix; merge last ws char.
                         emit_absorbing!(trim, end, check_contraction);
-                    } else if single_char {
-                        // Single ws char: emit standalone, token as-is.
-                        emit!(word(trim..ws_end));
-                        emit_absorbing!(start, end, check_contraction);
-                    } else {
-                        // 2+ ws chars: merge last ws char + non-letter
-                        // prefix into one span (like Punctuation ` ?X`),
-                        // then emit remaining letters separately.
+                    } else if text[trim] == b' ' {
+                        // Last ws char is an ASCII space: merge it with the
+                        // non-letter prefix into one span (like Punctuation
+                        // ` ?X`), then emit remaining letters separately.
                         let prefix_len = core::str::from_utf8(&text[start..end])
                             .expect("text is &str bytes, always valid UTF-8")
                             .chars()
@@ -216,6 +256,11 @@ pub fn for_each_classified_span(
                             .map_or(1, char::len_utf8);
                         emit!(word(trim..start + prefix_len));
                         emit_absorbing!(start + prefix_len, end, check_contraction);
+                    } else {
+                        // Other ws chars (tab, NBSP) are not absorbed by
+                        // ` ?`; emit standalone, token as-is.
+                        emit!(word(trim..ws_end));
+                        emit_absorbing!(start, end, check_contraction);
                     }
                 } else {
                     emit_absorbing!(start, end, check_contraction);
@@ -225,19 +270,49 @@ pub fn for_each_classified_span(
                 if let Some(ws) = pending_ws.take() {
                     let ws_end = ws.end;
                     let trim = flush_ws_split!(ws);
+                    if text[start] == b'\'' && text[trim] == b' ' {
+                        // A contraction only matches at the apostrophe;
+                        // from the space, the regex matches ` ?'` as
+                        // punctuation, and the suffix as letters.
+                        emit!(word(trim..start + 1));
+                        pending_suffix = Some(start + 1..end);
+                        continue;
+                    }
                     emit!(word(trim..ws_end));
                 }
                 emit!(word(start..end));
             }
-            TokenRole::Gap => {
+            TokenRole::Isolated => {
                 if let Some(ws) = pending_ws.take() {
                     emit!(word ws);
                 }
+                emit!(word(start..end));
+            }
+            TokenRole::Gap => {
+                if let Some(ws) = pending_ws.take() {
+                    // Gap chars are `\S`; so `\s+(?!\S)` backtracks.
+                    let ws_end = ws.end;
+                    let trim = flush_ws_split!(ws);
+                    emit!(word(trim..ws_end));
+                }
+                // The regex leaves the whole unmatched run as one gap.
+                let mut end = end;
+                while let Some((TokenRole::Gap, next)) = iter.peek()
+                    && next.start == end
+                {
+                    end = next.end;
+                    iter.next();
+                }
+                last = end;
                 emit!(gap(start..end));
             }
         }
     }
 
+    if let Some(suffix) = pending_suffix.take() {
+        emit!(word suffix);
+    }
+
     if let Some(ws) = pending_ws.take() {
         last = ws.end;
         emit!(word ws);
@@ -345,6 +420,7 @@ mod tests {
                 check_contraction: true,
             },
             TokenRole::Standalone,
+            TokenRole::Isolated,
             TokenRole::Gap,
         ];
 
@@ -386,6 +462,95 @@ mod tests {
         tokens
     }
 
+    #[test]
+    fn test_space_before_prefixed_tokens() {
+        let word = TokenRole::Word {
+            check_contraction: true,
+        };
+
+        // A space joins a non-letter prefix; a tab does not.
+        let text = " 'dog\t\t[A";
+        let tokens = [
+            (TokenRole::Whitespace, 0..1),
+            (word, 1..5),
+            (TokenRole::Whitespace, 5..7),
+            (word, 7..9),
+        ];
+        assert_eq!(
+            collect_spans(tokens.into_iter(), text, 0),
+            [
+                SpanRef::Word(0..2),
+                SpanRef::Word(2..5),
+                SpanRef::Word(5..6),
+                SpanRef::Word(6..7),
+                SpanRef::Word(7..9),
+            ]
+        );
+
+        // A space before a contraction splits like punctuation.
+        let text = "\t 'd";
+        let tokens = [(TokenRole::Whitespace, 0..2), (TokenRole::Standalone, 2..4)];
+        assert_eq!(
+            collect_spans(tokens.into_iter(), text, 0),
+            [
+                SpanRef::Word(0..1),
+                SpanRef::Word(1..3),
+                SpanRef::Word(3..4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contraction_suffix_joins_letters() {
+        let word = TokenRole::Word {
+            check_contraction: true,
+        };
+
+        // The suffix of a split contraction continues into the letters.
+        let text = "\t 'dog";
+        let tokens = [
+            (TokenRole::Whitespace, 0..2),
+            (TokenRole::Standalone, 2..4),
+            (word, 4..6),
+        ];
+        assert_eq!(
+            collect_spans(tokens.into_iter(), text, 0),
+            [
+                SpanRef::Word(0..1),
+                SpanRef::Word(1..3),
+                SpanRef::Word(3..6)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gap_runs() {
+        // Adjacent gaps are one unmatched run; whitespace before a gap
+        // leaves its last char to the gap side.
+        let text = "  ##";
+        let tokens = [
+            (TokenRole::Whitespace, 0..2),
+            (TokenRole::Gap, 2..3),
+            (TokenRole::Gap, 3..4),
+        ];
+        assert_eq!(
+            collect_spans(tokens.into_iter(), text, 0),
+            [SpanRef::Word(0..1), SpanRef::Word(1..2), SpanRef::Gap(2..4)]
+        );
+    }
+
+    #[test]
+    fn test_starts_with_word_char() {
+        assert!(starts_with_word_char(b"abc", true));
+        assert!(!starts_with_word_char(b"'abc", true));
+        assert!(!starts_with_word_char(b"", true));
+
+        // U+09D7 is alphabetic, but a mark; not `\p{L}`.
+        let mark = "\u{9d7}\u{a640}".as_bytes();
+        assert!(!starts_with_word_char(mark, true));
+        assert!(starts_with_word_char(mark, false));
+    }
+
     // -------------------------------------------------------------------
     // Structural invariant proptests
     // -------------------------------------------------------------------
@@ -396,7 +561,7 @@ mod tests {
         #[test]
         fn structural_invariants_multi_role(
             text in "\\PC{0,100}",
-            chunks in proptest::collection::vec((1..5usize, 0..6u8), 1..20),
+            chunks in proptest::collection::vec((1..5usize, 0..7u8), 1..20),
         ) {
             let tokens = build_token_stream(&text, &chunks);
             let spans = collect_spans(tokens.into_iter(), &text, 0);
@@ -406,7 +571,7 @@ mod tests {
         #[test]
         fn structural_invariants_with_offset(
             text in "\\PC{1,50}",
-            chunks in proptest::collection::vec((1..5usize, 0..6u8), 1..10),
+            chunks in proptest::collection::vec((1..5usize, 0..7u8), 1..10),
             offset in 0..1000usize,
         ) {
             let tokens = build_token_stream(&text, &chunks);
@@ -431,7 +596,7 @@ mod tests {
         #[test]
         fn early_termination(
             text in "\\PC{1,80}",
-            chunks in proptest::collection::vec((1..4usize, 0..6u8), 1..15),
+            chunks in proptest::collection::vec((1..4usize, 0..7u8), 1..15),
             stop_after in 1..10usize,
         ) {
             let tokens = build_token_stream(&text, &chunks);
@@ -472,7 +637,7 @@ mod tests {
         #[test]
         fn deterministic(
             text in "\\PC{0,80}",
-            chunks in proptest::collection::vec((1..4usize, 0..6u8), 1..15),
+            chunks in proptest::collection::vec((1..4usize, 0..7u8), 1..15),
         ) {
             let tokens1 = build_token_stream(&text, &chunks);
             let tokens2 = build_token_stream(&text, &chunks);
@@ -0,0 +1,158 @@
+//! # Llama 3 Logos Lexer
+//!
+//! Compile-time DFA lexer for the Meta `Llama 3` pattern.
+//!
+//! The Llama 3 pattern is the `cl100k_base` pattern written without
+//! possessive quantifiers; the two produce identical spans, so this
+//! lexer reuses the `cl100k` token DFA.
+
+use logos::Logos;
+
+use super::{cl100k::Cl100kToken, engine::for_each_classified_span, token_role::TokenRole};
+use crate::{
+    alloc::sync::Arc,
+    pretrained::meta::META_LLAMA3_PATTERN,
+    spanners::{
+        SpanRef,
+        span_lexers::{SpanLexer, accelerators::RegexAcceleratorHook},
+    },
+};
+
+/// A [`SpanLexer`] for the Meta `Llama 3` pattern (Llama 3, 3.1, 3.2, 3.3).
+///
+/// Uses a compile-time logos DFA for word scanning.
+///
+/// Only matches the regex spans; does not match the special tokens.
+#[derive(Clone, Debug)]
+pub struct Llama3Lexer;
+
+inventory::submit! {
+    RegexAcceleratorHook::new(META_LLAMA3_PATTERN, || Arc::new(Llama3Lexer))
+}
+
+impl SpanLexer for Llama3Lexer {
+    fn next_span(
+        &self,
+        text: &str,
+        offset: usize,
+    ) -> Option<(usize, usize)> {
+        let mut next_span: Option<(usize, usize)> = None;
+        self.for_each_word(text, offset, &mut |span_ref| match span_ref {
+            SpanRef::Word(r) => {
+                next_span = Some((r.start, r.end));
+                false
+            }
+            _ => true,
+        });
+        next_span
+    }
+
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        for_each_classified_span(
+            Cl100kToken::lexer(text).spanned().map(|(res, range)| {
+                let role = match res {
+                    Ok(tok) => tok.role(),
+                    Err(()) => TokenRole::Gap,
+                };
+                (role, range)
+            }),
+            text,
+            offset,
+            f,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{sync::Arc, vec, vec::Vec},
+        spanners::{TextSpanner, span_lexers::LexerTextSpanner},
+    };
+
+    fn spanner() -> LexerTextSpanner {
+        LexerTextSpanner::new(Arc::new(Llama3Lexer), None)
+    }
+
+    #[test]
+    fn test_logos_llama3_basic() {
+        let s = spanner();
+        assert_eq!(
+            s.split_spans("hello world"),
+            vec![SpanRef::Word(0..5), SpanRef::Word(5..11)]
+        );
+        assert!(s.split_spans("").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_logos_llama3_accelerated() {
+        use crate::spanners::span_lexers::accelerators::get_regex_accelerator;
+
+        assert!(get_regex_accelerator(META_LLAMA3_PATTERN.to_pattern().as_str()).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_logos_llama3_matches_regex() {
+        use crate::support::regex::RegexWrapper;
+
+        let regex: RegexWrapper = META_LLAMA3_PATTERN.compile().unwrap();
+        let regex_spanner = LexerTextSpanner::new(Arc::new(regex), None);
+        let logos_spanner = spanner();
+
+        let cases = [
+            "Hello world",
+            "don't I'LL she's",
+            "price is 1234567 dollars",
+            "caf\u{00e9} na\u{00ef}ve \u{4f60}\u{597d}",
+            "  \nfoo  \n\n bar   ",
+            "\t 'd",
+            " 'dog \t'dog",
+            "\t\t[A",
+            "\u{2000}\u{9d7}\u{a640}",
+            "Shakespeare's \"sources,\" then read",
+            "  $400 dollars",
+            " \u{2014}hello world",
+        ];
+
+        for text in cases {
+            let regex_spans = regex_spanner.split_spans(text);
+            let logos_spans = logos_spanner.split_spans(text);
+            assert_eq!(
+                regex_spans, logos_spans,
+                "llama3 mismatch for {:?}:\n  regex: {:?}\n  logos: {:?}",
+                text, regex_spans, logos_spans
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn proptest_llama3_logos_matches_regex() {
+        use proptest::prelude::*;
+
+        use crate::support::regex::RegexWrapper;
+
+        let regex: RegexWrapper = META_LLAMA3_PATTERN.compile().unwrap();
+        let regex_spanner = LexerTextSpanner::new(Arc::new(regex), None);
+        let logos_spanner = spanner();
+
+        let config = proptest::test_runner::Config::with_cases(2000);
+        proptest!(config, |(text in "\\PC{0,200}")| {
+            let regex_spans: Vec<SpanRef> = regex_spanner.split_spans(&text);
+            let logos_spans = logos_spanner.split_spans(&text);
+            prop_assert_eq!(
+                &regex_spans, &logos_spans,
+                "llama3 mismatch for {:?}",
+                text
+            );
+        });
+    }
+}
@@ -10,21 +10,36 @@
 //! 3. Implement [`SpanLexer`](super::SpanLexer) by feeding the token stream
 //!    to [`for_each_classified_span`].
 //!
-//! See [`Cl100kLexer`] and [`O200kLexer`] for reference implementations.
+//! See [`Cl100kLexer`] and [`O200kLexer`] for reference implementations;
+//! [`DeepSeekV3Lexer`] shows [`TokenRole::Isolated`] for split sequences.
+//!
+//! Patterns built from the usual `join_patterns!` branches (contractions,
+//! prefixed words, ` ?` punctuation, `\s+(?!\S)` / `\s+` whitespace) need not
+//! be hand-derived: the `wordchipper-lexgen` dev crate generates the lexer,
+//! and its differential test against the regex.
 
 mod cl100k;
+mod deepseek;
 mod engine;
+mod llama3;
 mod o200k;
+mod qwen2;
 mod r50k;
 mod token_role;
 
 #[doc(inline)]
 pub use cl100k::Cl100kLexer;
 #[doc(inline)]
+pub use deepseek::DeepSeekV3Lexer;
+#[doc(inline)]
 pub use engine::for_each_classified_span;
 #[doc(inline)]
+pub use llama3::Llama3Lexer;
+#[doc(inline)]
 pub use o200k::O200kLexer;
 #[doc(inline)]
+pub use qwen2::Qwen2Lexer;
+#[doc(inline)]
 pub use r50k::R50kLexer;
 #[doc(inline)]
 pub use token_role::{TokenRole, contraction_split};
@@ -5,6 +5,8 @@
 //! This serves as a reference implementation showing how to build an
 //! accelerated lexer using [`TokenRole`] and [`for_each_classified_span`].
 
+use core::ops::Range;
+
 use logos::Logos;
 
 use super::{engine::for_each_classified_span, token_role::TokenRole};
@@ -78,6 +80,255 @@ impl O200kToken {
     }
 }
 
+/// Char classes of the o200k word branches.
+#[derive(Logos, Debug, PartialEq, Clone, Copy)]
+enum O200kChar {
+    #[regex(r"[\p{Uppercase_Letter}\p{Titlecase_Letter}]")]
+    Upper,
+
+    #[regex(r"\p{Lowercase_Letter}")]
+    Lower,
+
+    #[regex(r"[\p{Modifier_Letter}\p{Other_Letter}]")]
+    OtherLetter,
+
+    #[regex(r"\p{Mark}")]
+    Mark,
+
+    #[regex(r"[^\p{Letter}\p{Mark}]")]
+    NonLetter,
+}
+
+impl O200kChar {
+    /// In `UPPER`.
+    fn is_upper(self) -> bool {
+        matches!(self, Self::Upper | Self::OtherLetter | Self::Mark)
+    }
+
+    /// In `LOWER`.
+    fn is_lower(self) -> bool {
+        matches!(self, Self::Lower | Self::OtherLetter | Self::Mark)
+    }
+
+    /// In `[^\r\n\p{L}\p{N}]`; word tokens contain no `\r\n\p{N}`.
+    fn is_prefix(self) -> bool {
+        matches!(self, Self::Mark | Self::NonLetter)
+    }
+}
+
+/// Classify the chars of `word[from..]`, with their end byte offsets.
+fn o200k_chars(
+    word: &str,
+    from: usize,
+) -> impl Iterator<Item = (O200kChar, usize)> + '_ {
+    O200kChar::lexer(&word[from..])
+        .spanned()
+        .map(move |(c, r)| (c.unwrap_or(O200kChar::NonLetter), from + r.end))
+}
+
+/// End of `[UPPER]*[LOWER]+` at `word[from..]`, with regex backtracking.
+fn o200k_upper_lower_end(
+    word: &str,
+    from: usize,
+) -> Option<usize> {
+    let mut chars = o200k_chars(word, from);
+    // If `[UPPER]*` must give chars back, `[LOWER]+` ends after the last
+    // `LOWER` char of the run; the char following it is not `LOWER`.
+    let mut backtrack_end = None;
+    while let Some((c, end)) = chars.next() {
+        if c.is_upper() {
+            if c.is_lower() {
+                backtrack_end = Some(end);
+            }
+            continue;
+        }
+        if !c.is_lower() {
+            break;
+        }
+        let mut end = end;
+        for (c, next) in chars.by_ref() {
+            if !c.is_lower() {
+                break;
+            }
+            end = next;
+        }
+        return Some(end);
+    }
+    backtrack_end
+}
+
+/// End of `[UPPER]+[LOWER]*` at `word[from..]`.
+fn o200k_lower_upper_end(
+    word: &str,
+    from: usize,
+) -> Option<usize> {
+    let mut end = None;
+    let mut in_lower = false;
+    for (c, next) in o200k_chars(word, from) {
+        if !in_lower && c.is_upper() {
+            end = Some(next);
+        } else if end.is_some() && c.is_lower() {
+            in_lower = true;
+            end = Some(next);
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+/// Length of an `(?i:'s|'t|'re|'ve|'m|'ll|'d)` suffix.
+fn o200k_contraction_len(rest: &[u8]) -> usize {
+    match rest {
+        [b'\'', a, b, ..]
+            if matches!(
+                [a.to_ascii_lowercase(), b.to_ascii_lowercase()],
+                [b'r', b'e'] | [b'v', b'e'] | [b'l', b'l']
+            ) =>
+        {
+            3
+        }
+        [b'\'', a, ..] if matches!(a.to_ascii_lowercase(), b's' | b't' | b'm' | b'd') => 2,
+        _ => 0,
+    }
+}
+
+/// Length of the regex word-branch match in a token.
+///
+/// Logos takes the longest match of the two word branches; the regex takes
+/// the first that matches, `[UPPER]*[LOWER]+` before `[UPPER]+[LOWER]*`.
+/// They differ when a char in both classes (`\p{Lm}`, `\p{Lo}`, `\p{M}`)
+/// precedes an upper-only char; e.g. the regex splits `"𬺰A"` as `"𬺰"`, `"A"`.
+///
+/// ## Arguments
+/// * `token` - the token text.
+/// * `from` - where the letter classes start.
+/// * `allow_prefix` - may `token[from]` be the `[^\r\n\p{L}\p{N}]?` prefix?
+///
+/// ## Returns
+/// The match end; or `None` if neither word branch matches.
+fn o200k_regex_word_len(
+    token: &str,
+    from: usize,
+    allow_prefix: bool,
+) -> Option<usize> {
+    let prefix_end = match o200k_chars(token, from).next() {
+        Some((c, end)) if allow_prefix && c.is_prefix() => Some(end),
+        _ => None,
+    };
+    let starts = || prefix_end.into_iter().chain([from]);
+    let end = starts()
+        .find_map(|from| o200k_upper_lower_end(token, from))
+        .or_else(|| starts().find_map(|from| o200k_lower_upper_end(token, from)))?;
+    Some(end + o200k_contraction_len(&token.as_bytes()[end..]))
+}
+
+/// Where the regex ends, and how it classifies, a non-ASCII logos token.
+///
+/// `\p{M}` is in both the word classes and `[^\s\p{L}\p{N}]`; so around
+/// marks, logos longest-match can pick a different branch than the regex:
+/// * a `Punctuation` token where a word branch matches first; e.g. `"\u{301}!"`.
+/// * a `Word` token with a punctuation prefix, after a space; the regex
+///   matches ` ?[^\s\p{L}\p{N}]+` from the space, absorbing following marks.
+///   Without marks, the regex word starts after the prefix; so it cannot
+///   take the prefix as `[^\r\n\p{L}\p{N}]?`, and may end sooner.
+///
+/// ## Arguments
+/// * `tok` - the logos token.
+/// * `token` - the token text.
+/// * `after_space` - is the token preceded by an ASCII space;
+///   which the span engine merges into it?
+///
+/// ## Returns
+/// The regex match length and token; or `None` if logos agrees.
+fn o200k_regex_token(
+    tok: &O200kToken,
+    token: &str,
+    after_space: bool,
+) -> Option<(usize, O200kToken)> {
+    // From a space (leading, or merged), the space is the only prefix.
+    let (from, allow_prefix) = match token.starts_with(' ') {
+        true => (1, false),
+        false => (0, !after_space),
+    };
+    match tok {
+        O200kToken::Word => {
+            let mut chars = o200k_chars(token, 0);
+            let mut from = from;
+            if after_space && let Some((O200kChar::NonLetter, prefix_end)) = chars.next() {
+                if let Some((O200kChar::Mark, mut end)) = chars.next() {
+                    for (c, next) in chars {
+                        if c != O200kChar::Mark {
+                            break;
+                        }
+                        end = next;
+                    }
+                    return Some((end, O200kToken::Punctuation));
+                }
+                // The span engine splits the space and prefix off as
+                // punctuation; the regex word starts after them.
+                from = prefix_end;
+            }
+            o200k_regex_word_len(token, from, allow_prefix)
+                .filter(|&len| len < token.len())
+                .map(|len| (len, O200kToken::Word))
+        }
+        O200kToken::Punctuation => {
+            o200k_regex_word_len(token, from, allow_prefix).map(|len| (len, O200kToken::Word))
+        }
+        _ => None,
+    }
+}
+
+/// The logos token stream, with tokens corrected to the regex match.
+///
+/// See [`o200k_regex_token`]; after a cut, lexing restarts at the cut.
+struct O200kTokens<'a> {
+    text: &'a str,
+    base: usize,
+    lexer: logos::Lexer<'a, O200kToken>,
+}
+
+impl<'a> O200kTokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            base: 0,
+            lexer: O200kToken::lexer(text),
+        }
+    }
+}
+
+impl Iterator for O200kTokens<'_> {
+    type Item = (TokenRole, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.lexer.next()?;
+        let span = self.lexer.span();
+        let range = self.base + span.start..self.base + span.end;
+
+        let tok = match res {
+            Ok(tok) => tok,
+            Err(()) => return Some((TokenRole::Gap, range)),
+        };
+        let token = &self.text[range.clone()];
+        if !token.is_ascii() {
+            let after_space = self.text.as_bytes()[..range.start].last() == Some(&b' ');
+            if let Some((len, fixed)) = o200k_regex_token(&tok, token, after_space)
+                && 0 < len
+            {
+                if len < token.len() {
+                    let split = range.start + len;
+                    self.base = split;
+                    self.lexer = O200kToken::lexer(&self.text[split..]);
+                }
+                return Some((fixed.role(), range.start..range.start + len));
+            }
+        }
+        Some((tok.role(), range))
+    }
+}
+
 /// A [`SpanLexer`] for the `o200k_base` pattern (GPT-4o).
 ///
 /// Uses a compile-time logos DFA for word scanning.
@@ -113,18 +364,7 @@ impl SpanLexer for O200kLexer {
         offset: usize,
         f: &mut dyn FnMut(SpanRef) -> bool,
     ) -> (bool, usize) {
-        for_each_classified_span(
-            O200kToken::lexer(text).spanned().map(|(res, range)| {
-                let role = match res {
-                    Ok(tok) => tok.role(),
-                    Err(()) => TokenRole::Gap,
-                };
-                (role, range)
-            }),
-            text,
-            offset,
-            f,
-        )
+        for_each_classified_span(O200kTokens::new(text), text, offset, f)
     }
 }
 
@@ -206,6 +446,11 @@ mod tests {
             "Hello \u{4e16}\u{754c} 123",
             "don't I'll she's",
             "HELLO WORLD",
+            // Word branch order around Lo/Lm and marks.
+            "CamelCase \u{2cec0}A HTTPServer",
+            "\u{3042}L \u{30a2}S",
+            "\u{301}! \u{301}!",
+            "e\u{0301}t\u{0301}e\u{0301} \u{0301}x",
         ];
 
         for text in cases {
@@ -0,0 +1,186 @@
+//! # Qwen2 Logos Lexer
+//!
+//! Compile-time DFA lexer for the `Qwen2` pattern (Qwen2, Qwen2.5, Qwen3).
+//!
+//! Generated by `wordchipper-lexgen` from [`QWEN2_PATTERN`]; do not edit.
+//! Regenerate with `cargo run -p wordchipper-lexgen -- --write qwen2`.
+
+use logos::Logos;
+
+use super::{engine::for_each_classified_span, token_role::TokenRole};
+use crate::{
+    alloc::sync::Arc,
+    pretrained::qwen::QWEN2_PATTERN,
+    spanners::{
+        SpanRef,
+        span_lexers::{SpanLexer, accelerators::RegexAcceleratorHook},
+    },
+};
+
+/// Logos token for the [`QWEN2_PATTERN`] branches.
+///
+/// | Regex branch | Logos variant |
+/// |--------------|---------------|
+/// | `(?i:'s\|'t\|'re\|'ve\|'m\|'ll\|'d)` | `Contraction` |
+/// | `[^\r\n\p{L}\p{N}]?\p{L}+` | `Word` |
+/// | `\p{N}` | `Standalone` |
+/// | ` ?[^\s\p{L}\p{N}]+[\r\n]*` | `Punctuation` |
+/// | `\s*[\r\n]+` | `Newline` |
+/// | `\s+(?!\S)` | `Whitespace` |
+/// | `\s+` | `Whitespace` |
+#[derive(Logos, Debug, PartialEq, Clone)]
+enum Qwen2Token {
+    #[regex(r"(?i:'s|'t|'re|'ve|'m|'ll|'d)")]
+    Contraction,
+
+    #[regex(r"[^\r\n\p{L}\p{N}]?\p{L}+")]
+    Word,
+
+    #[regex(r"\p{N}")]
+    Standalone,
+
+    #[regex(r" ?[^\s\p{L}\p{N}]+[\r\n]*")]
+    Punctuation,
+
+    #[regex(r"\s*[\r\n]+")]
+    Newline,
+
+    #[regex(r"[^\S\r\n]+")]
+    Whitespace,
+}
+
+impl Qwen2Token {
+    fn role(&self) -> TokenRole {
+        match self {
+            Self::Contraction => TokenRole::Standalone,
+            Self::Word => TokenRole::Word {
+                check_contraction: true,
+            },
+            Self::Standalone => TokenRole::Standalone,
+            Self::Punctuation => TokenRole::Punctuation,
+            Self::Newline => TokenRole::Standalone,
+            Self::Whitespace => TokenRole::Whitespace,
+        }
+    }
+}
+
+/// A [`SpanLexer`] for the `Qwen2` pattern (Qwen2, Qwen2.5, Qwen3).
+///
+/// Uses a compile-time logos DFA for word scanning.
+///
+/// Only matches the regex spans; does not match the special tokens.
+#[derive(Clone, Debug)]
+pub struct Qwen2Lexer;
+
+inventory::submit! {
+    RegexAcceleratorHook::new(QWEN2_PATTERN, || Arc::new(Qwen2Lexer))
+}
+
+impl SpanLexer for Qwen2Lexer {
+    fn next_span(
+        &self,
+        text: &str,
+        offset: usize,
+    ) -> Option<(usize, usize)> {
+        let mut next_span: Option<(usize, usize)> = None;
+        self.for_each_word(text, offset, &mut |span_ref| match span_ref {
+            SpanRef::Word(r) => {
+                next_span = Some((r.start, r.end));
+                false
+            }
+            _ => true,
+        });
+        next_span
+    }
+
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        for_each_classified_span(
+            Qwen2Token::lexer(text).spanned().map(|(res, range)| {
+                let role = match res {
+                    Ok(tok) => tok.role(),
+                    Err(()) => TokenRole::Gap,
+                };
+                (role, range)
+            }),
+            text,
+            offset,
+            f,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::sync::Arc,
+        spanners::{TextSpanner, span_lexers::LexerTextSpanner},
+    };
+
+    fn spanner() -> LexerTextSpanner {
+        LexerTextSpanner::new(Arc::new(Qwen2Lexer), None)
+    }
+
+    /// The reference spanner; the compiled regex, without accelerators.
+    fn regex_spanner() -> LexerTextSpanner {
+        LexerTextSpanner::new(Arc::new(QWEN2_PATTERN.compile().unwrap()), None)
+    }
+
+    #[test]
+    fn test_logos_qwen2_accelerated() {
+        use crate::spanners::span_lexers::accelerators::get_regex_accelerator;
+
+        assert!(get_regex_accelerator(QWEN2_PATTERN.as_str()).is_some());
+    }
+
+    #[test]
+    fn test_logos_qwen2_matches_regex() {
+        let regex_spanner = regex_spanner();
+        let logos_spanner = spanner();
+
+        let cases = [
+            "Hello world",
+            "don't I'LL she's 'd 'dog",
+            "price is 1234567 dollars, \u{0663}\u{0664} \u{00bd}",
+            "caf\u{00e9} na\u{00ef}ve \u{4f60}\u{597d}\u{4e16}\u{754c}",
+            "  \nfoo  \n\n bar   ",
+            "\t 'd\t\t[A \u{2000}\u{09d7}\u{a640}",
+            "Shakespeare's \"sources,\" then read",
+            "  $400 dollars\r\n\r\n",
+        ];
+
+        for text in cases {
+            let regex_spans = regex_spanner.split_spans(text);
+            let logos_spans = logos_spanner.split_spans(text);
+            assert_eq!(
+                regex_spans, logos_spans,
+                "qwen2 mismatch for {:?}:\n  regex: {:?}\n  logos: {:?}",
+                text, regex_spans, logos_spans
+            );
+        }
+    }
+
+    #[test]
+    fn proptest_qwen2_logos_matches_regex() {
+        use proptest::prelude::*;
+
+        let regex_spanner = regex_spanner();
+        let logos_spanner = spanner();
+
+        let config = proptest::test_runner::Config::with_cases(2000);
+        proptest!(config, |(text in "\\PC{0,200}|[ \\t\\r\\na'sStTdDlLvVeE1\\u{663}\\u{bd}\\u{e9}\\u{4f60}\\u{a640}.,!$\\u{2000}\\u{301}]{0,40}")| {
+            let regex_spans = regex_spanner.split_spans(&text);
+            let logos_spans = logos_spanner.split_spans(&text);
+            prop_assert_eq!(
+                &regex_spans, &logos_spans,
+                "qwen2 mismatch for {:?}",
+                text
+            );
+        });
+    }
+}
@@ -26,6 +26,7 @@
 /// };
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TokenRole {
     /// Horizontal whitespace. Buffered; last char may split to next token.
     Whitespace,
@@ -40,6 +41,10 @@ pub enum TokenRole {
     },
     /// Token that never absorbs whitespace (digits, contractions, newlines).
     Standalone,
+    /// Token isolated by an earlier split in a pre-tokenizer sequence
+    /// (e.g. `DeepSeek` digits and CJK runs). Never absorbs whitespace, and
+    /// preceding whitespace is emitted whole rather than split.
+    Isolated,
     /// Unrecognized bytes.
     Gap,
 }
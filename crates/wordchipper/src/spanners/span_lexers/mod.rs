@@ -3,13 +3,24 @@
 pub mod logos;
 
 pub mod accelerators;
+mod cross_check;
 mod lexer_builder;
 mod lexer_spanner;
 mod span_lexer;
+mod special_lexer;
+mod stage_lexers;
+#[cfg(any(test, feature = "testing"))]
+pub mod verification;
 
+#[doc(inline)]
+pub use cross_check::*;
 #[doc(inline)]
 pub use lexer_builder::*;
 #[doc(inline)]
 pub use lexer_spanner::*;
 #[doc(inline)]
 pub use span_lexer::*;
+#[doc(inline)]
+pub use special_lexer::*;
+#[doc(inline)]
+pub use stage_lexers::*;
@@ -0,0 +1,177 @@
+//! # Special Word Lexer
+
+use aho_corasick::{AhoCorasick, MatchKind};
+
+use crate::{
+    TokenType,
+    WCError,
+    WCResult,
+    alloc::{string::ToString, vec::Vec},
+    spanners::span_lexers::SpanLexer,
+    vocab::SpecialVocab,
+};
+
+/// An Aho-Corasick [`SpanLexer`] over a fixed set of special words.
+///
+/// Matches with leftmost-longest semantics: the earliest match wins,
+/// and among matches starting at the same byte, the longest wins.
+/// Scan cost is independent of the number of special words; unlike
+/// a regex alternation, which degrades with hundreds of reserved specials.
+#[derive(Debug, Clone)]
+pub struct SpecialWordsLexer {
+    ac: AhoCorasick,
+}
+
+impl SpecialWordsLexer {
+    /// Build a new lexer over the given words.
+    ///
+    /// ## Arguments
+    /// * `words` - the special words to match, as strings or bytes.
+    ///
+    /// ## Returns
+    /// `None` if `words` is empty; and `Some(lexer)` otherwise.
+    ///
+    /// ## Errors
+    /// If the automaton cannot be built; e.g. it exceeds the size limits.
+    pub fn new<I, S>(words: I) -> WCResult<Option<Self>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        let words: Vec<S> = words.into_iter().collect();
+        if words.is_empty() {
+            return Ok(None);
+        }
+
+        let ac = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(words.iter().map(|w| w.as_ref()))
+            .map_err(|e| WCError::External(e.to_string()))?;
+
+        Ok(Some(Self { ac }))
+    }
+
+    /// Build a new lexer over the words of a [`SpecialVocab`].
+    ///
+    /// ## Returns
+    /// `None` if the vocab is empty; and `Some(lexer)` otherwise.
+    ///
+    /// ## Errors
+    /// See [`new`](Self::new).
+    pub fn from_vocab<T: TokenType>(specials: &SpecialVocab<T>) -> WCResult<Option<Self>> {
+        Self::new(specials.span_map().keys().map(|k| k.as_slice()))
+    }
+
+    /// The number of special words.
+    pub fn len(&self) -> usize {
+        self.ac.patterns_len()
+    }
+
+    /// Is the lexer empty?
+    ///
+    /// Always `false`; empty lexers are not constructed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SpanLexer for SpecialWordsLexer {
+    fn next_span(
+        &self,
+        text: &str,
+        offset: usize,
+    ) -> Option<(usize, usize)> {
+        self.ac
+            .find(&text.as_bytes()[offset..])
+            .map(|m| (offset + m.start(), offset + m.end()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{format, string::String, vec, vec::Vec},
+        spanners::SpanRef,
+    };
+
+    fn spans(
+        lexer: &SpecialWordsLexer,
+        text: &str,
+    ) -> Vec<SpanRef> {
+        let mut spans = Vec::new();
+        lexer.for_each_word(text, 0, &mut |span| {
+            spans.push(span);
+            true
+        });
+        spans
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(SpecialWordsLexer::new::<_, &str>([]).unwrap().is_none());
+        assert!(
+            SpecialWordsLexer::from_vocab(&SpecialVocab::<u32>::default())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_leftmost_longest() {
+        use SpanRef::*;
+
+        let lexer = SpecialWordsLexer::new(["<|a|>", "<|a|>b", "|a"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(lexer.len(), 3);
+        assert!(!lexer.is_empty());
+
+        // Leftmost wins over the embedded "|a".
+        assert_eq!(lexer.next_span("x<|a|>y", 0), Some((1, 6)));
+        // Longest wins at the same start.
+        assert_eq!(lexer.next_span("x<|a|>b", 0), Some((1, 7)));
+        assert_eq!(lexer.next_span("x<|a|>b", 2), Some((2, 4)));
+        assert_eq!(lexer.next_span("nothing", 0), None);
+
+        assert_eq!(
+            spans(&lexer, "é<|a|>é"),
+            vec![Gap(0..2), Word(2..7), Gap(7..9)]
+        );
+    }
+
+    #[test]
+    fn test_from_vocab() {
+        let mut specials: SpecialVocab<u32> = SpecialVocab::default();
+        let mut words: Vec<String> = Vec::new();
+        for idx in 0..300 {
+            let word = format!("<|reserved_special_token_{idx}|>");
+            specials.add_str_word(&word, 1000 + idx);
+            words.push(word);
+        }
+
+        let lexer = SpecialWordsLexer::from_vocab(&specials).unwrap().unwrap();
+        assert_eq!(lexer.len(), 300);
+
+        // "_1|>" must not shadow "_10|>" or "_100|>".
+        let text = format!("a{}b{}c", words[1], words[100]);
+        let first = lexer.next_span(&text, 0).unwrap();
+        assert_eq!(&text[first.0..first.1], words[1]);
+        let second = lexer.next_span(&text, first.1).unwrap();
+        assert_eq!(&text[second.0..second.1], words[100]);
+    }
+
+    #[test]
+    fn test_non_utf8_words() {
+        // Special words are matched by their bytes; not their lossy decoding.
+        let specials: SpecialVocab<u32> = SpecialVocab::from_map(
+            [(b"<|a|>".to_vec(), 1000), (b"<|\xff|>".to_vec(), 1001)]
+                .into_iter()
+                .collect(),
+        );
+
+        let lexer = SpecialWordsLexer::from_vocab(&specials).unwrap().unwrap();
+        assert_eq!(lexer.next_span("x<|\u{FFFD}|>y", 0), None);
+        assert_eq!(lexer.next_span("x<|a|>y", 0), Some((1, 6)));
+    }
+}
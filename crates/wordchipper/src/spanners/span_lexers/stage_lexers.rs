@@ -0,0 +1,363 @@
+//! # Stage Lexers
+//!
+//! [`SpanLexer`]s for [`SpanningStage`](crate::spanners::SpanningStage)s,
+//! and the [`ChainLexer`] which feeds each stage into the next.
+
+use core::{num::NonZeroUsize, ops::Range};
+
+use logos::Logos;
+
+use crate::{
+    alloc::{sync::Arc, vec::Vec},
+    spanners::{SpanRef, span_lexers::SpanLexer},
+    support::ranges::offset_range,
+};
+
+/// Probe for Unicode punctuation chars.
+#[derive(Logos, Debug, PartialEq, Clone)]
+enum PunctuationProbe {
+    #[regex(r"\p{Punctuation}")]
+    Punctuation,
+}
+
+/// Is the char `\p{P}`, or ASCII punctuation?
+fn is_punctuation(c: char) -> bool {
+    if c.is_ascii() {
+        return c.is_ascii_punctuation();
+    }
+    let mut buf = [0u8; 4];
+    matches!(
+        PunctuationProbe::lexer(c.encode_utf8(&mut buf)).next(),
+        Some(Ok(PunctuationProbe::Punctuation))
+    )
+}
+
+/// The char classes split out by a [`CharClassLexer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    /// Numeric chars; see [`char::is_numeric`].
+    Digit,
+
+    /// `\p{P}`, and ASCII punctuation.
+    Punctuation,
+
+    /// Whitespace chars; see [`char::is_whitespace`].
+    Whitespace,
+}
+
+impl CharClass {
+    /// Is the char in this class?
+    pub fn contains(
+        self,
+        c: char,
+    ) -> bool {
+        match self {
+            Self::Digit => c.is_numeric(),
+            Self::Punctuation => is_punctuation(c),
+            Self::Whitespace => c.is_whitespace(),
+        }
+    }
+}
+
+/// A [`SpanLexer`] which splits a [`CharClass`] out of the text.
+///
+/// Runs of chars outside the class are words; runs of chars in the class
+/// are split out as words (or gaps, if `remove` is set).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharClassLexer {
+    class: CharClass,
+    individual: bool,
+    remove: bool,
+}
+
+impl CharClassLexer {
+    /// Create a new lexer.
+    ///
+    /// ## Arguments
+    /// * `class` - the class to split out.
+    /// * `individual` - split each class char into its own span.
+    /// * `remove` - emit class spans as gaps.
+    pub fn new(
+        class: CharClass,
+        individual: bool,
+        remove: bool,
+    ) -> Self {
+        Self {
+            class,
+            individual,
+            remove,
+        }
+    }
+}
+
+impl SpanLexer for CharClassLexer {
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        let mut emit = |range: Range<usize>, in_class: bool| {
+            let range = offset_range::<usize>(range, offset);
+            f(match in_class && self.remove {
+                true => SpanRef::Gap(range),
+                false => SpanRef::Word(range),
+            })
+        };
+
+        let mut start = 0;
+        let mut run_in_class = false;
+        for (idx, c) in text.char_indices() {
+            let in_class = self.class.contains(c);
+            let split = idx > start && (in_class != run_in_class || (in_class && self.individual));
+            if split {
+                if !emit(start..idx, run_in_class) {
+                    return (false, start);
+                }
+                start = idx;
+            }
+            run_in_class = in_class;
+        }
+        if start < text.len() && !emit(start..text.len(), run_in_class) {
+            return (false, start);
+        }
+        (true, text.len())
+    }
+}
+
+/// A [`SpanLexer`] which splits text into chunks of at most `max_bytes`.
+///
+/// Chunks end at char boundaries; a char longer than `max_bytes` is its
+/// own chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkLexer {
+    max_bytes: NonZeroUsize,
+}
+
+impl ChunkLexer {
+    /// Create a new lexer.
+    pub fn new(max_bytes: NonZeroUsize) -> Self {
+        Self { max_bytes }
+    }
+}
+
+impl SpanLexer for ChunkLexer {
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        let mut start = 0;
+        while start < text.len() {
+            let mut end = (start + self.max_bytes.get()).min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            if end == start {
+                end = start + text[start..].chars().next().map_or(1, char::len_utf8);
+            }
+            if !f(SpanRef::Word(offset_range::<usize>(start..end, offset))) {
+                return (false, start);
+            }
+            start = end;
+        }
+        (true, text.len())
+    }
+}
+
+/// A [`SpanLexer`] which chains stage lexers.
+///
+/// The first lexer scans the text; each `Word` span is re-scanned by the
+/// next lexer, and so on. `Gap` spans pass through untouched.
+#[derive(Clone)]
+pub struct ChainLexer {
+    stages: Vec<Arc<dyn SpanLexer>>,
+}
+
+impl ChainLexer {
+    /// Create a new chain.
+    ///
+    /// ## Arguments
+    /// * `stages` - the lexers, in order; must not be empty.
+    ///
+    /// ## Panics
+    /// If `stages` is empty.
+    pub fn new<I>(stages: I) -> Self
+    where
+        I: IntoIterator<Item = Arc<dyn SpanLexer>>,
+    {
+        let stages: Vec<_> = stages.into_iter().collect();
+        assert!(!stages.is_empty(), "a ChainLexer needs at least one stage");
+        Self { stages }
+    }
+
+    /// Get the stage lexers.
+    pub fn stages(&self) -> &[Arc<dyn SpanLexer>] {
+        &self.stages
+    }
+}
+
+/// Scan `text` with the first stage, and re-scan its words with the rest.
+fn chain_for_each_word(
+    stages: &[Arc<dyn SpanLexer>],
+    text: &str,
+    offset: usize,
+    f: &mut dyn FnMut(SpanRef) -> bool,
+) -> (bool, usize) {
+    let (first, rest) = stages.split_first().expect("non-empty stages");
+    if rest.is_empty() {
+        return first.for_each_word(text, offset, f);
+    }
+
+    // Where a nested stage halted; relative to `text`.
+    let mut halted_at: Option<usize> = None;
+    let result = first.for_each_word(text, offset, &mut |span| match span {
+        SpanRef::Word(range) => {
+            let start = range.start - offset;
+            let word = &text[start..range.end - offset];
+            let (completed, consumed) = chain_for_each_word(rest, word, range.start, f);
+            if !completed {
+                halted_at = Some(start + consumed);
+            }
+            completed
+        }
+        span => f(span),
+    });
+
+    match halted_at {
+        Some(consumed) => (false, consumed),
+        None => result,
+    }
+}
+
+impl SpanLexer for ChainLexer {
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {
+        chain_for_each_word(&self.stages, text, offset, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{vec, vec::Vec},
+        support::regex::{RegexPattern, RegexWrapper},
+    };
+
+    fn words(
+        lexer: &dyn SpanLexer,
+        text: &str,
+    ) -> Vec<SpanRef> {
+        let mut spans = Vec::new();
+        let (completed, consumed) = lexer.for_each_word(text, 0, &mut |span| {
+            spans.push(span);
+            true
+        });
+        assert_eq!((completed, consumed), (true, text.len()));
+        spans
+    }
+
+    fn regex(pattern: &str) -> Arc<dyn SpanLexer> {
+        let re: RegexWrapper = RegexPattern::from(pattern).compile().unwrap();
+        Arc::new(re)
+    }
+
+    #[test]
+    fn test_char_class_lexer() {
+        use SpanRef::*;
+
+        let digits = CharClassLexer::new(CharClass::Digit, false, false);
+        assert_eq!(
+            words(&digits, "ab123c4"),
+            vec![Word(0..2), Word(2..5), Word(5..6), Word(6..7)]
+        );
+
+        let digits = CharClassLexer::new(CharClass::Digit, true, false);
+        assert_eq!(
+            words(&digits, "a12"),
+            vec![Word(0..1), Word(1..2), Word(2..3)]
+        );
+
+        let punct = CharClassLexer::new(CharClass::Punctuation, true, false);
+        assert_eq!(
+            words(&punct, "hi!?\u{201c}$"),
+            vec![Word(0..2), Word(2..3), Word(3..4), Word(4..7), Word(7..8)]
+        );
+
+        let ws = CharClassLexer::new(CharClass::Whitespace, false, true);
+        assert_eq!(
+            words(&ws, " a \t b"),
+            vec![Gap(0..1), Word(1..2), Gap(2..5), Word(5..6)]
+        );
+
+        // Early halt.
+        let mut spans = Vec::new();
+        let result = ws.for_each_word(" a b", 10, &mut |span| {
+            spans.push(span);
+            spans.len() < 4
+        });
+        assert_eq!(result, (false, 3));
+        assert_eq!(
+            spans,
+            vec![Gap(10..11), Word(11..12), Gap(12..13), Word(13..14)]
+        );
+    }
+
+    #[test]
+    fn test_chunk_lexer() {
+        use SpanRef::*;
+
+        let chunks = ChunkLexer::new(NonZeroUsize::new(3).unwrap());
+        assert_eq!(
+            words(&chunks, "abcdefg"),
+            vec![Word(0..3), Word(3..6), Word(6..7)]
+        );
+        // Chunks end at char boundaries; wide chars stand alone.
+        assert_eq!(
+            words(&chunks, "a\u{e9}\u{1f600}b"),
+            vec![Word(0..3), Word(3..7), Word(7..8)]
+        );
+        assert_eq!(words(&chunks, ""), vec![]);
+    }
+
+    #[test]
+    fn test_chain_lexer() {
+        use SpanRef::*;
+
+        let chain = ChainLexer::new([
+            regex(r"\S+"),
+            Arc::new(CharClassLexer::new(CharClass::Digit, true, false)) as Arc<dyn SpanLexer>,
+            Arc::new(ChunkLexer::new(NonZeroUsize::new(2).unwrap())),
+        ]);
+        assert_eq!(chain.stages().len(), 3);
+
+        let text = "abcde 12 x";
+        assert_eq!(
+            words(&chain, text),
+            vec![
+                Word(0..2),
+                Word(2..4),
+                Word(4..5),
+                Gap(5..6),
+                Word(6..7),
+                Word(7..8),
+                Gap(8..9),
+                Word(9..10),
+            ]
+        );
+
+        // A nested stage halting reports the bytes consumed so far.
+        let mut count = 0;
+        let result = chain.for_each_word(text, 0, &mut |_| {
+            count += 1;
+            count < 3
+        });
+        assert_eq!(result, (false, 4));
+    }
+}
@@ -0,0 +1,382 @@
+//! # Accelerator Verification
+//!
+//! Differential checks of regex accelerators against the compiled regex.
+//!
+//! [`get_regex_accelerator`] trusts that an accelerator matches its pattern
+//! exactly, by comparing the pattern strings; these check the spans.
+//!
+//! ```rust,ignore
+//! use wordchipper::{
+//!     pretrained::openai::OA_O200K_BASE_PATTERN,
+//!     spanners::span_lexers::verification::verify_accelerator_generated,
+//! };
+//!
+//! let divergence = verify_accelerator_generated(OA_O200K_BASE_PATTERN, 0, 100).unwrap();
+//! if let Some(divergence) = divergence {
+//!     println!("{divergence}");
+//! }
+//! ```
+
+use crate::{
+    WCError,
+    WCResult,
+    alloc::{format, string::String, vec::Vec},
+    prelude::*,
+    spanners::span_lexers::{
+        LexerDivergence,
+        SpanLexer,
+        accelerators::get_regex_accelerator,
+        find_lexer_divergence,
+    },
+    support::{
+        random::SplitMix64,
+        regex::{RegexPattern, RegexWrapper},
+    },
+};
+
+/// Fixed inputs for [`verify_accelerator_corpus`].
+///
+/// Covers the whitespace, contraction, digit, script and mark
+/// interactions where accelerators and regexes tend to diverge.
+pub const LEXER_TEST_CORPUS: &[&str] = &[
+    "",
+    "Hello world",
+    "don't I'LL she's 'd 'dog 'The",
+    "price is 1234567 dollars, \u{0663}\u{0664} \u{00bd}",
+    "caf\u{00e9} na\u{00ef}ve \u{4f60}\u{597d}\u{4e16}\u{754c} \u{3053}\u{3093}",
+    "  \nfoo  \n\n bar   ",
+    "\n\t",
+    "\t 'd\t\t[A \u{2000}\u{09d7}\u{a640}",
+    "Shakespeare's \"sources,\" then read",
+    "  $400 dollars\r\n\r\n",
+    " \u{2014}hello world",
+    "CamelCase \u{2cec0}A HTTPServer",
+    "e\u{0301}t\u{0301}e\u{0301} \u{0301}x",
+    "tabs\tand\u{00a0}nbsp\u{3000}ideographic",
+];
+
+/// Chars over-represented by [`generate_lexer_inputs`].
+const EDGE_CHARS: &[char] = &[
+    ' ',
+    ' ',
+    ' ',
+    '\t',
+    '\n',
+    '\r',
+    '\u{a0}',
+    '\u{2000}',
+    '\u{3000}',
+    '\'',
+    '\'',
+    's',
+    't',
+    'd',
+    'm',
+    'l',
+    'v',
+    'e',
+    'r',
+    'S',
+    'T',
+    'L',
+    'a',
+    'Z',
+    '0',
+    '7',
+    '\u{663}',
+    '\u{bd}',
+    '\u{e9}',
+    '\u{301}',
+    '\u{9d7}',
+    '\u{4f60}',
+    '\u{3042}',
+    '\u{30a2}',
+    '\u{a640}',
+    '\u{1d400}',
+    '\u{2cec0}',
+    '.',
+    ',',
+    '!',
+    '$',
+    '[',
+    '"',
+    '\u{2014}',
+    '\u{1f600}',
+];
+
+/// Generate deterministic pseudo-random lexer inputs.
+///
+/// Mostly drawn from a set of edge-case chars (whitespace variants,
+/// contraction letters, digits, marks, CJK, astral letters);
+/// with some uniformly random scalar values mixed in.
+///
+/// ## Arguments
+/// * `seed` - the generator seed.
+/// * `count` - the number of inputs.
+/// * `max_chars` - the max chars per input.
+pub fn generate_lexer_inputs(
+    seed: u64,
+    count: usize,
+    max_chars: usize,
+) -> Vec<String> {
+    let mut rng = SplitMix64::new(seed);
+    (0..count)
+        .map(|_| {
+            let len = rng.below(max_chars + 1);
+            (0..len)
+                .map(|_| match rng.below(4) {
+                    0 => loop {
+                        if let Some(c) = char::from_u32(rng.below(0x3_0000) as u32)
+                            && !c.is_control()
+                        {
+                            break c;
+                        }
+                    },
+                    _ => EDGE_CHARS[rng.below(EDGE_CHARS.len())],
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Shrink a divergent input, by deleting chars while the lexers still diverge.
+///
+/// ## Returns
+/// The divergence on the shrunk input.
+pub fn shrink_divergence(
+    reference: &dyn SpanLexer,
+    candidate: &dyn SpanLexer,
+    divergence: LexerDivergence,
+) -> LexerDivergence {
+    let mut best = divergence;
+    let mut idx = 0;
+    while let Some((pos, c)) = best.text.char_indices().nth(idx) {
+        let mut text = best.text.clone();
+        text.replace_range(pos..pos + c.len_utf8(), "");
+        match find_lexer_divergence(reference, candidate, &text) {
+            Some(smaller) => best = smaller,
+            None => idx += 1,
+        }
+    }
+    best
+}
+
+/// Verify a candidate lexer against a reference lexer.
+///
+/// ## Arguments
+/// * `reference` - the trusted lexer; e.g. the compiled regex.
+/// * `candidate` - the lexer under test; e.g. an accelerator.
+/// * `inputs` - the texts to scan.
+///
+/// ## Returns
+/// The first divergence, shrunk by [`shrink_divergence`];
+/// or `None` if the lexers agree on all inputs.
+pub fn verify_lexer<I, S>(
+    reference: &dyn SpanLexer,
+    candidate: &dyn SpanLexer,
+    inputs: I,
+) -> Option<LexerDivergence>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    inputs
+        .into_iter()
+        .find_map(|text| find_lexer_divergence(reference, candidate, text.as_ref()))
+        .map(|divergence| shrink_divergence(reference, candidate, divergence))
+}
+
+/// Verify the registered accelerator for a pattern against the compiled regex.
+///
+/// ## Arguments
+/// * `pattern` - the accelerated pattern.
+/// * `inputs` - the texts to scan.
+///
+/// ## Returns
+/// * `Ok(None)` - the accelerator agrees with the regex on all inputs.
+/// * `Ok(Some(divergence))` - the first (shrunk) divergence.
+/// * `Err(WCError::Unsupported(_))` - the pattern has no accelerator.
+/// * `Err(WCError::Parse(_))` - the pattern does not compile.
+pub fn verify_accelerator<P, I, S>(
+    pattern: P,
+    inputs: I,
+) -> WCResult<Option<LexerDivergence>>
+where
+    P: Into<RegexPattern>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let pattern: RegexPattern = pattern.into();
+    let accelerator = get_regex_accelerator(pattern.as_str()).ok_or_else(|| {
+        WCError::Unsupported(format!("no accelerator for pattern {:?}", pattern.as_str()))
+    })?;
+    let regex: RegexWrapper = pattern
+        .compile()
+        .map_err(|e| WCError::Parse(format!("{e}")))?;
+
+    Ok(verify_lexer(&regex, &*accelerator, inputs))
+}
+
+/// Verify an accelerator over the [`LEXER_TEST_CORPUS`].
+///
+/// See [`verify_accelerator`].
+pub fn verify_accelerator_corpus<P: Into<RegexPattern>>(
+    pattern: P
+) -> WCResult<Option<LexerDivergence>> {
+    verify_accelerator(pattern, LEXER_TEST_CORPUS)
+}
+
+/// Verify an accelerator over the [`LEXER_TEST_CORPUS`], and generated inputs.
+///
+/// See [`verify_accelerator`] and [`generate_lexer_inputs`].
+///
+/// ## Arguments
+/// * `pattern` - the accelerated pattern.
+/// * `seed` - the input generator seed.
+/// * `count` - the number of generated inputs.
+pub fn verify_accelerator_generated<P: Into<RegexPattern>>(
+    pattern: P,
+    seed: u64,
+    count: usize,
+) -> WCResult<Option<LexerDivergence>> {
+    let inputs = LEXER_TEST_CORPUS
+        .iter()
+        .map(|s| s.to_string())
+        .chain(generate_lexer_inputs(seed, count, 48));
+    verify_accelerator(pattern, inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::sync::Arc,
+        pretrained::{
+            deepseek::DEEPSEEK_V3_PATTERN,
+            meta::META_LLAMA3_PATTERN,
+            openai::{OA_CL100K_BASE_PATTERN, OA_O200K_BASE_PATTERN, OA_R50K_BASE_PATTERN},
+            qwen::QWEN2_PATTERN,
+        },
+        spanners::{SpanRef, span_lexers::ChainLexer},
+    };
+
+    #[test]
+    fn test_generate_lexer_inputs() {
+        let a = generate_lexer_inputs(7, 20, 16);
+        assert_eq!(a.len(), 20);
+        assert!(a.iter().all(|s| s.chars().count() <= 16));
+        assert_eq!(a, generate_lexer_inputs(7, 20, 16));
+        assert_ne!(a, generate_lexer_inputs(8, 20, 16));
+    }
+
+    #[test]
+    fn test_verify_accelerators() {
+        for pattern in [
+            OA_R50K_BASE_PATTERN,
+            OA_CL100K_BASE_PATTERN,
+            OA_O200K_BASE_PATTERN,
+            META_LLAMA3_PATTERN,
+            QWEN2_PATTERN,
+            DEEPSEEK_V3_PATTERN,
+        ] {
+            assert_eq!(verify_accelerator_corpus(pattern).unwrap(), None);
+            for seed in 0..4 {
+                let divergence = verify_accelerator_generated(pattern, seed, 500).unwrap();
+                assert!(divergence.is_none(), "{}", divergence.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_accelerator_errors() {
+        assert!(matches!(
+            verify_accelerator_corpus(r"\p{L}+"),
+            Err(WCError::Unsupported(_))
+        ));
+    }
+
+    /// Splits out regex matches; the text between them is kept as words.
+    ///
+    /// As a `Split` pre-tokenizer step with `Isolated` behavior.
+    struct IsolateLexer(RegexWrapper);
+
+    impl SpanLexer for IsolateLexer {
+        fn for_each_word(
+            &self,
+            text: &str,
+            offset: usize,
+            f: &mut dyn FnMut(SpanRef) -> bool,
+        ) -> (bool, usize) {
+            let mut pos = 0;
+            while pos < text.len() {
+                let (start, end) = self
+                    .0
+                    .next_span(text, pos)
+                    .unwrap_or((text.len(), text.len()));
+                for range in [pos..start, start..end] {
+                    if !range.is_empty()
+                        && !f(SpanRef::Word(range.start + offset..range.end + offset))
+                    {
+                        return (false, range.start);
+                    }
+                }
+                pos = end;
+            }
+            (true, text.len())
+        }
+    }
+
+    #[test]
+    fn test_deepseek_v3_folded_pattern() {
+        // The `DeepSeek` V3 pre-tokenizer sequence, which the pattern folds.
+        let isolate = |pattern: &str| -> Arc<dyn SpanLexer> {
+            Arc::new(IsolateLexer(RegexPattern::from(pattern).compile().unwrap()))
+        };
+        let words = isolate(concat!(
+            r"[!-/:-@\[-`{-~][A-Za-z]+",
+            r"|[^\r\n\p{L}\p{P}\p{S}]?[\p{L}\p{M}]+",
+            r"| ?[\p{P}\p{S}]+[\r\n]*",
+            r"|\s*[\r\n]+",
+            r"|\s+(?!\S)",
+            r"|\s+",
+        ));
+        let sequence =
+            ChainLexer::new([isolate(r"\p{N}{1,3}"), isolate(r"[一-龥぀-ゟ゠-ヿ]+"), words]);
+
+        let folded: RegexWrapper = RegexPattern::from(DEEPSEEK_V3_PATTERN).compile().unwrap();
+        let inputs = LEXER_TEST_CORPUS
+            .iter()
+            .map(|s| s.to_string())
+            .chain((0..4).flat_map(|seed| generate_lexer_inputs(seed, 500, 48)));
+        let divergence = verify_lexer(&sequence, &folded, inputs);
+        assert!(divergence.is_none(), "{}", divergence.unwrap());
+    }
+
+    /// Matches up to the first `!`.
+    struct BangLexer;
+
+    impl SpanLexer for BangLexer {
+        fn next_span(
+            &self,
+            text: &str,
+            offset: usize,
+        ) -> Option<(usize, usize)> {
+            let rest = &text[offset..];
+            let end = rest.find('!').unwrap_or(rest.len());
+            (end > 0).then_some((offset, offset + end))
+        }
+    }
+
+    #[test]
+    fn test_verify_lexer_shrinks() {
+        let regex: RegexWrapper = RegexPattern::from(r"[^!]+").compile().unwrap();
+        assert_eq!(verify_lexer(&regex, &BangLexer, ["abc", "def"]), None);
+
+        // `BangLexer` stops matching at the first `!`; so the rest is one gap.
+        let divergence = verify_lexer(&regex, &BangLexer, ["ok", "hello there!world"]).unwrap();
+        assert_eq!(divergence.text, "!d");
+        assert_eq!(divergence.index, 0);
+        assert_eq!(divergence.expected, Some(SpanRef::Gap(0..1)));
+        assert_eq!(divergence.actual, Some(SpanRef::Gap(0..2)));
+    }
+}
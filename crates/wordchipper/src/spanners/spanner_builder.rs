@@ -5,11 +5,21 @@ use core::num::NonZeroUsize;
 use crate::{
     TokenType,
     UnifiedTokenVocab,
-    alloc::sync::Arc,
+    alloc::{sync::Arc, vec::Vec},
     spanners::{
         TextSpanner,
         TextSpanningConfig,
-        span_lexers::{LexerTextSpanner, SpanLexer, build_regex_lexer},
+        span_lexers::{
+            ChainLexer,
+            ChunkLexer,
+            CrossCheckLexer,
+            LexerTextSpanner,
+            SpanLexer,
+            SpecialWordsLexer,
+            accelerators::get_regex_accelerator,
+            build_regex_lexer,
+            build_stage_lexer,
+        },
     },
 };
 
@@ -17,13 +27,16 @@ use crate::{
 ///
 /// The primary tuning knobs here are:
 /// * [`set_concurrent`](Self::set_concurrent) - whether to request a concurrency support.
+/// * [`set_max_span_bytes`](Self::set_max_span_bytes) - a cap on word span length.
 #[derive(Clone, PartialEq)]
 pub struct TextSpannerBuilder<T: TokenType> {
     config: TextSpanningConfig<T>,
 
     accelerated_lexers: bool,
+    cross_check_lexers: bool,
     concurrent: bool,
     max_pool: Option<NonZeroUsize>,
+    max_span_bytes: Option<NonZeroUsize>,
 }
 
 impl<T: TokenType> TextSpannerBuilder<T> {
@@ -44,8 +57,10 @@ impl<T: TokenType> TextSpannerBuilder<T> {
         Self {
             config,
             accelerated_lexers: true,
+            cross_check_lexers: false,
             concurrent: true,
             max_pool: None,
+            max_span_bytes: None,
         }
     }
 
@@ -88,6 +103,38 @@ impl<T: TokenType> TextSpannerBuilder<T> {
         self
     }
 
+    /// Are accelerated lexers cross-checked against the regex?
+    ///
+    /// When enabled, and an accelerated lexer is used; every scan
+    /// also runs the compiled regex, logs any divergence, and
+    /// uses the regex spans. See [`CrossCheckLexer`].
+    pub fn cross_check_lexers(&self) -> bool {
+        self.cross_check_lexers
+    }
+
+    /// Set whether accelerated lexers are cross-checked against the regex.
+    ///
+    /// This is a debug mode; it roughly doubles spanning cost.
+    /// See [`CrossCheckLexer`].
+    pub fn set_cross_check_lexers(
+        &mut self,
+        cross_check_lexers: bool,
+    ) {
+        self.cross_check_lexers = cross_check_lexers;
+    }
+
+    /// Set whether accelerated lexers are cross-checked against the regex.
+    ///
+    /// This is a debug mode; it roughly doubles spanning cost.
+    /// See [`CrossCheckLexer`].
+    pub fn with_cross_check_lexers(
+        mut self,
+        cross_check_lexers: bool,
+    ) -> Self {
+        self.set_cross_check_lexers(cross_check_lexers);
+        self
+    }
+
     /// Get whether the decoder should use parallel decoding.
     ///
     /// Enabling concurrency will select an encoder which plays
@@ -141,25 +188,102 @@ impl<T: TokenType> TextSpannerBuilder<T> {
         self
     }
 
+    /// Get the max word span length, in bytes.
+    ///
+    /// When set, longer word spans are split into chunks of at most
+    /// this many bytes, at char boundaries; see [`ChunkLexer`].
+    pub fn max_span_bytes(&self) -> Option<NonZeroUsize> {
+        self.max_span_bytes
+    }
+
+    /// Set the max word span length, in bytes.
+    ///
+    /// Bounds the per-span encode cost of adversarial input (base64 blobs,
+    /// long runs of one char). The split is deterministic; but tokens never
+    /// span a chunk boundary, so oversized spans may encode differently.
+    pub fn set_max_span_bytes<M>(
+        &mut self,
+        max_span_bytes: M,
+    ) where
+        M: Into<Option<NonZeroUsize>>,
+    {
+        self.max_span_bytes = max_span_bytes.into();
+    }
+
+    /// Set the max word span length, in bytes.
+    ///
+    /// See [`set_max_span_bytes`](Self::set_max_span_bytes).
+    pub fn with_max_span_bytes<M>(
+        mut self,
+        max_span_bytes: M,
+    ) -> Self
+    where
+        M: Into<Option<NonZeroUsize>>,
+    {
+        self.set_max_span_bytes(max_span_bytes);
+        self
+    }
+
     /// Build a [`TextSpanner`] with the current configuration.
     ///
     /// Automatically selects the fastest available word lexer for the
     /// configured pattern (e.g. a logos DFA accelerator if the `logos`
     /// feature is enabled and the pattern is recognized).
     /// Falls back to the compiled regex otherwise.
-    /// The special lexer (if any) is always built from the regex pattern.
+    /// The special lexer (if any) is a [`SpecialWordsLexer`].
+    ///
+    /// With [`cross_check_lexers`](Self::cross_check_lexers), an
+    /// accelerated word lexer is wrapped in a [`CrossCheckLexer`].
+    ///
+    /// Configured [`stages`](TextSpanningConfig::stages) are chained
+    /// after the word lexer with a [`ChainLexer`]; followed by a
+    /// [`ChunkLexer`], when [`max_span_bytes`](Self::max_span_bytes) is set.
+    ///
+    /// ## Panics
+    /// If the special words exceed the Aho-Corasick automaton limits.
     pub fn build(&self) -> Arc<dyn TextSpanner> {
-        let word_lexer: Arc<dyn SpanLexer> = build_regex_lexer(
-            self.config().pattern().clone(),
-            self.accelerated_lexers,
-            self.concurrent,
-            self.max_pool,
-        );
-        let special_lexer: Option<Arc<dyn SpanLexer>> = self
+        let pattern = self.config().pattern().clone();
+        let accelerator = match self.accelerated_lexers && self.cross_check_lexers {
+            true => get_regex_accelerator(pattern.as_str()),
+            false => None,
+        };
+        let word_lexer: Arc<dyn SpanLexer> = match accelerator {
+            Some(candidate) => {
+                let reference = build_regex_lexer(pattern, false, self.concurrent, self.max_pool);
+                Arc::new(CrossCheckLexer::new(reference, candidate))
+            }
+            None => build_regex_lexer(
+                pattern,
+                self.accelerated_lexers,
+                self.concurrent,
+                self.max_pool,
+            ),
+        };
+        let mut stage_lexers: Vec<Arc<dyn SpanLexer>> = self
             .config
-            .specials()
-            .special_pattern()
-            .map(|pattern| build_regex_lexer(pattern, false, self.concurrent, self.max_pool));
+            .stages()
+            .iter()
+            .map(|stage| {
+                build_stage_lexer(
+                    stage.clone(),
+                    self.accelerated_lexers,
+                    self.concurrent,
+                    self.max_pool,
+                )
+            })
+            .collect();
+        if let Some(max_span_bytes) = self.max_span_bytes {
+            stage_lexers.push(Arc::new(ChunkLexer::new(max_span_bytes)));
+        }
+        let word_lexer = match stage_lexers.is_empty() {
+            true => word_lexer,
+            false => Arc::new(ChainLexer::new(
+                core::iter::once(word_lexer).chain(stage_lexers),
+            )),
+        };
+        let special_lexer = SpecialWordsLexer::from_vocab(self.config.specials())
+            .expect("failed to build the special words lexer")
+            .map(|lexer| Arc::new(lexer) as Arc<dyn SpanLexer>);
 
         Arc::new(LexerTextSpanner::new(word_lexer, special_lexer))
     }
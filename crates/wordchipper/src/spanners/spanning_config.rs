@@ -1,5 +1,12 @@
 //! # Text Spanner Configuration
-use crate::{TokenType, WCResult, support::regex::RegexPattern, vocab::SpecialVocab};
+use crate::{
+    TokenType,
+    WCResult,
+    alloc::vec::Vec,
+    spanners::{SpanningStage, TextNormalizer},
+    support::regex::RegexPattern,
+    vocab::SpecialVocab,
+};
 
 /// Description of text spanners configuration.
 ///
@@ -14,6 +21,12 @@ pub struct TextSpanningConfig<T: TokenType> {
 
     /// Special tokens vocabulary.
     specials: SpecialVocab<T>,
+
+    /// Normalizer applied to text before spanning.
+    normalizer: TextNormalizer,
+
+    /// Stages chained after the word pattern.
+    stages: Vec<SpanningStage>,
 }
 
 impl<T: TokenType> From<RegexPattern> for TextSpanningConfig<T> {
@@ -36,6 +49,8 @@ impl<T: TokenType> TextSpanningConfig<T> {
         Self {
             pattern: pattern.into(),
             specials: SpecialVocab::default(),
+            normalizer: TextNormalizer::default(),
+            stages: Vec::new(),
         }
     }
 
@@ -91,11 +106,61 @@ impl<T: TokenType> TextSpanningConfig<T> {
         }
     }
 
+    /// Set the text normalizer.
+    ///
+    /// ## Arguments
+    /// * `normalizer` - The normalizer applied before spanning.
+    pub fn with_normalizer<N>(
+        self,
+        normalizer: N,
+    ) -> Self
+    where
+        N: Into<TextNormalizer>,
+    {
+        Self {
+            normalizer: normalizer.into(),
+            ..self
+        }
+    }
+
+    /// Set the stages chained after the word pattern.
+    ///
+    /// Each stage re-splits the `Word` spans of the stage before it.
+    ///
+    /// ## Arguments
+    /// * `stages` - The stages, in order.
+    pub fn with_stages<I>(
+        self,
+        stages: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = SpanningStage>,
+    {
+        Self {
+            stages: stages.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Append a stage to the chain.
+    ///
+    /// ## Arguments
+    /// * `stage` - The stage to append.
+    pub fn with_stage(
+        mut self,
+        stage: SpanningStage,
+    ) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
     /// Convert to a different token type.
     pub fn to_token_type<G: TokenType>(&self) -> WCResult<TextSpanningConfig<G>> {
         Ok(TextSpanningConfig::<G> {
             pattern: self.pattern.clone(),
             specials: self.specials.to_token_type()?,
+            normalizer: self.normalizer.clone(),
+            stages: self.stages.clone(),
         })
     }
 
@@ -109,6 +174,16 @@ impl<T: TokenType> TextSpanningConfig<T> {
         &self.specials
     }
 
+    /// Get the text normalizer.
+    pub fn normalizer(&self) -> &TextNormalizer {
+        &self.normalizer
+    }
+
+    /// Get the stages chained after the word pattern.
+    pub fn stages(&self) -> &[SpanningStage] {
+        &self.stages
+    }
+
     /// Get the special pattern, if any.
     pub fn special_pattern(&self) -> Option<RegexPattern> {
         self.specials.special_pattern()
@@ -123,7 +198,11 @@ impl<T: TokenType> TextSpanningConfig<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{alloc::string::ToString, vocab::SpecialVocab};
+    use crate::{
+        alloc::string::ToString,
+        spanners::{NormalizerStep, SpanningStage},
+        vocab::SpecialVocab,
+    };
 
     #[test]
     fn test_from_pattern() {
@@ -147,5 +226,18 @@ mod tests {
 
         let config = config.with_specials(specials.clone());
         assert_eq!(config.specials(), &specials);
+
+        assert!(config.normalizer().is_empty());
+        let config = config.with_normalizer([NormalizerStep::Nfc]);
+        assert_eq!(config.normalizer().steps(), &[NormalizerStep::Nfc]);
+
+        assert!(config.stages().is_empty());
+        let config = config
+            .with_stages([SpanningStage::Punctuation])
+            .with_stage(SpanningStage::chunk(16));
+        assert_eq!(
+            config.stages(),
+            &[SpanningStage::Punctuation, SpanningStage::chunk(16)]
+        );
     }
 }
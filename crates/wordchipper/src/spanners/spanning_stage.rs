@@ -0,0 +1,74 @@
+//! # Spanning Stages
+//!
+//! Declarative pre-tokenizer stages, chained after the word pattern.
+//!
+//! Each stage re-splits the `Word` spans of the stage before it;
+//! `Gap` spans pass through untouched. See
+//! [`TextSpanningConfig::with_stages`](crate::spanners::TextSpanningConfig::with_stages).
+
+use core::num::NonZeroUsize;
+
+use crate::support::regex::RegexPattern;
+
+/// A pre-tokenizer stage applied to the spans of the preceding stage.
+///
+/// ## Style Hints
+///
+/// Instance names should prefer `stage`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SpanningStage {
+    /// Split by a regex pattern.
+    ///
+    /// As with the word pattern; matches are words, unmatched text is a gap.
+    Pattern(RegexPattern),
+
+    /// Isolate numeric chars from the surrounding text.
+    Digits {
+        /// Split each digit into its own span; rather than each run.
+        individual: bool,
+    },
+
+    /// Isolate each punctuation char into its own span.
+    ///
+    /// Punctuation is `\p{P}`, and ASCII punctuation (which includes
+    /// symbols such as `$` and `+`).
+    Punctuation,
+
+    /// Split out each run of whitespace.
+    Whitespace {
+        /// Emit whitespace runs as gaps; rather than as words.
+        remove: bool,
+    },
+
+    /// Split spans longer than `max_bytes` into chunks, at char boundaries.
+    ///
+    /// A char longer than `max_bytes` is its own chunk.
+    Chunk {
+        /// The max chunk length, in bytes.
+        max_bytes: NonZeroUsize,
+    },
+}
+
+impl From<RegexPattern> for SpanningStage {
+    fn from(pattern: RegexPattern) -> Self {
+        Self::Pattern(pattern)
+    }
+}
+
+impl SpanningStage {
+    /// Build a [`SpanningStage::Pattern`] stage.
+    pub fn pattern<P: Into<RegexPattern>>(pattern: P) -> Self {
+        Self::Pattern(pattern.into())
+    }
+
+    /// Build a [`SpanningStage::Chunk`] stage.
+    ///
+    /// ## Panics
+    /// If `max_bytes` is zero.
+    pub fn chunk(max_bytes: usize) -> Self {
+        Self::Chunk {
+            max_bytes: NonZeroUsize::new(max_bytes).expect("max_bytes must be non-zero"),
+        }
+    }
+}
@@ -0,0 +1,790 @@
+//! # Text Normalizer
+//!
+//! [`TextNormalizer`] rewrites text before it is spanned; e.g. NFC, NFKC,
+//! lowercasing or whitespace collapsing; as some vocabularies assume.
+//!
+//! [`NormalizedText`] keeps the alignment of each normalized byte to the
+//! original text; so offsets in the normalized text can be mapped back.
+
+use core::ops::Range;
+
+use logos::Logos;
+use unicode_normalization::{
+    IsNormalized,
+    UnicodeNormalization,
+    char::canonical_combining_class,
+    is_nfc_quick,
+    is_nfd_quick,
+    is_nfkc_quick,
+    is_nfkd_quick,
+};
+
+use crate::{
+    alloc::{borrow::Cow, string::String, vec::Vec},
+    spanners::span_lexers::SpanLexer,
+};
+
+/// Probe for the general categories the char filters test.
+#[derive(Logos, Debug, PartialEq, Clone)]
+enum CategoryProbe {
+    #[regex(r"\p{Nonspacing_Mark}")]
+    NonspacingMark,
+
+    #[regex(r"\p{Other}")]
+    Other,
+}
+
+/// Probe the general category of a non-ASCII char.
+fn probe_category(c: char) -> Option<CategoryProbe> {
+    let mut buf = [0u8; 4];
+    CategoryProbe::lexer(c.encode_utf8(&mut buf)).next()?.ok()
+}
+
+/// Is the char a nonspacing mark (`\p{Mn}`)?
+fn is_nonspacing_mark(c: char) -> bool {
+    !c.is_ascii() && probe_category(c) == Some(CategoryProbe::NonspacingMark)
+}
+
+/// Is the char in the "other" categories (`\p{C}`); but not `\t`, `\n`, `\r`?
+fn is_other(c: char) -> bool {
+    match c {
+        '\t' | '\n' | '\r' => false,
+        c if c.is_ascii() => c.is_ascii_control(),
+        c => probe_category(c) == Some(CategoryProbe::Other),
+    }
+}
+
+/// A single normalization step of a [`TextNormalizer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NormalizerStep {
+    /// Unicode canonical composition (NFC).
+    Nfc,
+
+    /// Unicode compatibility composition (NFKC).
+    Nfkc,
+
+    /// Unicode canonical decomposition (NFD).
+    Nfd,
+
+    /// Unicode compatibility decomposition (NFKD).
+    Nfkd,
+
+    /// Lowercase every char.
+    Lowercase,
+
+    /// Remove nonspacing marks (`\p{Mn}`); as BERT's `strip_accents`.
+    ///
+    /// Only strips accents from decomposed text; so usually follows
+    /// [`Nfd`](Self::Nfd) or [`Nfkd`](Self::Nfkd). Spacing and enclosing
+    /// marks (e.g. Devanagari vowel signs) are kept.
+    StripAccents,
+
+    /// Remove control chars, and replace each whitespace char with an
+    /// ASCII space; as BERT's `clean_text`.
+    ///
+    /// Removes `\0`, `\u{FFFD}` and the "other" categories (`\p{C}`;
+    /// controls, format chars, private use, unassigned); except `\t`, `\n`
+    /// and `\r`, which are whitespace.
+    CleanText,
+
+    /// Replace each run of whitespace with a single ASCII space.
+    CollapseWhitespace,
+
+    /// Remove leading and trailing whitespace.
+    Trim,
+
+    /// Prepend a literal string to non-empty text.
+    ///
+    /// E.g. the sentencepiece "dummy prefix" space.
+    Prepend {
+        /// The literal to prepend.
+        content: String,
+    },
+
+    /// Replace each occurrence of a literal string, left to right.
+    ///
+    /// An empty `pattern` matches nothing.
+    Replace {
+        /// The literal to replace.
+        pattern: String,
+
+        /// The replacement.
+        content: String,
+    },
+}
+
+impl NormalizerStep {
+    /// Build a [`NormalizerStep::Replace`] step.
+    pub fn replace<P, C>(
+        pattern: P,
+        content: C,
+    ) -> Self
+    where
+        P: Into<String>,
+        C: Into<String>,
+    {
+        Self::Replace {
+            pattern: pattern.into(),
+            content: content.into(),
+        }
+    }
+
+    /// Build a [`NormalizerStep::Prepend`] step.
+    pub fn prepend<C>(content: C) -> Self
+    where
+        C: Into<String>,
+    {
+        Self::Prepend {
+            content: content.into(),
+        }
+    }
+
+    /// Is the text already unchanged by this step; by a quick check?
+    fn is_noop(
+        &self,
+        text: &str,
+    ) -> bool {
+        match self {
+            Self::Nfc => is_nfc_quick(text.chars()) == IsNormalized::Yes,
+            Self::Nfkc => is_nfkc_quick(text.chars()) == IsNormalized::Yes,
+            Self::Nfd => is_nfd_quick(text.chars()) == IsNormalized::Yes,
+            Self::Nfkd => is_nfkd_quick(text.chars()) == IsNormalized::Yes,
+            Self::Trim => text.trim().len() == text.len(),
+            Self::Prepend { content } => content.is_empty() || text.is_empty(),
+            Self::Replace { pattern, .. } => pattern.is_empty() || !text.contains(pattern.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Can a normalization segment start at this char?
+    ///
+    /// Each segment normalizes independently of its neighbours: a segment
+    /// starts where the char decomposes to a starter which never composes
+    /// with a preceding char.
+    fn is_boundary(
+        &self,
+        c: char,
+    ) -> bool {
+        let single = core::iter::once(c);
+        let first = match self {
+            Self::Nfkc | Self::Nfkd => single.nfkd().next(),
+            _ => single.nfd().next(),
+        }
+        .unwrap_or(c);
+
+        let first_only = core::iter::once(first);
+        canonical_combining_class(first) == 0
+            && match self {
+                Self::Nfc => is_nfc_quick(first_only) == IsNormalized::Yes,
+                Self::Nfkc => is_nfkc_quick(first_only) == IsNormalized::Yes,
+                _ => true,
+            }
+    }
+
+    /// Rewrite the text segment by segment.
+    ///
+    /// Calls `f(range, replacement)` for consecutive segments covering
+    /// `text`; unchanged segments pass `&text[range]` as the replacement.
+    fn rewrite(
+        &self,
+        text: &str,
+        f: &mut dyn FnMut(Range<usize>, &str),
+    ) {
+        if self.is_noop(text) {
+            f(0..text.len(), text);
+            return;
+        }
+
+        let mut buf = String::new();
+        match self {
+            Self::Nfc | Self::Nfkc | Self::Nfd | Self::Nfkd => {
+                let mut start = 0;
+                for (idx, c) in text.char_indices().skip(1) {
+                    if self.is_boundary(c) {
+                        self.normalize_segment(&text[start..idx], &mut buf);
+                        f(start..idx, &buf);
+                        start = idx;
+                    }
+                }
+                self.normalize_segment(&text[start..], &mut buf);
+                f(start..text.len(), &buf);
+            }
+            Self::Lowercase => {
+                for_each_char_rewrite(text, f, |c, buf| {
+                    let mut lower = c.to_lowercase();
+                    if lower.len() == 1 && lower.next() == Some(c) {
+                        return false;
+                    }
+                    buf.extend(c.to_lowercase());
+                    true
+                });
+            }
+            Self::StripAccents => {
+                for_each_char_rewrite(text, f, |c, _| is_nonspacing_mark(c));
+            }
+            Self::CleanText => {
+                for_each_char_rewrite(text, f, |c, buf| {
+                    if c == '\0' || c == '\u{FFFD}' || is_other(c) {
+                        return true;
+                    }
+                    if c != ' ' && c.is_whitespace() {
+                        buf.push(' ');
+                        return true;
+                    }
+                    false
+                });
+            }
+            Self::CollapseWhitespace => {
+                let mut kept = 0;
+                let mut chars = text.char_indices().peekable();
+                while let Some((start, c)) = chars.next() {
+                    if !c.is_whitespace() {
+                        continue;
+                    }
+                    let mut end = start + c.len_utf8();
+                    while let Some(&(idx, c)) = chars.peek()
+                        && c.is_whitespace()
+                    {
+                        end = idx + c.len_utf8();
+                        chars.next();
+                    }
+                    if &text[start..end] == " " {
+                        continue;
+                    }
+                    if kept < start {
+                        f(kept..start, &text[kept..start]);
+                    }
+                    f(start..end, " ");
+                    kept = end;
+                }
+                if kept < text.len() {
+                    f(kept..text.len(), &text[kept..]);
+                }
+            }
+            Self::Trim => {
+                let start = text.len() - text.trim_start().len();
+                let end = text.trim_end().len().max(start);
+                if start > 0 {
+                    f(0..start, "");
+                }
+                if start < end {
+                    f(start..end, &text[start..end]);
+                }
+                if end < text.len() {
+                    f(end..text.len(), "");
+                }
+            }
+            Self::Prepend { content } => {
+                f(0..0, content);
+                f(0..text.len(), text);
+            }
+            Self::Replace { pattern, content } => {
+                let mut kept = 0;
+                for (start, _) in text.match_indices(pattern.as_str()) {
+                    if kept < start {
+                        f(kept..start, &text[kept..start]);
+                    }
+                    let end = start + pattern.len();
+                    f(start..end, content);
+                    kept = end;
+                }
+                if kept < text.len() {
+                    f(kept..text.len(), &text[kept..]);
+                }
+            }
+        }
+    }
+
+    /// Normalize one Unicode normalization segment into `buf`.
+    fn normalize_segment(
+        &self,
+        segment: &str,
+        buf: &mut String,
+    ) {
+        buf.clear();
+        match self {
+            Self::Nfc => buf.extend(segment.nfc()),
+            Self::Nfkc => buf.extend(segment.nfkc()),
+            Self::Nfd => buf.extend(segment.nfd()),
+            Self::Nfkd => buf.extend(segment.nfkd()),
+            _ => buf.push_str(segment),
+        }
+    }
+}
+
+/// Rewrite the text char by char.
+///
+/// `g(c, buf)` pushes the replacement of `c` to the (empty) `buf`, and
+/// returns `true`; or returns `false` to keep `c`. Kept chars are passed
+/// to `f` in runs.
+fn for_each_char_rewrite(
+    text: &str,
+    f: &mut dyn FnMut(Range<usize>, &str),
+    mut g: impl FnMut(char, &mut String) -> bool,
+) {
+    let mut buf = String::new();
+    let mut kept = 0;
+    for (start, c) in text.char_indices() {
+        buf.clear();
+        if !g(c, &mut buf) {
+            continue;
+        }
+        if kept < start {
+            f(kept..start, &text[kept..start]);
+        }
+        let end = start + c.len_utf8();
+        f(start..end, &buf);
+        kept = end;
+    }
+    if kept < text.len() {
+        f(kept..text.len(), &text[kept..]);
+    }
+}
+
+/// A pipeline of [`NormalizerStep`]s, applied in order before spanning.
+///
+/// The default normalizer has no steps, and leaves text unchanged.
+///
+/// ## Style Hints
+///
+/// Instance names should prefer `normalizer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TextNormalizer {
+    steps: Vec<NormalizerStep>,
+}
+
+impl<I> From<I> for TextNormalizer
+where
+    I: IntoIterator<Item = NormalizerStep>,
+{
+    fn from(steps: I) -> Self {
+        Self::new(steps)
+    }
+}
+
+impl TextNormalizer {
+    /// Create a new normalizer from a sequence of steps.
+    pub fn new<I>(steps: I) -> Self
+    where
+        I: IntoIterator<Item = NormalizerStep>,
+    {
+        Self {
+            steps: steps.into_iter().collect(),
+        }
+    }
+
+    /// Append a step.
+    pub fn with_step(
+        mut self,
+        step: NormalizerStep,
+    ) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Get the steps.
+    pub fn steps(&self) -> &[NormalizerStep] {
+        &self.steps
+    }
+
+    /// Does this normalizer have no steps?
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Normalize text, without alignment.
+    ///
+    /// ## Returns
+    /// The normalized text; borrowed if no step changed it.
+    pub fn normalize_str<'a>(
+        &self,
+        text: &'a str,
+    ) -> Cow<'a, str> {
+        let mut current = Cow::Borrowed(text);
+        for step in &self.steps {
+            if step.is_noop(&current) {
+                continue;
+            }
+            let mut out = String::with_capacity(current.len());
+            step.rewrite(&current, &mut |_, replacement| out.push_str(replacement));
+            current = Cow::Owned(out);
+        }
+        current
+    }
+
+    /// Normalize text, keeping the alignment to the original text.
+    pub fn normalize(
+        &self,
+        text: &str,
+    ) -> NormalizedText {
+        let mut current = NormalizedText::identity(text);
+        for step in &self.steps {
+            if step.is_noop(&current.text) {
+                continue;
+            }
+            let mut next = NormalizedText {
+                text: String::with_capacity(current.text.len()),
+                alignments: Vec::with_capacity(current.alignments.len()),
+                original_len: current.original_len,
+            };
+            step.rewrite(&current.text, &mut |range, replacement| {
+                if replacement.as_ptr() == current.text[range.clone()].as_ptr() {
+                    next.alignments
+                        .extend_from_slice(&current.alignments[range.clone()]);
+                } else {
+                    let original = current.original_range(range);
+                    next.alignments
+                        .extend((0..replacement.len()).map(|_| original.clone()));
+                }
+                next.text.push_str(replacement);
+            });
+            current = next;
+        }
+        current
+    }
+
+    /// Normalize text, leaving special words unchanged; without alignment.
+    ///
+    /// ## Arguments
+    /// * `text` - The text to normalize.
+    /// * `specials` - The optional lexer matching special words.
+    ///
+    /// ## Returns
+    /// The normalized text; borrowed if nothing changed it.
+    pub fn normalize_str_around_specials<'a>(
+        &self,
+        text: &'a str,
+        specials: Option<&dyn SpanLexer>,
+    ) -> Cow<'a, str> {
+        let Some(specials) = specials else {
+            return self.normalize_str(text);
+        };
+        if self.is_empty() || specials.next_span(text, 0).is_none() {
+            return self.normalize_str(text);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        while let Some((start, end)) = specials.next_span(text, last) {
+            out.push_str(&self.normalize_str(&text[last..start]));
+            out.push_str(&text[start..end]);
+            last = end;
+        }
+        out.push_str(&self.normalize_str(&text[last..]));
+        Cow::Owned(out)
+    }
+
+    /// Normalize text, leaving special words unchanged; keeping the alignment.
+    ///
+    /// Special words are matched on the original text; so normalization
+    /// (e.g. lowercasing) cannot hide them from the spanner.
+    ///
+    /// ## Arguments
+    /// * `text` - The text to normalize.
+    /// * `specials` - The optional lexer matching special words.
+    pub fn normalize_around_specials(
+        &self,
+        text: &str,
+        specials: Option<&dyn SpanLexer>,
+    ) -> NormalizedText {
+        let Some(specials) = specials else {
+            return self.normalize(text);
+        };
+        if self.is_empty() {
+            return self.normalize(text);
+        }
+
+        let mut out = NormalizedText {
+            text: String::with_capacity(text.len()),
+            alignments: Vec::with_capacity(text.len()),
+            original_len: text.len(),
+        };
+        let mut last = 0;
+        while let Some((start, end)) = specials.next_span(text, last) {
+            out.append(self.normalize(&text[last..start]), last);
+            out.append(NormalizedText::identity(&text[start..end]), start);
+            last = end;
+        }
+        out.append(self.normalize(&text[last..]), last);
+        out
+    }
+}
+
+/// Normalized text, aligned to the original text.
+///
+/// Every byte of the normalized text maps to the range of original bytes
+/// it was produced from. Chars rewritten together (e.g. composed by NFC,
+/// or a collapsed whitespace run) all map to their whole original range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedText {
+    text: String,
+    alignments: Vec<Range<usize>>,
+    original_len: usize,
+}
+
+impl NormalizedText {
+    /// Normalized text identical to the original.
+    pub fn identity(text: &str) -> Self {
+        Self {
+            text: text.into(),
+            alignments: text
+                .char_indices()
+                .flat_map(|(idx, c)| {
+                    let range = idx..idx + c.len_utf8();
+                    range.clone().map(move |_| range.clone())
+                })
+                .collect(),
+            original_len: text.len(),
+        }
+    }
+
+    /// Append normalized text whose original begins at `offset`.
+    fn append(
+        &mut self,
+        other: NormalizedText,
+        offset: usize,
+    ) {
+        self.text.push_str(&other.text);
+        self.alignments.extend(
+            other
+                .alignments
+                .into_iter()
+                .map(|r| r.start + offset..r.end + offset),
+        );
+    }
+
+    /// Get the normalized text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the length of the original text.
+    pub fn original_len(&self) -> usize {
+        self.original_len
+    }
+
+    /// Get the original range of each normalized byte.
+    pub fn alignments(&self) -> &[Range<usize>] {
+        &self.alignments
+    }
+
+    /// Map a byte range of the normalized text to the original text.
+    ///
+    /// Empty ranges map to an empty range at the corresponding position.
+    ///
+    /// ## Panics
+    /// If the range is out of bounds of the normalized text.
+    pub fn original_range(
+        &self,
+        range: Range<usize>,
+    ) -> Range<usize> {
+        assert!(
+            range.start <= range.end && range.end <= self.text.len(),
+            "range {range:?} out of bounds of normalized text of length {}",
+            self.text.len()
+        );
+        if range.is_empty() {
+            let pos = match self.alignments.get(range.start) {
+                Some(next) => next.start,
+                None => self.original_len,
+            };
+            return pos..pos;
+        }
+        self.alignments[range.start].start..self.alignments[range.end - 1].end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{string::ToString, vec},
+        support::regex::{RegexWrapper, alternate_choice_regex_pattern},
+    };
+
+    fn normalize(
+        steps: &[NormalizerStep],
+        text: &str,
+    ) -> NormalizedText {
+        let normalizer = TextNormalizer::new(steps.iter().cloned());
+        let normalized = normalizer.normalize(text);
+        assert_eq!(normalizer.normalize_str(text), normalized.text());
+        assert_eq!(normalized.alignments().len(), normalized.text().len());
+        normalized
+    }
+
+    #[test]
+    fn test_empty_normalizer() {
+        let normalizer = TextNormalizer::default();
+        assert!(normalizer.is_empty());
+        assert!(matches!(
+            normalizer.normalize_str("abc"),
+            Cow::Borrowed("abc")
+        ));
+
+        let normalized = normalizer.normalize("h\u{e9}");
+        assert_eq!(normalized, NormalizedText::identity("h\u{e9}"));
+        assert_eq!(normalized.original_range(1..3), 1..3);
+        assert_eq!(normalized.original_range(3..3), 3..3);
+    }
+
+    #[test]
+    fn test_nfc_nfd() {
+        let text = "e\u{301}t\u{e9} \u{1100}\u{1161}";
+
+        let nfc = normalize(&[NormalizerStep::Nfc], text);
+        assert_eq!(nfc.text(), "\u{e9}t\u{e9} \u{ac00}");
+        // The composed chars map to their decomposed originals.
+        assert_eq!(nfc.original_range(0..2), 0..3);
+        assert_eq!(nfc.original_range(2..3), 3..4);
+        assert_eq!(nfc.original_range(6..9), 7..13);
+
+        let nfd = normalize(&[NormalizerStep::Nfd], text);
+        assert_eq!(nfd.text(), "e\u{301}te\u{301} \u{1100}\u{1161}");
+        assert_eq!(nfd.original_range(4..7), 4..6);
+    }
+
+    #[test]
+    fn test_nfkc() {
+        let nfkc = normalize(&[NormalizerStep::Nfkc], "\u{fb01}x\u{2460}");
+        assert_eq!(nfkc.text(), "fix1");
+        assert_eq!(nfkc.original_range(0..2), 0..3);
+        assert_eq!(nfkc.original_range(2..3), 3..4);
+        assert_eq!(nfkc.original_range(3..4), 4..7);
+
+        // The halfwidth voiced mark composes with the preceding kana.
+        let nfkc = normalize(&[NormalizerStep::Nfkc], "\u{ff76}\u{ff9e}");
+        assert_eq!(nfkc.text(), "\u{30ac}");
+        assert_eq!(nfkc.original_range(0..3), 0..6);
+    }
+
+    #[test]
+    fn test_lowercase_strip_accents() {
+        let text = "CAF\u{c9} \u{130}x";
+        let normalized = normalize(
+            &[
+                NormalizerStep::Nfd,
+                NormalizerStep::StripAccents,
+                NormalizerStep::Lowercase,
+            ],
+            text,
+        );
+        // `İ` lowercases to `i\u{307}`; NFD splits off the dot first.
+        assert_eq!(normalized.text(), "cafe ix");
+        assert_eq!(normalized.original_range(3..4), 3..5);
+        assert_eq!(normalized.original_range(5..6), 6..8);
+        assert_eq!(normalized.original_range(6..7), 8..9);
+    }
+
+    #[test]
+    fn test_strip_accents_nonspacing_only() {
+        // U+0301 is Mn; U+093E (Devanagari sign aa) is Mc; U+20DD is Me.
+        let normalized = normalize(
+            &[NormalizerStep::StripAccents],
+            "e\u{301} \u{915}\u{93e} \u{20dd}",
+        );
+        assert_eq!(normalized.text(), "e \u{915}\u{93e} \u{20dd}");
+    }
+
+    #[test]
+    fn test_clean_text() {
+        let text = "a\tb\r\n\u{0}c\u{fffd}\u{7}d\u{200b}e\u{a0}f\u{e000}";
+        let normalized = normalize(&[NormalizerStep::CleanText], text);
+        // U+200B (zero width space) is Cf; U+A0 is whitespace; U+E000 is Co.
+        assert_eq!(normalized.text(), "a b  cde f");
+        assert_eq!(normalized.original_range(1..2), 1..2);
+        assert_eq!(normalized.original_range(8..9), 16..18);
+        assert_eq!(normalized.original_len(), text.len());
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        let normalized = normalize(&[NormalizerStep::CollapseWhitespace], "a \t\n b c\u{3000}d");
+        assert_eq!(normalized.text(), "a b c d");
+        assert_eq!(normalized.original_range(1..2), 1..5);
+        assert_eq!(normalized.original_range(2..3), 5..6);
+        assert_eq!(normalized.original_range(3..4), 6..7);
+        assert_eq!(normalized.original_range(5..6), 8..11);
+    }
+
+    #[test]
+    fn test_replace() {
+        let normalizer = TextNormalizer::default()
+            .with_step(NormalizerStep::replace("``", "\""))
+            .with_step(NormalizerStep::replace("", "x"));
+        assert_eq!(normalizer.steps().len(), 2);
+
+        let normalized = normalizer.normalize("say ``hi``");
+        assert_eq!(normalized.text(), "say \"hi\"");
+        assert_eq!(normalized.original_range(4..5), 4..6);
+        assert_eq!(normalized.original_range(5..7), 6..8);
+        assert_eq!(normalized.original_range(4..8), 4..10);
+
+        // Deleted text maps to the empty range after it.
+        let normalized = normalize(&[NormalizerStep::replace("-", "")], "a-b");
+        assert_eq!(normalized.text(), "ab");
+        assert_eq!(normalized.original_range(1..2), 2..3);
+        assert_eq!(normalized.original_range(2..2), 3..3);
+    }
+
+    #[test]
+    fn test_trim_prepend() {
+        let normalized = normalize(&[NormalizerStep::Trim], " \t ab c\n");
+        assert_eq!(normalized.text(), "ab c");
+        assert_eq!(normalized.original_range(0..4), 3..7);
+        assert_eq!(normalize(&[NormalizerStep::Trim], " \n ").text(), "");
+
+        let steps = [NormalizerStep::prepend("\u{2581}")];
+        let normalized = normalize(&steps, "ab");
+        assert_eq!(normalized.text(), "\u{2581}ab");
+        // The prefix maps to the empty range before the text.
+        assert_eq!(normalized.original_range(0..3), 0..0);
+        assert_eq!(normalized.original_range(0..4), 0..1);
+        assert_eq!(normalize(&steps, "").text(), "");
+    }
+
+    #[test]
+    fn test_pipeline_composes_alignment() {
+        let normalizer: TextNormalizer = vec![
+            NormalizerStep::Nfkc,
+            NormalizerStep::Lowercase,
+            NormalizerStep::CollapseWhitespace,
+        ]
+        .into();
+        let text = "\u{ff28}ello  \u{2163}";
+        let normalized = normalizer.normalize(text);
+        assert_eq!(normalized.text(), "hello iv");
+        assert_eq!(normalized.original_range(0..1), 0..3);
+        assert_eq!(normalized.original_range(5..6), 7..9);
+        assert_eq!(normalized.original_range(6..8), 9..12);
+        assert_eq!(normalized.original_len(), text.len());
+    }
+
+    #[test]
+    fn test_normalize_around_specials() {
+        let normalizer = TextNormalizer::new([NormalizerStep::Lowercase]);
+        let specials: RegexWrapper =
+            alternate_choice_regex_pattern(&["[CLS]".to_string(), "[SEP]".to_string()])
+                .compile()
+                .unwrap();
+        let text = "[CLS] \u{c9}T\u{c9}[SEP]";
+
+        assert_eq!(normalizer.normalize_str(text), "[cls] \u{e9}t\u{e9}[sep]");
+        assert_eq!(
+            normalizer.normalize_str_around_specials(text, Some(&specials)),
+            "[CLS] \u{e9}t\u{e9}[SEP]"
+        );
+        assert_eq!(
+            normalizer.normalize_str_around_specials(text, None),
+            normalizer.normalize_str(text)
+        );
+
+        let normalized = normalizer.normalize_around_specials(text, Some(&specials));
+        assert_eq!(normalized.text(), "[CLS] \u{e9}t\u{e9}[SEP]");
+        assert_eq!(normalized.alignments().len(), normalized.text().len());
+        assert_eq!(normalized.original_len(), text.len());
+        assert_eq!(normalized.original_range(0..5), 0..5);
+        assert_eq!(normalized.original_range(6..8), 6..8);
+        assert_eq!(normalized.original_range(11..16), 11..16);
+    }
+}
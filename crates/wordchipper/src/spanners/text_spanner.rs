@@ -4,6 +4,7 @@ use core::ops::Range;
 
 use crate::{
     alloc::{string::String, vec::Vec},
+    spanners::span_lexers::SpanLexer,
     vocab::DEFAULT_BYTE_PER_TOKEN_RATIO,
 };
 
@@ -57,6 +58,13 @@ pub trait TextSpanner: Send + Sync {
         text.len() / self.expected_bytes_per_span() as usize
     }
 
+    /// Get the lexer matching special words, if any.
+    ///
+    /// Used to leave special words untouched by text normalization.
+    fn special_lexer(&self) -> Option<&dyn SpanLexer> {
+        None
+    }
+
     /// Iterate over all split [`SpanRef`]s in the text.
     ///
     /// # Arguments
@@ -0,0 +1,172 @@
+//! # Parallel Batch Scheduling
+//!
+//! The scheduling shared by the parallel encoders:
+//! * [`document_pieces`] splits a large text into pieces;
+//! * [`BatchUnits`] orders the batch items (or their pieces) largest first,
+//!   and reassembles the results in the original order.
+
+use core::cmp::Reverse;
+
+use crate::{TokenType, WCResult, encoders::TokenEncoder, prelude::*, spanners::DocumentSplitter};
+
+/// Build the [`DocumentSplitter`] for `encoder`.
+///
+/// Needs the encoder's [`normalizer`](TokenEncoder::normalizer);
+/// without it, texts are not split. Build once per encoder.
+///
+/// ## Arguments
+/// * `encoder` - the encoder the pieces are for.
+pub fn document_splitter<T: TokenType>(encoder: &dyn TokenEncoder<T>) -> DocumentSplitter {
+    encoder
+        .normalizer()
+        .map(|normalizer| {
+            DocumentSplitter::new(
+                encoder.spanner().as_ref(),
+                normalizer,
+                encoder.special_vocab(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Split `text` into pieces, when worthwhile.
+///
+/// ## Arguments
+/// * `splitter` - the encoder's splitter; see [`document_splitter`].
+/// * `text` - the text.
+/// * `piece_bytes` - the min piece length, in bytes; `0` disables splitting.
+///
+/// ## Returns
+/// The pieces; or `None` if the text should be encoded whole.
+pub fn document_pieces<'a>(
+    splitter: &DocumentSplitter,
+    text: &'a str,
+    piece_bytes: usize,
+) -> Option<Vec<&'a str>> {
+    if piece_bytes == 0 || text.len() < 2 * piece_bytes {
+        return None;
+    }
+    let pieces = splitter.split(text, piece_bytes);
+    (pieces.len() > 1).then_some(pieces)
+}
+
+/// The work units of a batch, largest first.
+///
+/// Each unit is a batch item, or a piece of one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchUnits<'a> {
+    /// The batch item count.
+    len: usize,
+
+    /// `(item index, piece index, piece)`; largest piece first.
+    units: Vec<(usize, usize, &'a str)>,
+}
+
+impl<'a> BatchUnits<'a> {
+    /// Build the units from the pieces of each batch item.
+    ///
+    /// ## Arguments
+    /// * `item_pieces` - the pieces of each item, in batch order.
+    pub fn from_item_pieces<I>(item_pieces: I) -> Self
+    where
+        I: IntoIterator<Item = Vec<&'a str>>,
+    {
+        let mut len = 0;
+        let mut units = Vec::new();
+        for (idx, pieces) in item_pieces.into_iter().enumerate() {
+            len = idx + 1;
+            units.extend(
+                pieces
+                    .into_iter()
+                    .enumerate()
+                    .map(|(piece_idx, piece)| (idx, piece_idx, piece)),
+            );
+        }
+        units.sort_by_key(|&(_, _, piece)| Reverse(piece.len()));
+        Self { len, units }
+    }
+
+    /// Get the number of units.
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    /// Are there no units?
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// Get the text of the unit at `idx`.
+    pub fn get(
+        &self,
+        idx: usize,
+    ) -> &'a str {
+        self.units[idx].2
+    }
+
+    /// Reassemble per-unit results into per-item results.
+    ///
+    /// ## Arguments
+    /// * `results` - the result of each unit, in unit order.
+    ///
+    /// ## Returns
+    /// The tokens of each batch item, in batch order.
+    ///
+    /// ## Errors
+    /// The first error in batch order.
+    pub fn assemble<T: TokenType>(
+        self,
+        results: Vec<WCResult<Vec<T>>>,
+    ) -> WCResult<Vec<Vec<T>>> {
+        let mut done: Vec<_> = self.units.into_iter().zip(results).collect();
+        done.sort_unstable_by_key(|&((idx, piece_idx, _), _)| (idx, piece_idx));
+
+        let mut out = vec![Vec::new(); self.len];
+        for ((idx, _, _), result) in done {
+            let tokens = result?;
+            match out[idx].is_empty() {
+                true => out[idx] = tokens,
+                false => out[idx].extend(tokens),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// The indices of `lens`, largest first.
+pub fn largest_first(lens: impl IntoIterator<Item = usize>) -> Vec<usize> {
+    let mut order: Vec<(usize, usize)> = lens.into_iter().enumerate().collect();
+    order.sort_by_key(|&(_, len)| Reverse(len));
+    order.into_iter().map(|(idx, _)| idx).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alloc::vec;
+
+    #[test]
+    fn test_batch_units() {
+        let units =
+            BatchUnits::from_item_pieces(vec![vec!["a"], vec!["bbb", "cc"], vec![], vec!["dddd"]]);
+        assert_eq!(units.len(), 4);
+        assert!(!units.is_empty());
+        let texts: Vec<&str> = (0..units.len()).map(|idx| units.get(idx)).collect();
+        assert_eq!(texts, vec!["dddd", "bbb", "cc", "a"]);
+
+        let tokens = |text: &str| -> Vec<u32> { text.bytes().map(u32::from).collect() };
+        let results = texts.iter().map(|text| Ok(tokens(text))).collect();
+        assert_eq!(
+            units.assemble(results).unwrap(),
+            vec![tokens("a"), tokens("bbbcc"), vec![], tokens("dddd")]
+        );
+
+        assert!(BatchUnits::from_item_pieces(Vec::<Vec<&str>>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_largest_first() {
+        assert_eq!(largest_first([1, 5, 0, 3]), vec![1, 3, 0, 2]);
+        assert_eq!(largest_first([]), Vec::<usize>::new());
+    }
+}
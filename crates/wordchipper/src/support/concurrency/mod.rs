@@ -1,7 +1,9 @@
 //! # Concurrency Utilities
 
+pub mod batch_schedule;
 #[cfg(feature = "parallel")]
 pub mod rayon;
+pub mod scoped_threads;
 #[cfg(feature = "std")]
 pub mod threads;
 
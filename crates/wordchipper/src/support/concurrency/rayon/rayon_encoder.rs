@@ -6,17 +6,54 @@ use crate::{
     alloc::sync::Arc,
     encoders::TokenEncoder,
     prelude::*,
-    spanners::TextSpanner,
+    spanners::{DocumentSplitter, TextNormalizer, TextSpanner},
+    support::concurrency::batch_schedule::{
+        BatchUnits,
+        document_pieces,
+        document_splitter,
+        largest_first,
+    },
     vocab::SpecialVocab,
 };
 
 /// Batch-Level Parallel Encoder Wrapper.
 ///
 /// Enables ``rayon`` encoding of batches when available.
+///
+/// ## Document Pieces
+///
+/// With [`with_document_piece_bytes`](Self::with_document_piece_bytes),
+/// a single large text is also split into pieces (see [`document_pieces`]);
+/// which are encoded in parallel, and concatenated. Splits are only taken
+/// at line starts, and only when a check of the inner encoder's spanner and
+/// normalizer finds the tokens unchanged; a heuristic, exact for the
+/// pretrained patterns (see [`DocumentSplitter`]). This needs the
+/// inner encoder's [`normalizer`](TokenEncoder::normalizer); without it,
+/// texts are not split.
+///
+/// ## Batch Scheduling
+///
+/// Batch items are encoded largest first. With document pieces enabled,
+/// large items are also split into pieces, so one huge document does not
+/// become the straggler. Results are returned in the original order.
+///
+/// ## Thread Pool
+///
+/// Work runs on the global ``rayon`` pool; or on the pool given to
+/// [`with_thread_pool`](Self::with_thread_pool).
 pub struct ParallelRayonEncoder<T: TokenType> {
     /// Inner encoder.
     pub inner: Arc<dyn TokenEncoder<T>>,
 
+    /// Min document piece length, in bytes; `0` disables splitting.
+    document_piece_bytes: usize,
+
+    /// The line starts where texts may be split.
+    document_splitter: DocumentSplitter,
+
+    /// The thread pool; `None` for the global pool.
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -34,9 +71,77 @@ where
     pub fn new(inner: Arc<dyn TokenEncoder<T>>) -> Self {
         Self {
             inner,
+            document_piece_bytes: 0,
+            document_splitter: DocumentSplitter::default(),
+            thread_pool: None,
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Enable splitting large texts into pieces encoded in parallel.
+    ///
+    /// The safe split points are checked here, once (see [`document_splitter`]);
+    /// splitting stays disabled when there are none.
+    ///
+    /// ## Arguments
+    /// * `document_piece_bytes` - the min piece length, in bytes;
+    ///   `0` disables splitting.
+    pub fn with_document_piece_bytes(
+        mut self,
+        document_piece_bytes: usize,
+    ) -> Self {
+        if document_piece_bytes != 0 {
+            self.document_splitter = document_splitter(self.inner.as_ref());
+        }
+        self.document_piece_bytes = if self.document_splitter.can_split() {
+            document_piece_bytes
+        } else {
+            0
+        };
+        self
+    }
+
+    /// Get the min document piece length, in bytes; `0` when disabled.
+    pub fn document_piece_bytes(&self) -> usize {
+        self.document_piece_bytes
+    }
+
+    /// Run on the given thread pool, rather than the global pool.
+    ///
+    /// Isolates encoding from other ``rayon`` work in the process.
+    pub fn with_thread_pool(
+        mut self,
+        thread_pool: Arc<rayon::ThreadPool>,
+    ) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// Get the thread pool; `None` for the global pool.
+    pub fn thread_pool(&self) -> Option<&Arc<rayon::ThreadPool>> {
+        self.thread_pool.as_ref()
+    }
+
+    /// Run `f` on the configured thread pool.
+    fn install<R: Send>(
+        &self,
+        f: impl FnOnce() -> R + Send,
+    ) -> R {
+        match &self.thread_pool {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
+
+    /// Split `text` into pieces, when document pieces are enabled.
+    ///
+    /// See [`document_pieces`].
+    fn document_pieces<'a>(
+        &self,
+        text: &'a str,
+    ) -> Option<Vec<&'a str>> {
+        document_pieces(&self.document_splitter, text, self.document_piece_bytes)
+    }
 }
 
 impl<T> TokenEncoder<T> for ParallelRayonEncoder<T>
@@ -51,26 +156,89 @@ where
         self.inner.special_vocab()
     }
 
+    fn normalizer(&self) -> Option<&TextNormalizer> {
+        self.inner.normalizer()
+    }
+
     fn try_encode_append(
         &self,
         text: &str,
         tokens: &mut Vec<T>,
     ) -> WCResult<()> {
-        self.inner.try_encode_append(text, tokens)
+        use rayon::prelude::*;
+
+        let Some(pieces) = self.document_pieces(text) else {
+            return self.inner.try_encode_append(text, tokens);
+        };
+
+        let results: Vec<WCResult<Vec<T>>> = self.install(|| {
+            pieces
+                .par_iter()
+                .map(|piece| self.inner.try_encode(piece))
+                .collect()
+        });
+
+        for result in results {
+            tokens.extend(result?);
+        }
+        Ok(())
     }
 
+    fn try_encode_bytes_append(
+        &self,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) -> WCResult<()> {
+        self.inner.try_encode_bytes_append(bytes, tokens)
+    }
+
+    /// Encode a batch of byte strings; largest first.
+    fn try_encode_bytes_batch(
+        &self,
+        batch: &[&[u8]],
+    ) -> WCResult<Vec<Vec<T>>> {
+        use rayon::prelude::*;
+
+        let order = largest_first(batch.iter().map(|bytes| bytes.len()));
+        let results: Vec<(usize, WCResult<Vec<T>>)> = self.install(|| {
+            order
+                .par_iter()
+                .map(|&idx| (idx, self.inner.try_encode_bytes(batch[idx])))
+                .collect()
+        });
+
+        let mut out = vec![Vec::new(); batch.len()];
+        for (idx, result) in results {
+            out[idx] = result?;
+        }
+        Ok(out)
+    }
+
+    /// Encode a batch of texts.
+    ///
+    /// With [`document_piece_bytes`](Self::document_piece_bytes) set, large
+    /// items are split into pieces (see [`document_pieces`]); the items and
+    /// pieces are encoded largest first, and the results are reassembled
+    /// in the original order.
     fn try_encode_batch(
         &self,
         batch: &[&str],
     ) -> WCResult<Vec<Vec<T>>> {
         use rayon::prelude::*;
 
-        let results: Vec<WCResult<Vec<T>>> = batch
-            .par_iter()
-            .map(|text| self.inner.try_encode(text))
-            .collect();
+        self.install(|| {
+            let item_pieces: Vec<Vec<&str>> = batch
+                .par_iter()
+                .map(|&text| self.document_pieces(text).unwrap_or_else(|| vec![text]))
+                .collect();
+            let units = BatchUnits::from_item_pieces(item_pieces);
 
-        results.into_iter().collect()
+            let results: Vec<WCResult<Vec<T>>> = (0..units.len())
+                .into_par_iter()
+                .map(|idx| self.inner.try_encode(units.get(idx)))
+                .collect();
+            units.assemble(results)
+        })
     }
 }
 
@@ -110,4 +278,116 @@ mod tests {
     fn test_encoder_u32() {
         test_encoder::<u32>();
     }
+
+    #[test]
+    fn test_document_pieces() {
+        use crate::{
+            alloc::{string::String, vec::Vec},
+            pretrained::{
+                deepseek::DEEPSEEK_V3_PATTERN,
+                meta::META_LLAMA3_PATTERN,
+                openai::{OA_CL100K_BASE_PATTERN, OA_O200K_BASE_PATTERN, OA_R50K_BASE_PATTERN},
+                qwen::QWEN2_PATTERN,
+            },
+            spanners::{
+                TextSpanningConfig,
+                span_lexers::verification::{LEXER_TEST_CORPUS, generate_lexer_inputs},
+            },
+            vocab::{
+                BERT_BASIC_PATTERN,
+                SENTENCEPIECE_PATTERN,
+                bert_spanning_config,
+                sentencepiece_spanning_config,
+                utility::testing::{build_test_shift_byte_vocab, build_test_vocab},
+            },
+        };
+        type T = u32;
+
+        let mut lines: Vec<String> = LEXER_TEST_CORPUS.iter().map(|s| s.to_string()).collect();
+        lines.extend(generate_lexer_inputs(3, 200, 24));
+        let text = lines.join("\n") + ".\nThe end  \n\nfin\n";
+
+        let configs: Vec<(TextSpanningConfig<T>, bool)> = vec![
+            (TextSpanningConfig::from_pattern(OA_R50K_BASE_PATTERN), true),
+            (
+                TextSpanningConfig::from_pattern(OA_CL100K_BASE_PATTERN),
+                true,
+            ),
+            (
+                TextSpanningConfig::from_pattern(OA_O200K_BASE_PATTERN),
+                true,
+            ),
+            (TextSpanningConfig::from_pattern(META_LLAMA3_PATTERN), true),
+            (TextSpanningConfig::from_pattern(QWEN2_PATTERN), true),
+            (TextSpanningConfig::from_pattern(DEEPSEEK_V3_PATTERN), true),
+            (TextSpanningConfig::from_pattern(BERT_BASIC_PATTERN), true),
+            (bert_spanning_config(true), true),
+            // The dummy prefix of each piece stands in for the newline.
+            (sentencepiece_spanning_config(), true),
+            (
+                TextSpanningConfig::from_pattern(SENTENCEPIECE_PATTERN),
+                false,
+            ),
+        ];
+        for (config, splits) in configs {
+            let vocab: Arc<UnifiedTokenVocab<T>> =
+                build_test_vocab(build_test_shift_byte_vocab(10), config).into();
+            let inner = TokenEncoderOptions::default().build(vocab.clone());
+            let encoder = ParallelRayonEncoder::new(inner.clone()).with_document_piece_bytes(64);
+            assert_eq!(encoder.document_piece_bytes() != 0, splits);
+            assert_eq!(encoder.document_pieces(&text).is_some(), splits);
+
+            assert_eq!(
+                encoder.try_encode(&text).unwrap(),
+                inner.try_encode(&text).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_scheduling() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab::<T>().into();
+        let inner = TokenEncoderOptions::default().build(vocab.clone());
+
+        let pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .unwrap(),
+        );
+        let encoder = ParallelRayonEncoder::new(inner.clone())
+            .with_document_piece_bytes(32)
+            .with_thread_pool(pool.clone());
+        assert!(Arc::ptr_eq(encoder.thread_pool().unwrap(), &pool));
+        assert_eq!(encoder.install(rayon::current_num_threads), 2);
+
+        // Without a configured piece length, items are never split.
+        let whole = ParallelRayonEncoder::new(inner.clone()).with_thread_pool(pool);
+
+        let huge = "hello world, it's the heat\nand the salt. \n".repeat(50);
+        let batch = vec![
+            "hi",
+            huge.as_str(),
+            "",
+            "it's not",
+            huge.as_str(),
+            "san francisco",
+        ];
+        assert!(encoder.document_pieces(&huge).unwrap().len() > 1);
+        assert!(whole.document_pieces(&huge).is_none());
+
+        let expected: Vec<Vec<T>> = batch
+            .iter()
+            .map(|text| inner.try_encode(text).unwrap())
+            .collect();
+        assert_eq!(encoder.try_encode_batch(&batch).unwrap(), expected);
+        assert_eq!(whole.try_encode_batch(&batch).unwrap(), expected);
+
+        let byte_batch: Vec<&[u8]> = batch.iter().map(|text| text.as_bytes()).collect();
+        assert_eq!(
+            encoder.try_encode_bytes_batch(&byte_batch).unwrap(),
+            expected
+        );
+    }
 }
@@ -0,0 +1,18 @@
+//! # Scoped Thread Utilities
+//!
+//! ``std``-only wrappers for parallel encoders and decoders;
+//! for embedders who can't take ``rayon``.
+//!
+//! Each batch spawns scoped threads, which pull chunks of work
+//! from a shared [`WorkQueue`]; no threads outlive the call.
+
+mod thread_decoder;
+mod thread_encoder;
+mod work_queue;
+
+#[doc(inline)]
+pub use thread_decoder::ParallelThreadDecoder;
+#[doc(inline)]
+pub use thread_encoder::ParallelThreadEncoder;
+#[doc(inline)]
+pub use work_queue::WorkQueue;
@@ -0,0 +1,128 @@
+//! # Thread Parallel Decoder
+
+use crate::{
+    TokenType,
+    WCResult,
+    alloc::sync::Arc,
+    decoders::{BatchDecodeResult, DecodeResult, TokenDecoder},
+    prelude::*,
+    support::concurrency::scoped_threads::WorkQueue,
+};
+
+/// Batch-Level Parallel Decoder Wrapper, on scoped ``std`` threads.
+///
+/// The ``std``-only counterpart of `ParallelRayonDecoder`.
+pub struct ParallelThreadDecoder<T: TokenType> {
+    /// Wrapped decoder.
+    pub inner: Arc<dyn TokenDecoder<T>>,
+
+    /// The work queue.
+    work_queue: WorkQueue,
+
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T> ParallelThreadDecoder<T>
+where
+    T: TokenType,
+{
+    /// Create a new parallel token decoder.
+    ///
+    /// ## Arguments
+    /// * `inner` - The token decoder to wrap.
+    ///
+    /// ## Returns
+    /// A new `ParallelThreadDecoder` instance.
+    pub fn new(inner: Arc<dyn TokenDecoder<T>>) -> Self {
+        Self {
+            inner,
+            work_queue: WorkQueue::default(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Use the given work queue; to bound the threads, or set the chunk size.
+    pub fn with_work_queue(
+        mut self,
+        work_queue: WorkQueue,
+    ) -> Self {
+        self.work_queue = work_queue;
+        self
+    }
+
+    /// Get the work queue.
+    pub fn work_queue(&self) -> &WorkQueue {
+        &self.work_queue
+    }
+}
+
+impl<T> TokenDecoder<T> for ParallelThreadDecoder<T>
+where
+    T: TokenType,
+{
+    fn try_decode_to_bytes(
+        &self,
+        tokens: &[T],
+    ) -> WCResult<DecodeResult<Vec<u8>>> {
+        self.inner.try_decode_to_bytes(tokens)
+    }
+
+    fn try_decode_batch_to_bytes(
+        &self,
+        batch: &[&[T]],
+    ) -> WCResult<BatchDecodeResult<Vec<u8>>> {
+        self.work_queue
+            .map(batch.len(), |idx| self.try_decode_to_bytes(batch[idx]))
+            .into_iter()
+            .collect::<WCResult<Vec<_>>>()
+            .map(BatchDecodeResult::from)
+    }
+
+    fn try_decode_batch_to_strings(
+        &self,
+        batch: &[&[T]],
+    ) -> WCResult<BatchDecodeResult<String>> {
+        self.work_queue
+            .map(batch.len(), |idx| self.try_decode_to_string(batch[idx]))
+            .into_iter()
+            .collect::<WCResult<Vec<_>>>()
+            .map(BatchDecodeResult::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroUsize;
+
+    use super::*;
+    use crate::{
+        TokenDecoderOptions,
+        UnifiedTokenVocab,
+        decoders::utility::testing::common_decoder_unit_test,
+        pretrained::openai::OA_CL100K_BASE_PATTERN,
+        spanners::TextSpanningConfig,
+        vocab::utility::testing::{build_test_shift_byte_vocab, build_test_vocab},
+    };
+
+    #[test]
+    fn test_thread_decoder() {
+        type T = u16;
+
+        let vocab: Arc<UnifiedTokenVocab<T>> = build_test_vocab(
+            build_test_shift_byte_vocab(10),
+            TextSpanningConfig::from_pattern(OA_CL100K_BASE_PATTERN),
+        )
+        .into();
+
+        let inner = TokenDecoderOptions::default()
+            .with_parallel(false)
+            .build(vocab.clone());
+        let work_queue = WorkQueue::default()
+            .with_max_threads(NonZeroUsize::new(2))
+            .with_chunk_size(1);
+        let decoder = ParallelThreadDecoder::new(inner).with_work_queue(work_queue);
+        assert_eq!(decoder.work_queue(), &work_queue);
+
+        common_decoder_unit_test(vocab, &decoder);
+    }
+}
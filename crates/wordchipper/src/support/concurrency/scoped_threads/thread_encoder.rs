@@ -0,0 +1,295 @@
+//! # Thread Parallel Encoder
+
+use crate::{
+    TokenType,
+    WCResult,
+    alloc::sync::Arc,
+    encoders::TokenEncoder,
+    prelude::*,
+    spanners::{DocumentSplitter, TextNormalizer, TextSpanner},
+    support::concurrency::{
+        batch_schedule::{BatchUnits, document_pieces, document_splitter, largest_first},
+        scoped_threads::WorkQueue,
+    },
+    vocab::SpecialVocab,
+};
+
+/// Batch-Level Parallel Encoder Wrapper, on scoped ``std`` threads.
+///
+/// The ``std``-only counterpart of `ParallelRayonEncoder`; batches are
+/// encoded on scoped threads pulling from a [`WorkQueue`].
+///
+/// ## Document Pieces
+///
+/// With [`with_document_piece_bytes`](Self::with_document_piece_bytes),
+/// a single large text is also split into pieces (see [`document_pieces`]);
+/// which are encoded in parallel, and concatenated. Splits are only taken
+/// at line starts, and only when a check of the inner encoder's spanner and
+/// normalizer finds the tokens unchanged; a heuristic, exact for the
+/// pretrained patterns (see [`DocumentSplitter`]).
+///
+/// ## Batch Scheduling
+///
+/// Batch items are encoded largest first. With document pieces enabled,
+/// large items are also split into pieces. Results are returned in the
+/// original order.
+pub struct ParallelThreadEncoder<T: TokenType> {
+    /// Inner encoder.
+    pub inner: Arc<dyn TokenEncoder<T>>,
+
+    /// Min document piece length, in bytes; `0` disables splitting.
+    document_piece_bytes: usize,
+
+    /// The line starts where texts may be split.
+    document_splitter: DocumentSplitter,
+
+    /// The work queue.
+    work_queue: WorkQueue,
+
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T> ParallelThreadEncoder<T>
+where
+    T: TokenType,
+{
+    /// Create a new parallel encoder.
+    ///
+    /// ## Arguments
+    /// * `inner` - The token encoder to wrap.
+    ///
+    /// ## Returns
+    /// A new `ParallelThreadEncoder` instance.
+    pub fn new(inner: Arc<dyn TokenEncoder<T>>) -> Self {
+        Self {
+            inner,
+            document_piece_bytes: 0,
+            document_splitter: DocumentSplitter::default(),
+            work_queue: WorkQueue::default(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Enable splitting large texts into pieces encoded in parallel.
+    ///
+    /// The safe split points are checked here, once (see [`document_splitter`]);
+    /// splitting stays disabled when there are none.
+    ///
+    /// ## Arguments
+    /// * `document_piece_bytes` - the min piece length, in bytes;
+    ///   `0` disables splitting.
+    pub fn with_document_piece_bytes(
+        mut self,
+        document_piece_bytes: usize,
+    ) -> Self {
+        if document_piece_bytes != 0 {
+            self.document_splitter = document_splitter(self.inner.as_ref());
+        }
+        self.document_piece_bytes = if self.document_splitter.can_split() {
+            document_piece_bytes
+        } else {
+            0
+        };
+        self
+    }
+
+    /// Get the min document piece length, in bytes; `0` when disabled.
+    pub fn document_piece_bytes(&self) -> usize {
+        self.document_piece_bytes
+    }
+
+    /// Use the given work queue; to bound the threads, or set the chunk size.
+    pub fn with_work_queue(
+        mut self,
+        work_queue: WorkQueue,
+    ) -> Self {
+        self.work_queue = work_queue;
+        self
+    }
+
+    /// Get the work queue.
+    pub fn work_queue(&self) -> &WorkQueue {
+        &self.work_queue
+    }
+
+    /// Split `text` into pieces, when document pieces are enabled.
+    ///
+    /// See [`document_pieces`].
+    fn document_pieces<'a>(
+        &self,
+        text: &'a str,
+    ) -> Option<Vec<&'a str>> {
+        document_pieces(&self.document_splitter, text, self.document_piece_bytes)
+    }
+}
+
+impl<T> TokenEncoder<T> for ParallelThreadEncoder<T>
+where
+    T: TokenType,
+{
+    fn spanner(&self) -> &Arc<dyn TextSpanner> {
+        self.inner.spanner()
+    }
+
+    fn special_vocab(&self) -> &SpecialVocab<T> {
+        self.inner.special_vocab()
+    }
+
+    fn normalizer(&self) -> Option<&TextNormalizer> {
+        self.inner.normalizer()
+    }
+
+    fn try_encode_append(
+        &self,
+        text: &str,
+        tokens: &mut Vec<T>,
+    ) -> WCResult<()> {
+        let Some(pieces) = self.document_pieces(text) else {
+            return self.inner.try_encode_append(text, tokens);
+        };
+
+        let results = self
+            .work_queue
+            .map(pieces.len(), |idx| self.inner.try_encode(pieces[idx]));
+
+        for result in results {
+            tokens.extend(result?);
+        }
+        Ok(())
+    }
+
+    fn try_encode_bytes_append(
+        &self,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) -> WCResult<()> {
+        self.inner.try_encode_bytes_append(bytes, tokens)
+    }
+
+    /// Encode a batch of byte strings; largest first.
+    fn try_encode_bytes_batch(
+        &self,
+        batch: &[&[u8]],
+    ) -> WCResult<Vec<Vec<T>>> {
+        let order = largest_first(batch.iter().map(|bytes| bytes.len()));
+        let results = self.work_queue.map(order.len(), |i| {
+            self.inner.try_encode_bytes(batch[order[i]])
+        });
+
+        let mut out = vec![Vec::new(); batch.len()];
+        for (idx, result) in order.into_iter().zip(results) {
+            out[idx] = result?;
+        }
+        Ok(out)
+    }
+
+    /// Encode a batch of texts.
+    ///
+    /// With [`document_piece_bytes`](Self::document_piece_bytes) set, large
+    /// items are split into pieces (see [`document_pieces`]); the items and
+    /// pieces are encoded largest first, and the results are reassembled
+    /// in the original order.
+    fn try_encode_batch(
+        &self,
+        batch: &[&str],
+    ) -> WCResult<Vec<Vec<T>>> {
+        let item_pieces = self.work_queue.map(batch.len(), |idx| {
+            self.document_pieces(batch[idx])
+                .unwrap_or_else(|| vec![batch[idx]])
+        });
+        let units = BatchUnits::from_item_pieces(item_pieces);
+
+        let results = self
+            .work_queue
+            .map(units.len(), |idx| self.inner.try_encode(units.get(idx)));
+        units.assemble(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroUsize;
+
+    use super::*;
+    use crate::{
+        TokenEncoderOptions,
+        TokenType,
+        UnifiedTokenVocab,
+        encoders::{
+            TokenEncoder,
+            testing::{common_encoder_test_vocab, common_encoder_tests},
+        },
+    };
+
+    fn test_encoder<T: TokenType>() {
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab::<T>().into();
+        let inner = TokenEncoderOptions::default()
+            .with_parallel(false)
+            .build(vocab.clone());
+        let encoder = ParallelThreadEncoder::new(inner);
+
+        assert_eq!(encoder.special_vocab(), encoder.inner.special_vocab());
+
+        let encoder: Arc<dyn TokenEncoder<T>> = Arc::new(encoder);
+
+        common_encoder_tests(vocab, encoder)
+    }
+
+    #[test]
+    fn test_encoder_u16() {
+        test_encoder::<u16>();
+    }
+
+    #[test]
+    fn test_encoder_u32() {
+        test_encoder::<u32>();
+    }
+
+    #[test]
+    fn test_batch_scheduling() {
+        type T = u32;
+        let vocab: Arc<UnifiedTokenVocab<T>> = common_encoder_test_vocab::<T>().into();
+        let inner = TokenEncoderOptions::default().build(vocab.clone());
+
+        let work_queue = WorkQueue::default()
+            .with_max_threads(NonZeroUsize::new(2))
+            .with_chunk_size(1);
+        let encoder = ParallelThreadEncoder::new(inner.clone())
+            .with_document_piece_bytes(32)
+            .with_work_queue(work_queue);
+        assert_eq!(encoder.document_piece_bytes(), 32);
+        assert_eq!(encoder.work_queue(), &work_queue);
+
+        // Without a configured piece length, items are never split.
+        let whole = ParallelThreadEncoder::new(inner.clone()).with_work_queue(work_queue);
+
+        let huge = "hello world, it's the heat\nand the salt. \n".repeat(50);
+        let batch = vec![
+            "hi",
+            huge.as_str(),
+            "",
+            "it's not",
+            huge.as_str(),
+            "san francisco",
+        ];
+        assert!(encoder.document_pieces(&huge).unwrap().len() > 1);
+        assert!(whole.document_pieces(&huge).is_none());
+        assert_eq!(
+            encoder.try_encode(&huge).unwrap(),
+            inner.try_encode(&huge).unwrap()
+        );
+
+        let expected: Vec<Vec<T>> = batch
+            .iter()
+            .map(|text| inner.try_encode(text).unwrap())
+            .collect();
+        assert_eq!(encoder.try_encode_batch(&batch).unwrap(), expected);
+        assert_eq!(whole.try_encode_batch(&batch).unwrap(), expected);
+
+        let byte_batch: Vec<&[u8]> = batch.iter().map(|text| text.as_bytes()).collect();
+        assert_eq!(
+            encoder.try_encode_bytes_batch(&byte_batch).unwrap(),
+            expected
+        );
+    }
+}
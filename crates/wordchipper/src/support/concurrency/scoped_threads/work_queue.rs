@@ -0,0 +1,188 @@
+//! # Chunked Work Queue
+
+use core::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::{panic::resume_unwind, thread};
+
+use crate::{prelude::*, support::concurrency::threads::resolve_max_pool};
+
+/// The chunks per thread, when the chunk size is automatic.
+///
+/// More chunks balance uneven work better; fewer contend less on the queue.
+const AUTO_CHUNKS_PER_THREAD: usize = 4;
+
+/// A chunked work queue over scoped threads.
+///
+/// [`map`](Self::map) spawns scoped threads; each claims the next
+/// chunk of indices from a shared counter until none remain.
+/// Items are claimed in index order; so callers wanting the
+/// largest items first should order them that way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WorkQueue {
+    /// The max thread count; `None` for [`resolve_max_pool`].
+    pub max_threads: Option<NonZeroUsize>,
+
+    /// The items claimed at a time; `0` for automatic.
+    pub chunk_size: usize,
+}
+
+impl WorkQueue {
+    /// Get the max thread count; `None` for the available parallelism.
+    pub fn max_threads(&self) -> Option<NonZeroUsize> {
+        self.max_threads
+    }
+
+    /// Set the max thread count; `None` for the available parallelism.
+    pub fn set_max_threads<M: Into<Option<NonZeroUsize>>>(
+        &mut self,
+        max_threads: M,
+    ) {
+        self.max_threads = max_threads.into();
+    }
+
+    /// Set the max thread count, and return the queue.
+    pub fn with_max_threads<M: Into<Option<NonZeroUsize>>>(
+        mut self,
+        max_threads: M,
+    ) -> Self {
+        self.set_max_threads(max_threads);
+        self
+    }
+
+    /// Get the items claimed at a time; `0` for automatic.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Set the items claimed at a time; `0` for automatic.
+    pub fn set_chunk_size(
+        &mut self,
+        chunk_size: usize,
+    ) {
+        self.chunk_size = chunk_size;
+    }
+
+    /// Set the items claimed at a time, and return the queue.
+    pub fn with_chunk_size(
+        mut self,
+        chunk_size: usize,
+    ) -> Self {
+        self.set_chunk_size(chunk_size);
+        self
+    }
+
+    /// The thread count available to the queue.
+    ///
+    /// See [`resolve_max_pool`].
+    pub fn num_threads(&self) -> usize {
+        resolve_max_pool(self.max_threads)
+    }
+
+    /// The chunk size for `len` items over `num_threads` threads.
+    fn effective_chunk_size(
+        &self,
+        len: usize,
+        num_threads: usize,
+    ) -> usize {
+        match self.chunk_size {
+            0 => len.div_ceil(num_threads * AUTO_CHUNKS_PER_THREAD).max(1),
+            chunk_size => chunk_size,
+        }
+    }
+
+    /// Map `f` over `0..len` in parallel.
+    ///
+    /// Runs on the calling thread when there is only one chunk of work.
+    ///
+    /// ## Arguments
+    /// * `len` - the item count.
+    /// * `f` - the item function.
+    ///
+    /// ## Returns
+    /// `f(idx)` for each `idx` in `0..len`, in order.
+    ///
+    /// ## Panics
+    /// If `f` panics; the panic is resumed on the calling thread.
+    pub fn map<R, F>(
+        &self,
+        len: usize,
+        f: F,
+    ) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(usize) -> R + Sync,
+    {
+        let num_threads = self.num_threads();
+        let chunk_size = self.effective_chunk_size(len, num_threads);
+        let num_threads = num_threads.min(len.div_ceil(chunk_size));
+        if num_threads <= 1 {
+            return (0..len).map(f).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut done = Vec::new();
+            loop {
+                let start = next.fetch_add(chunk_size, Ordering::Relaxed);
+                if start >= len {
+                    return done;
+                }
+                let end = (start + chunk_size).min(len);
+                done.extend((start..end).map(|idx| (idx, f(idx))));
+            }
+        };
+
+        let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..num_threads).map(|_| scope.spawn(worker)).collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap_or_else(|e| resume_unwind(e)))
+                .collect()
+        });
+        results.sort_unstable_by_key(|&(idx, _)| idx);
+        results.into_iter().map(|(_, r)| r).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_queue() {
+        let queue = WorkQueue::default();
+        assert_eq!(queue.max_threads(), None);
+        assert_eq!(queue.chunk_size(), 0);
+
+        let queue = queue
+            .with_max_threads(NonZeroUsize::new(3))
+            .with_chunk_size(2);
+        assert_eq!(queue.max_threads(), NonZeroUsize::new(3));
+        assert!(queue.num_threads() <= 3);
+
+        let expected: Vec<usize> = (0..101).map(|idx| idx * idx).collect();
+        assert_eq!(queue.map(101, |idx| idx * idx), expected);
+        assert_eq!(queue.map(0, |idx| idx), Vec::<usize>::new());
+
+        let auto = WorkQueue::default().with_max_threads(NonZeroUsize::new(4));
+        assert_eq!(auto.effective_chunk_size(100, 4), 7);
+        assert_eq!(auto.effective_chunk_size(3, 4), 1);
+        assert_eq!(auto.map(101, |idx| idx * idx), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "boom")]
+    fn test_work_queue_panic() {
+        let queue = WorkQueue::default()
+            .with_max_threads(NonZeroUsize::new(2))
+            .with_chunk_size(1);
+        queue.map(4, |idx| {
+            if idx == 3 {
+                panic!("boom");
+            }
+            idx
+        });
+    }
+}
@@ -0,0 +1,57 @@
+//! # SHA-256 Hashing
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::prelude::*;
+
+/// Render digest bytes as lowercase hex.
+///
+/// ## Arguments
+/// * `digest` - the digest bytes.
+pub fn hex_digest(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Compute the lowercase hex SHA-256 digest of a file.
+///
+/// ## Arguments
+/// * `path` - the file to hash.
+///
+/// ## Errors
+/// If the file cannot be read.
+pub fn file_sha256(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex_digest(&hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_sha256() {
+        let dir = tempdir::TempDir::new("hashing").unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, "abc").unwrap();
+
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(file_sha256(&path).unwrap(), abc);
+        assert_eq!(hex_digest(&Sha256::digest(b"abc")), abc);
+        assert_eq!(hex_digest(&[0x00, 0x0f, 0xff]), "000fff");
+    }
+}
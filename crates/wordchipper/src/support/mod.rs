@@ -2,6 +2,10 @@
 
 #[cfg(feature = "concurrent")]
 pub mod concurrency;
+#[cfg(feature = "std")]
+pub mod hashing;
+pub mod parallel_backend;
+pub mod random;
 pub mod ranges;
 pub mod regex;
 pub mod resources;
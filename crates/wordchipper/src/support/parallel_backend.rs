@@ -0,0 +1,55 @@
+//! # Parallel Backend Selection
+
+/// Selects the threading backend for parallel encoders and decoders.
+///
+/// Only used when parallelism is requested; see
+/// [`TokenEncoderOptions::parallel`](crate::TokenEncoderOptions::parallel).
+///
+/// The default is [`Rayon`](Self::Rayon) when the `parallel` feature is
+/// enabled, and [`Threads`](Self::Threads) otherwise. Selecting a backend
+/// whose feature is disabled logs a warning, and builds a single-threaded
+/// encoder or decoder; see [`is_available`](Self::is_available).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ParallelBackend {
+    /// The ``rayon`` thread pool; needs the `parallel` feature.
+    ///
+    /// See `support::concurrency::rayon`.
+    #[cfg_attr(feature = "parallel", default)]
+    Rayon,
+
+    /// Scoped ``std`` threads, spawned per batch; needs the `concurrent` feature.
+    ///
+    /// For embedders whose own runtime conflicts with a ``rayon`` pool.
+    /// See `support::concurrency::scoped_threads`.
+    #[cfg_attr(not(feature = "parallel"), default)]
+    Threads,
+}
+
+impl ParallelBackend {
+    /// Is this backend compiled in?
+    pub fn is_available(&self) -> bool {
+        match self {
+            Self::Rayon => cfg!(feature = "parallel"),
+            Self::Threads => cfg!(feature = "concurrent"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_backend() {
+        let backend = ParallelBackend::default();
+        if cfg!(feature = "parallel") {
+            assert_eq!(backend, ParallelBackend::Rayon);
+        } else {
+            assert_eq!(backend, ParallelBackend::Threads);
+        }
+        assert_eq!(
+            ParallelBackend::Threads.is_available(),
+            cfg!(feature = "concurrent")
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! # Random Utilities
+
+/// The `splitmix64` state increment.
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A small deterministic PRNG; `splitmix64`.
+///
+/// Not cryptographic; used for reproducible test inputs and sampling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Derive the `idx`-th seed of a family of generators.
+    ///
+    /// This is the `idx`-th output of the generator seeded with `seed`;
+    /// so the derived seeds are as independent as its outputs.
+    pub fn derive_seed(
+        seed: u64,
+        idx: u64,
+    ) -> u64 {
+        Self::new(seed.wrapping_add(idx.wrapping_mul(GAMMA))).next_u64()
+    }
+
+    /// Get the next `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Get the next `usize` in `0..n`.
+    ///
+    /// ## Panics
+    /// If `n` is zero.
+    pub fn below(
+        &mut self,
+        n: usize,
+    ) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Get the next `f64` in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_mix() {
+        let mut a = SplitMix64::new(7);
+        let mut b = SplitMix64::new(7);
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert_ne!(a.next_u64(), SplitMix64::new(8).next_u64());
+
+        let mut c = SplitMix64::new(7);
+        for idx in 0..3 {
+            assert_eq!(SplitMix64::derive_seed(7, idx), c.next_u64());
+        }
+
+        for _ in 0..100 {
+            assert!(a.below(3) < 3);
+            let x = a.next_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! # Chained Resource Loader
+
+use std::{io::BufRead, path::PathBuf};
+
+use crate::{
+    WCError,
+    WCResult,
+    prelude::*,
+    support::resources::{KeyedResource, ResourceLoader},
+};
+
+/// A [`ResourceLoader`] which tries a sequence of loaders in order.
+///
+/// A loader failing with [`WCError::ResourceNotFound`] falls through
+/// to the next; any other error is returned immediately. If no loader
+/// has the resource, the last `ResourceNotFound` error is returned.
+///
+/// ## Example
+/// ```rust,ignore
+/// let mut loader = ChainResourceLoader::default()
+///     .with_loader(MemoryResourceLoader::new().with_resource(id, bytes))
+///     .with_loader(OfflineResourceLoader::new("/opt/vocabs"));
+/// ```
+#[derive(Default)]
+pub struct ChainResourceLoader {
+    loaders: Vec<Box<dyn ResourceLoader>>,
+}
+
+impl ChainResourceLoader {
+    /// Create a chain from a list of loaders.
+    pub fn new(loaders: Vec<Box<dyn ResourceLoader>>) -> Self {
+        Self { loaders }
+    }
+
+    /// Append a loader to the chain, returning the chain.
+    pub fn with_loader<L>(
+        mut self,
+        loader: L,
+    ) -> Self
+    where
+        L: ResourceLoader + 'static,
+    {
+        self.push(loader);
+        self
+    }
+
+    /// Append a loader to the chain.
+    pub fn push<L>(
+        &mut self,
+        loader: L,
+    ) where
+        L: ResourceLoader + 'static,
+    {
+        self.loaders.push(Box::new(loader));
+    }
+
+    /// The number of loaders in the chain.
+    pub fn len(&self) -> usize {
+        self.loaders.len()
+    }
+
+    /// Is the chain empty?
+    pub fn is_empty(&self) -> bool {
+        self.loaders.is_empty()
+    }
+
+    fn first_found<V>(
+        &mut self,
+        resource: &KeyedResource,
+        load: &mut dyn FnMut(&mut dyn ResourceLoader) -> WCResult<V>,
+    ) -> WCResult<V> {
+        let mut last_err = None;
+        for loader in self.loaders.iter_mut() {
+            match load(loader.as_mut()) {
+                Err(WCError::ResourceNotFound(msg)) => {
+                    last_err = Some(WCError::ResourceNotFound(msg));
+                }
+                result => return result,
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            WCError::ResourceNotFound(format!("no loaders for resource: {resource:?}"))
+        }))
+    }
+}
+
+impl ResourceLoader for ChainResourceLoader {
+    fn load_resource_path(
+        &mut self,
+        resource: &KeyedResource,
+    ) -> WCResult<PathBuf> {
+        self.first_found(resource, &mut |loader| loader.load_resource_path(resource))
+    }
+
+    fn open_resource(
+        &mut self,
+        resource: &KeyedResource,
+    ) -> WCResult<Box<dyn BufRead>> {
+        self.first_found(resource, &mut |loader| loader.open_resource(resource))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Read};
+
+    use super::*;
+    use crate::support::resources::{
+        ConstKeyedResource,
+        ConstUrlResource,
+        MemoryResourceLoader,
+        OfflineResourceLoader,
+    };
+
+    struct FailingLoader;
+
+    impl ResourceLoader for FailingLoader {
+        fn load_resource_path(
+            &mut self,
+            _resource: &KeyedResource,
+        ) -> WCResult<PathBuf> {
+            Err(WCError::External("boom".to_string()))
+        }
+    }
+
+    fn read_all(mut reader: Box<dyn BufRead>) -> String {
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_chain_loader() {
+        let a: KeyedResource = ConstKeyedResource {
+            key: &["k"],
+            resource: ConstUrlResource {
+                urls: &["https://example.invalid/a.txt"],
+                hash: None,
+            },
+        }
+        .into();
+        let b: KeyedResource = ConstKeyedResource {
+            key: &["k"],
+            resource: ConstUrlResource {
+                urls: &["https://example.invalid/b.txt"],
+                hash: None,
+            },
+        }
+        .into();
+
+        let mut empty = ChainResourceLoader::default();
+        assert!(empty.is_empty());
+        assert!(matches!(
+            empty.open_resource(&a),
+            Err(WCError::ResourceNotFound(_))
+        ));
+
+        let dir = tempdir::TempDir::new("chain_loader").unwrap();
+        fs::create_dir_all(dir.path().join("k")).unwrap();
+        fs::write(dir.path().join("k").join("b.txt"), "from disk").unwrap();
+
+        let mut loader = ChainResourceLoader::default()
+            .with_loader(
+                MemoryResourceLoader::new().with_resource("k/a.txt", b"from memory".as_slice()),
+            )
+            .with_loader(OfflineResourceLoader::new(dir.path()));
+        assert_eq!(loader.len(), 2);
+
+        assert_eq!(read_all(loader.open_resource(&a).unwrap()), "from memory");
+        assert_eq!(read_all(loader.open_resource(&b).unwrap()), "from disk");
+        assert_eq!(
+            loader.load_resource_path(&b).unwrap(),
+            dir.path().join("k").join("b.txt")
+        );
+
+        // Non-"not found" errors stop the chain.
+        loader.push(FailingLoader);
+        let c: KeyedResource = ConstKeyedResource {
+            key: &["k"],
+            resource: ConstUrlResource {
+                urls: &["https://example.invalid/c.txt"],
+                hash: None,
+            },
+        }
+        .into();
+        assert!(matches!(
+            loader.open_resource(&c),
+            Err(WCError::External(_))
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+//! # In-Memory Resource Loader
+
+use std::{io::BufRead, path::PathBuf};
+
+use crate::{
+    WCError,
+    WCResult,
+    alloc::sync::Arc,
+    prelude::*,
+    support::resources::{KeyedResource, ResourceLoader},
+    types::WCHashMap,
+};
+
+/// A [`ResourceLoader`] serving resource contents from memory.
+///
+/// Contents are keyed by [`KeyedResource::resource_id`],
+/// e.g. ``"openai/gpt2/vocab.bpe"``; which makes this loader
+/// suitable for tests, and for applications which embed their
+/// vocabularies with `include_bytes!`.
+///
+/// In-memory resources have no filesystem path; so
+/// [`load_resource_path`](ResourceLoader::load_resource_path)
+/// always fails with [`WCError::ResourceNotFound`], and consumers should use
+/// [`open_resource`](ResourceLoader::open_resource). In a
+/// [`ChainResourceLoader`](super::ChainResourceLoader), path lookups
+/// fall through to the next loader.
+///
+/// Contents are not checked against a resource
+/// [`hash`](crate::support::resources::UrlResource::hash).
+#[derive(Debug, Clone, Default)]
+pub struct MemoryResourceLoader {
+    resources: WCHashMap<String, Arc<[u8]>>,
+}
+
+impl MemoryResourceLoader {
+    /// Create an empty loader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a resource, returning the loader.
+    ///
+    /// ## Arguments
+    /// * `resource_id` - the [`KeyedResource::resource_id`] to serve.
+    /// * `contents` - the contents of the resource.
+    pub fn with_resource<S, B>(
+        mut self,
+        resource_id: S,
+        contents: B,
+    ) -> Self
+    where
+        S: Into<String>,
+        B: Into<Arc<[u8]>>,
+    {
+        self.insert(resource_id, contents);
+        self
+    }
+
+    /// Add a resource.
+    ///
+    /// ## Arguments
+    /// * `resource_id` - the [`KeyedResource::resource_id`] to serve.
+    /// * `contents` - the contents of the resource.
+    ///
+    /// ## Returns
+    /// The previous contents for `resource_id`, if any.
+    pub fn insert<S, B>(
+        &mut self,
+        resource_id: S,
+        contents: B,
+    ) -> Option<Arc<[u8]>>
+    where
+        S: Into<String>,
+        B: Into<Arc<[u8]>>,
+    {
+        self.resources.insert(resource_id.into(), contents.into())
+    }
+
+    /// Get the contents of a resource, if present.
+    pub fn get(
+        &self,
+        resource: &KeyedResource,
+    ) -> Option<&Arc<[u8]>> {
+        self.resources.get(&resource.resource_id()?)
+    }
+
+    /// The number of resources held.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Is the loader empty?
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    fn not_found(resource: &KeyedResource) -> WCError {
+        WCError::ResourceNotFound(format!(
+            "in-memory resource not found: {}",
+            resource
+                .resource_id()
+                .unwrap_or_else(|| format!("{resource:?}"))
+        ))
+    }
+}
+
+impl ResourceLoader for MemoryResourceLoader {
+    fn load_resource_path(
+        &mut self,
+        resource: &KeyedResource,
+    ) -> WCResult<PathBuf> {
+        match self.get(resource) {
+            None => Err(Self::not_found(resource)),
+            Some(_) => Err(WCError::ResourceNotFound(format!(
+                "in-memory resource has no path: {}",
+                resource.resource_id().unwrap_or_default()
+            ))),
+        }
+    }
+
+    fn open_resource(
+        &mut self,
+        resource: &KeyedResource,
+    ) -> WCResult<Box<dyn BufRead>> {
+        let contents = self
+            .get(resource)
+            .cloned()
+            .ok_or_else(|| Self::not_found(resource))?;
+        Ok(Box::new(std::io::Cursor::new(contents)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::support::resources::{ConstKeyedResource, ConstUrlResource};
+
+    #[test]
+    fn test_memory_loader() {
+        let res: KeyedResource = ConstKeyedResource {
+            key: &["vendor", "model"],
+            resource: ConstUrlResource {
+                urls: &["https://example.invalid/files/vocab.txt"],
+                hash: None,
+            },
+        }
+        .into();
+
+        let mut loader = MemoryResourceLoader::new();
+        assert!(loader.is_empty());
+        assert!(matches!(
+            loader.open_resource(&res),
+            Err(WCError::ResourceNotFound(_))
+        ));
+
+        let mut loader = loader.with_resource("vendor/model/vocab.txt", b"hello".as_slice());
+        assert_eq!(loader.len(), 1);
+        assert_eq!(
+            loader.get(&res).map(|b| b.as_ref()),
+            Some(b"hello".as_slice())
+        );
+
+        let mut buf = String::new();
+        loader
+            .open_resource(&res)
+            .unwrap()
+            .read_to_string(&mut buf)
+            .unwrap();
+        assert_eq!(buf, "hello");
+
+        // Present, but with no path.
+        assert!(matches!(
+            loader.load_resource_path(&res),
+            Err(WCError::ResourceNotFound(msg)) if msg.contains("has no path")
+        ));
+    }
+
+    #[test]
+    fn test_memory_loader_in_chain() {
+        use std::fs;
+
+        use crate::support::resources::{ChainResourceLoader, OfflineResourceLoader};
+
+        let res: KeyedResource = ConstKeyedResource {
+            key: &["vendor", "model"],
+            resource: ConstUrlResource {
+                urls: &["https://example.invalid/files/vocab.txt"],
+                hash: None,
+            },
+        }
+        .into();
+
+        let dir = tempdir::TempDir::new("memory_loader").unwrap();
+        let offline = OfflineResourceLoader::new(dir.path());
+        let path = offline.resource_path(&res).unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "on disk").unwrap();
+
+        let mut loader = ChainResourceLoader::default()
+            .with_loader(
+                MemoryResourceLoader::new()
+                    .with_resource("vendor/model/vocab.txt", b"in memory".as_slice()),
+            )
+            .with_loader(offline);
+
+        // Contents come from memory; paths from the next loader.
+        let mut buf = String::new();
+        loader
+            .open_resource(&res)
+            .unwrap()
+            .read_to_string(&mut buf)
+            .unwrap();
+        assert_eq!(buf, "in memory");
+        assert_eq!(loader.load_resource_path(&res).unwrap(), path);
+    }
+}
@@ -1,8 +1,30 @@
 //! # External Resource Management
+//!
+//! Resources are fetched through a [`ResourceLoader`]:
+//! * `WordchipperDiskCache` (with `download`) - a download cache;
+//!   which may be placed in offline mode.
+//! * [`OfflineResourceLoader`] - a pre-populated directory; never downloads.
+//! * [`MemoryResourceLoader`] - in-memory contents, for tests and embedding.
+//! * [`ChainResourceLoader`] - tries a sequence of loaders in order.
 
+#[cfg(feature = "std")]
+mod chain_loader;
+#[cfg(feature = "std")]
+mod memory_loader;
+#[cfg(feature = "std")]
+mod offline_loader;
 mod resource_loader;
 mod url_resource;
 
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use chain_loader::*;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use memory_loader::*;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use offline_loader::*;
 #[doc(inline)]
 pub use resource_loader::*;
 #[doc(inline)]
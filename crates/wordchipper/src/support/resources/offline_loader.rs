@@ -0,0 +1,164 @@
+//! # Offline Resource Loader
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    WCError,
+    WCResult,
+    support::{
+        hashing::file_sha256,
+        resources::{KeyedResource, ResourceLoader},
+    },
+};
+
+/// A [`ResourceLoader`] which only resolves resources from a local directory.
+///
+/// Resources are located at ``{root}/{key}/.../{file_name}``; the same layout
+/// used by the download cache, so a cache directory populated on a connected
+/// host can be copied to an air-gapped one and served from here.
+///
+/// This loader never touches the network; a missing resource fails
+/// immediately with [`WCError::ResourceNotFound`]. When the resource has a
+/// [`hash`](crate::support::resources::UrlResource::hash), the file must
+/// match that SHA-256 digest; a mismatch is also `ResourceNotFound`, so a
+/// [`ChainResourceLoader`](super::ChainResourceLoader) falls through to
+/// its next loader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineResourceLoader {
+    root: PathBuf,
+}
+
+impl OfflineResourceLoader {
+    /// Create a new loader rooted at `root`.
+    ///
+    /// ## Arguments
+    /// * `root` - the directory holding the resources.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Get the root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get the expected path of a resource.
+    ///
+    /// Does not check that the path exists.
+    ///
+    /// ## Returns
+    /// The path, or `None` if the resource has no file name.
+    pub fn resource_path(
+        &self,
+        resource: &KeyedResource,
+    ) -> Option<PathBuf> {
+        let file_name = resource.file_name()?;
+        let mut path = self.root.clone();
+        path.extend(&resource.key);
+        path.push(file_name);
+        Some(path)
+    }
+}
+
+impl ResourceLoader for OfflineResourceLoader {
+    fn load_resource_path(
+        &mut self,
+        resource: &KeyedResource,
+    ) -> WCResult<PathBuf> {
+        let path = self.resource_path(resource).ok_or_else(|| {
+            WCError::ResourceNotFound(format!("resource has no file name: {resource:?}"))
+        })?;
+
+        if !path.is_file() {
+            return Err(WCError::ResourceNotFound(format!(
+                "offline resource not found: {}",
+                path.display()
+            )));
+        }
+
+        if let Some(hash) = &resource.resource.hash
+            && !file_sha256(&path)?.eq_ignore_ascii_case(hash)
+        {
+            return Err(WCError::ResourceNotFound(format!(
+                "offline resource failed verification: {}",
+                path.display()
+            )));
+        }
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Read};
+
+    use super::*;
+    use crate::{
+        prelude::*,
+        support::resources::{ConstKeyedResource, ConstUrlResource},
+    };
+
+    const RES: ConstKeyedResource = ConstKeyedResource {
+        key: &["vendor", "model"],
+        resource: ConstUrlResource {
+            urls: &["https://example.invalid/files/vocab.txt"],
+            hash: None,
+        },
+    };
+
+    #[test]
+    fn test_offline_loader() {
+        let dir = tempdir::TempDir::new("offline_loader").unwrap();
+        let mut loader = OfflineResourceLoader::new(dir.path());
+        assert_eq!(loader.root(), dir.path());
+
+        let res: KeyedResource = RES.into();
+        let expected = dir.path().join("vendor").join("model").join("vocab.txt");
+        assert_eq!(loader.resource_path(&res), Some(expected.clone()));
+
+        assert!(matches!(
+            loader.load_resource_path(&res),
+            Err(WCError::ResourceNotFound(_))
+        ));
+
+        fs::create_dir_all(expected.parent().unwrap()).unwrap();
+        fs::write(&expected, "hello").unwrap();
+
+        assert_eq!(loader.load_resource_path(&res).unwrap(), expected);
+
+        let mut buf = String::new();
+        loader
+            .open_resource(&res)
+            .unwrap()
+            .read_to_string(&mut buf)
+            .unwrap();
+        assert_eq!(buf, "hello");
+    }
+
+    #[test]
+    fn test_offline_loader_hash() {
+        let dir = tempdir::TempDir::new("offline_loader").unwrap();
+        let mut loader = OfflineResourceLoader::new(dir.path());
+
+        let mut res: KeyedResource = RES.into();
+        let path = loader.resource_path(&res).unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "abc").unwrap();
+
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(file_sha256(&path).unwrap(), abc);
+
+        res.resource.hash = Some(abc.to_uppercase());
+        assert_eq!(loader.load_resource_path(&res).unwrap(), path);
+
+        res.resource.hash = Some(abc.replace('b', "c"));
+        assert!(matches!(
+            loader.load_resource_path(&res),
+            Err(WCError::ResourceNotFound(msg)) if msg.contains("failed verification")
+        ));
+        assert!(loader.open_resource(&res).is_err());
+    }
+}
@@ -1,9 +1,9 @@
 //! # Resource Loader
 
 #[cfg(feature = "std")]
-use std::path::PathBuf;
+use std::{io::BufRead, path::PathBuf};
 
-#[cfg(feature = "download")]
+#[cfg(feature = "std")]
 use crate::prelude::*;
 #[cfg(feature = "std")]
 use crate::support::resources::KeyedResource;
@@ -16,6 +16,22 @@ pub trait ResourceLoader {
         &mut self,
         resource: &KeyedResource,
     ) -> crate::WCResult<PathBuf>;
+
+    /// Open a reader over a resource's contents.
+    ///
+    /// The default implementation opens the file at
+    /// [`load_resource_path`](Self::load_resource_path);
+    /// loaders which are not backed by files override this.
+    #[cfg(feature = "std")]
+    fn open_resource(
+        &mut self,
+        resource: &KeyedResource,
+    ) -> crate::WCResult<Box<dyn BufRead>> {
+        let path = self.load_resource_path(resource)?;
+        Ok(Box::new(std::io::BufReader::new(std::fs::File::open(
+            path,
+        )?)))
+    }
 }
 
 #[cfg(feature = "download")]
@@ -25,7 +41,19 @@ impl ResourceLoader for crate::disk_cache::WordchipperDiskCache {
         &mut self,
         resource: &KeyedResource,
     ) -> crate::WCResult<PathBuf> {
-        self.load_cached_path(&resource.key, &resource.resource.urls, true)
-            .map_err(|e| crate::WCError::External(e.to_string()))
+        let download = !self.offline();
+        self.load_cached_path_verified(
+            &resource.key,
+            &resource.resource.urls,
+            download,
+            resource.resource.hash.as_deref(),
+        )
+        .map_err(|e| {
+            if download {
+                crate::WCError::External(e.to_string())
+            } else {
+                crate::WCError::ResourceNotFound(e.to_string())
+            }
+        })
     }
 }
@@ -60,6 +60,30 @@ pub struct KeyedResource {
     pub resource: UrlResource,
 }
 
+impl KeyedResource {
+    /// The file name of the resource.
+    ///
+    /// This is the final path segment of the first URL,
+    /// without any query or fragment; matching the name
+    /// used by the download cache.
+    pub fn file_name(&self) -> Option<&str> {
+        let url = self.resource.urls.first()?;
+        let url = url.split(['?', '#']).next().unwrap_or_default();
+        url.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+
+    /// The ``"{key}/.../{file_name}"`` path-like identifier of the resource.
+    ///
+    /// This is the relative location of the resource under a cache directory,
+    /// and the lookup key for in-memory loaders.
+    pub fn resource_id(&self) -> Option<String> {
+        let file_name = self.file_name()?;
+        let mut parts: Vec<&str> = self.key.iter().map(|s| s.as_str()).collect();
+        parts.push(file_name);
+        Some(parts.join("/"))
+    }
+}
+
 impl From<ConstKeyedResource> for KeyedResource {
     fn from(resource: ConstKeyedResource) -> Self {
         KeyedResource {
@@ -89,4 +113,31 @@ mod tests {
         assert_eq!(res.resource.urls, vec!["test_url".to_string()]);
         assert_eq!(res.resource.hash, Some("test_hash".to_string()));
     }
+
+    #[test]
+    fn test_resource_id() {
+        let res: KeyedResource = ConstKeyedResource {
+            key: &["openai", "gpt2"],
+            resource: ConstUrlResource {
+                urls: &[
+                    "https://example.com/a/vocab.bpe?sig=1",
+                    "https://mirror/b/other",
+                ],
+                hash: None,
+            },
+        }
+        .into();
+        assert_eq!(res.file_name(), Some("vocab.bpe"));
+        assert_eq!(res.resource_id(), Some("openai/gpt2/vocab.bpe".to_string()));
+
+        let res = KeyedResource {
+            key: vec!["x".to_string()],
+            resource: UrlResource {
+                urls: vec!["https://example.com/".to_string()],
+                hash: None,
+            },
+        };
+        assert_eq!(res.file_name(), None);
+        assert_eq!(res.resource_id(), None);
+    }
 }
@@ -1,5 +1,7 @@
 //! # String Utilities
 
+use core::fmt::Write;
+
 use crate::alloc::{borrow::Cow, string::String, vec::Vec};
 
 /// "stable" stub for for [`String::from_utf8_lossy`].
@@ -16,6 +18,43 @@ pub fn string_from_utf8_lossy(v: Vec<u8>) -> String {
     }
 }
 
+/// Render a byte span as a human-readable, single-line string.
+///
+/// Printable UTF-8 is kept as-is; everything else is escaped:
+/// * `' '` is rendered as `Ġ` (as in the GPT-2 byte-level views);
+/// * `'\n'`, `'\r'`, `'\t'` and `'\\'` use their backslash escapes;
+/// * other whitespace and control chars are rendered as `\u{..}`;
+/// * bytes which are not part of a complete UTF-8 sequence are rendered as `\xNN`.
+///
+/// ## Arguments
+/// * `bytes` - The byte span to render.
+///
+/// ## Returns
+/// The escaped rendering.
+pub fn escape_bytes_for_display(bytes: &[u8]) -> String {
+    // Writing to a `String` cannot fail.
+    let mut out = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                ' ' => out.push('Ġ'),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                '\\' => out.push_str("\\\\"),
+                c if c.is_whitespace() || c.is_control() => {
+                    write!(out, "\\u{{{:x}}}", c as u32).unwrap();
+                }
+                c => out.push(c),
+            }
+        }
+        for b in chunk.invalid() {
+            write!(out, "\\x{b:02x}").unwrap();
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,4 +70,17 @@ mod tests {
         let s = string_from_utf8_lossy(v);
         assert_eq!(s, "ab".to_string());
     }
+
+    #[test]
+    fn test_escape_bytes_for_display() {
+        assert_eq!(escape_bytes_for_display(b"hello"), "hello");
+        assert_eq!(escape_bytes_for_display(b" world"), "Ġworld");
+        assert_eq!(escape_bytes_for_display(b"a\n\r\tb\\"), "a\\n\\r\\tb\\\\");
+        assert_eq!(escape_bytes_for_display(b"\x00\x1b"), "\\u{0}\\u{1b}");
+        assert_eq!(escape_bytes_for_display("\u{a0}é".as_bytes()), "\\u{a0}é");
+
+        // A partial 'é' (0xC3 0xA9), and a stray continuation byte.
+        assert_eq!(escape_bytes_for_display(&[b'a', 0xc3]), "a\\xc3");
+        assert_eq!(escape_bytes_for_display(&[0xa9, b'b']), "\\xa9b");
+    }
 }
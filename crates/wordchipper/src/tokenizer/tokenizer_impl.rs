@@ -1,3 +1,5 @@
+use core::ops::Range;
+
 use crate::{
     TokenDecoder,
     TokenEncoder,
@@ -5,9 +7,9 @@ use crate::{
     UnifiedTokenVocab,
     WCResult,
     alloc::sync::Arc,
-    decoders::{BatchDecodeResult, DecodeResult},
+    decoders::{BatchDecodeResult, DecodeResult, TokenPiece, decode_pieces},
     prelude::*,
-    spanners::TextSpanner,
+    spanners::{SpanRef, TextNormalizer, TextSpanner},
 };
 
 /// Unified Tokenizer.
@@ -21,6 +23,8 @@ pub struct Tokenizer<T: TokenType> {
     vocab: Arc<UnifiedTokenVocab<T>>,
     encoder: Arc<dyn TokenEncoder<T>>,
     decoder: Arc<dyn TokenDecoder<T>>,
+    offsets_decoder: Option<Arc<dyn TokenDecoder<T>>>,
+    unknown_run_token: Option<T>,
 }
 
 impl<T: TokenType> Tokenizer<T> {
@@ -34,6 +38,40 @@ impl<T: TokenType> Tokenizer<T> {
             vocab,
             encoder,
             decoder,
+            offsets_decoder: None,
+            unknown_run_token: None,
+        }
+    }
+
+    /// Set the decoder of each token's bytes in the normalized text.
+    ///
+    /// Used by [`try_encode_with_offsets`](Self::try_encode_with_offsets);
+    /// for decoders which rewrite pieces (e.g. sentencepiece `▁` markers
+    /// decode as spaces). Defaults to the decoder.
+    pub fn with_offsets_decoder(
+        self,
+        offsets_decoder: Arc<dyn TokenDecoder<T>>,
+    ) -> Self {
+        Self {
+            offsets_decoder: Some(offsets_decoder),
+            ..self
+        }
+    }
+
+    /// Set the token which stands for a run of unknown chars within a span.
+    ///
+    /// Used by [`try_encode_with_offsets`](Self::try_encode_with_offsets);
+    /// for encoders which fuse unknown chars into one token, and continue
+    /// the span after it (e.g. the unigram `<unk>`). Its range ends where
+    /// the next token's bytes resume in the span. Other unknown tokens
+    /// (e.g. the wordpiece `[UNK]`) cover the rest of their span.
+    pub fn with_unknown_run_token(
+        self,
+        unknown_run_token: T,
+    ) -> Self {
+        Self {
+            unknown_run_token: Some(unknown_run_token),
+            ..self
         }
     }
 
@@ -51,6 +89,97 @@ impl<T: TokenType> Tokenizer<T> {
     pub fn decoder(&self) -> &Arc<dyn TokenDecoder<T>> {
         &self.decoder
     }
+
+    /// Decode each token into a [`TokenPiece`].
+    ///
+    /// Each piece carries the raw token bytes, an escaped display rendering,
+    /// and whether the token is a special token.
+    ///
+    /// ## Arguments
+    /// * `tokens` - The tokens to decode.
+    ///
+    /// ## Errors
+    /// [`WCError::UnknownToken`](crate::WCError::UnknownToken) if a token cannot be decoded.
+    pub fn decode_pieces(
+        &self,
+        tokens: &[T],
+    ) -> WCResult<Vec<TokenPiece<T>>> {
+        decode_pieces(self.decoder.as_ref(), self.vocab.special_vocab(), tokens)
+    }
+
+    /// Encode text, with the byte range of each token in the text.
+    ///
+    /// Tokens are encoded from the text as normalized by the spanning
+    /// [`TextNormalizer`](crate::spanners::TextNormalizer); their ranges are
+    /// mapped back to the original text through the normalization alignment.
+    /// Tokens produced from the same normalized char (e.g. the byte tokens
+    /// of a composed char) share its original range; an unknown token
+    /// covers the rest of its span, or its run
+    /// (see [`with_unknown_run_token`](Self::with_unknown_run_token)).
+    ///
+    /// ## Arguments
+    /// * `text` - The text to encode.
+    ///
+    /// ## Returns
+    /// The tokens, and the original byte range of each token.
+    pub fn try_encode_with_offsets(
+        &self,
+        text: &str,
+    ) -> WCResult<(Vec<T>, Vec<Range<usize>>)> {
+        let normalized = self
+            .vocab
+            .spanning()
+            .normalizer()
+            .normalize_around_specials(text, self.spanner().special_lexer());
+        let tokens = self.encoder.try_encode(text)?;
+        let offsets_decoder = self.offsets_decoder.as_ref().unwrap_or(&self.decoder);
+        let pieces = decode_pieces(
+            offsets_decoder.as_ref(),
+            self.vocab.special_vocab(),
+            &tokens,
+        )?;
+
+        // Gaps produce no tokens; so walk the token bytes span by span.
+        let mut spans = self
+            .spanner()
+            .split_spans(normalized.text())
+            .into_iter()
+            .filter(|span| !matches!(span, SpanRef::Gap(_)))
+            .map(Range::from);
+
+        let text_bytes = normalized.text().as_bytes();
+        let mut pos = 0;
+        let mut end = 0;
+        let offsets = pieces
+            .iter()
+            .enumerate()
+            .map(|(idx, piece)| {
+                if pos == end
+                    && let Some(span) = spans.next()
+                {
+                    (pos, end) = (span.start, span.end);
+                }
+                let start = pos;
+                let rest = &text_bytes[pos..end];
+                // A token never crosses its span. A token whose bytes are not
+                // the text (an unknown token, e.g. `[UNK]`) covers the rest of it;
+                // or, for an unknown run, the text up to the next token.
+                pos = match rest.starts_with(piece.bytes()) {
+                    true => pos + piece.bytes().len(),
+                    false if Some(piece.token()) == self.unknown_run_token => pieces
+                        .get(idx + 1)
+                        .map(|next| next.bytes())
+                        .filter(|next| !next.is_empty())
+                        .and_then(|next| (1..rest.len()).find(|&i| rest[i..].starts_with(next)))
+                        .map_or(end, |i| pos + i),
+                    false => end,
+                };
+                normalized.original_range(start..pos)
+            })
+            .collect();
+
+        Ok((tokens, offsets))
+    }
 }
 
 impl<T: TokenType> TokenEncoder<T> for Tokenizer<T> {
@@ -62,6 +191,10 @@ impl<T: TokenType> TokenEncoder<T> for Tokenizer<T> {
         self.encoder.special_vocab()
     }
 
+    fn normalizer(&self) -> Option<&TextNormalizer> {
+        self.encoder.normalizer()
+    }
+
     fn try_encode_append(
         &self,
         text: &str,
@@ -83,6 +216,28 @@ impl<T: TokenType> TokenEncoder<T> for Tokenizer<T> {
     ) -> WCResult<Vec<Vec<T>>> {
         self.encoder.try_encode_batch(batch)
     }
+
+    fn try_encode_bytes_append(
+        &self,
+        bytes: &[u8],
+        tokens: &mut Vec<T>,
+    ) -> WCResult<()> {
+        self.encoder.try_encode_bytes_append(bytes, tokens)
+    }
+
+    fn try_encode_bytes(
+        &self,
+        bytes: &[u8],
+    ) -> WCResult<Vec<T>> {
+        self.encoder.try_encode_bytes(bytes)
+    }
+
+    fn try_encode_bytes_batch(
+        &self,
+        batch: &[&[u8]],
+    ) -> WCResult<Vec<Vec<T>>> {
+        self.encoder.try_encode_bytes_batch(batch)
+    }
 }
 
 impl<T: TokenType> TokenDecoder<T> for Tokenizer<T> {
@@ -114,3 +269,58 @@ impl<T: TokenType> TokenDecoder<T> for Tokenizer<T> {
         self.decoder.try_decode_batch_to_strings(batch)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TokenizerOptions,
+        alloc::vec,
+        pretrained::openai::OA_CL100K_BASE_PATTERN,
+        spanners::{NormalizerStep, TextSpanningConfig},
+        vocab::utility::testing::{build_test_shift_byte_vocab, build_test_vocab},
+    };
+
+    #[test]
+    fn test_encode_with_offsets() {
+        type T = u16;
+
+        let config = TextSpanningConfig::from_pattern(OA_CL100K_BASE_PATTERN)
+            .with_normalizer([NormalizerStep::Nfkc, NormalizerStep::Lowercase]);
+        let mut vocab: UnifiedTokenVocab<T> =
+            build_test_vocab(build_test_shift_byte_vocab(10), config);
+        vocab.special_vocab_mut().add_str_word("<|END|>", 1000);
+
+        let tokenizer = TokenizerOptions::default()
+            .with_parallel(false)
+            .build(Arc::new(vocab));
+
+        // Special words are not normalized.
+        let text = "\u{ff28}ello WORLD\u{2163}<|END|>";
+        let (tokens, offsets) = tokenizer.try_encode_with_offsets(text).unwrap();
+        assert_eq!(tokens, tokenizer.try_encode(text).unwrap());
+        assert_eq!(tokens.last(), Some(&1000));
+        assert_eq!(
+            tokens,
+            tokenizer.try_encode("hello worldiv<|END|>").unwrap()
+        );
+
+        let pieces = tokenizer.decode_pieces(&tokens).unwrap();
+        let originals: Vec<(&str, &str)> = pieces
+            .iter()
+            .zip(offsets)
+            .map(|(piece, range)| (piece.display(), &text[range]))
+            .collect();
+        assert_eq!(
+            originals,
+            vec![
+                ("hello", "\u{ff28}ello"),
+                ("Ġ", " "),
+                ("world", "WORLD"),
+                ("i", "\u{2163}"),
+                ("v", "\u{2163}"),
+                ("<|END|>", "<|END|>"),
+            ]
+        );
+    }
+}
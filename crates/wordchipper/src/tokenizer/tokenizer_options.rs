@@ -4,7 +4,11 @@ use crate::{
     TokenType,
     Tokenizer,
     UnifiedTokenVocab,
+    WCResult,
     alloc::sync::Arc,
+    decoders::TokenDictDecoder,
+    support::parallel_backend::ParallelBackend,
+    vocab::{UnigramVocab, WordPieceVocab},
 };
 
 /// Options for configuring a [`Tokenizer`].
@@ -84,6 +88,35 @@ impl TokenizerOptions {
         self
     }
 
+    /// Gets the configured parallel backend of the encoder.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn parallel_backend(&self) -> ParallelBackend {
+        self.encoder.parallel_backend()
+    }
+
+    /// Sets the configured parallel backend on both encoder and decoder.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn set_parallel_backend(
+        &mut self,
+        parallel_backend: ParallelBackend,
+    ) {
+        self.encoder.set_parallel_backend(parallel_backend);
+        self.decoder.set_parallel_backend(parallel_backend);
+    }
+
+    /// Sets the configured parallel backend.
+    ///
+    /// Selects the threading implementation when [`parallel`](Self::parallel).
+    pub fn with_parallel_backend(
+        mut self,
+        parallel_backend: ParallelBackend,
+    ) -> Self {
+        self.set_parallel_backend(parallel_backend);
+        self
+    }
+
     /// Returns true if either parallel or concurrent is enabled.
     pub fn is_concurrent(&self) -> bool {
         self.concurrent() || self.parallel()
@@ -136,4 +169,46 @@ impl TokenizerOptions {
         )
         .into()
     }
+
+    /// Build a wordpiece [`Tokenizer`] for the given wordpiece vocab.
+    ///
+    /// The tokenizer's vocab is [`WordPieceVocab::to_unified_vocab`].
+    ///
+    /// ## Errors
+    /// If [`WordPieceVocab::to_unified_vocab`] fails.
+    pub fn build_wordpiece<T: TokenType>(
+        &self,
+        wordpiece_vocab: Arc<WordPieceVocab<T>>,
+    ) -> WCResult<Arc<Tokenizer<T>>> {
+        Ok(Tokenizer::new(
+            wordpiece_vocab.to_unified_vocab()?.into(),
+            self.encoder.build_wordpiece(wordpiece_vocab.clone())?,
+            self.decoder.build_wordpiece(wordpiece_vocab),
+        )
+        .into())
+    }
+
+    /// Build a unigram [`Tokenizer`] for the given unigram vocab.
+    ///
+    /// The tokenizer's vocab is [`UnigramVocab::to_unified_vocab`];
+    /// offsets are measured over the raw pieces, and a fused `<unk>`
+    /// covers its run of unknown chars.
+    ///
+    /// ## Errors
+    /// If [`UnigramVocab::to_unified_vocab`] fails.
+    pub fn build_unigram<T: TokenType>(
+        &self,
+        unigram_vocab: Arc<UnigramVocab<T>>,
+    ) -> WCResult<Arc<Tokenizer<T>>> {
+        let offsets_decoder = TokenDictDecoder::new(unigram_vocab.token_spans());
+        let unk_token = unigram_vocab.unk_token();
+        Ok(Tokenizer::new(
+            unigram_vocab.to_unified_vocab()?.into(),
+            self.encoder.build_unigram(unigram_vocab.clone())?,
+            self.decoder.build_unigram(unigram_vocab),
+        )
+        .with_offsets_decoder(Arc::new(offsets_decoder))
+        .with_unknown_run_token(unk_token)
+        .into())
+    }
 }
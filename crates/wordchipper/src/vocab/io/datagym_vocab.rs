@@ -1,6 +1,6 @@
 //! # `DataGym` Vocabulary
 
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
 use serde_json::Value;
 
@@ -181,14 +181,8 @@ pub fn read_datagym_vocab(
 pub fn load_gpt2_vocab<T: TokenType>(
     loader: &mut dyn ResourceLoader
 ) -> WCResult<UnifiedTokenVocab<T>> {
-    let vocab_path = loader.load_resource_path(&OA_GPT2_VOCAB_BPE_KEYED_RESOURCE.into())?;
-    let encoder_path = loader.load_resource_path(&OA_GPT2_ENCODER_JSON_KEYED_RESOURCE.into())?;
-
-    let vocab_file = std::fs::File::open(vocab_path)?;
-    let encoder_file = std::fs::File::open(encoder_path)?;
-
-    let mut vocab_reader = BufReader::new(vocab_file);
-    let mut encoder_reader = BufReader::new(encoder_file);
+    let mut vocab_reader = loader.open_resource(&OA_GPT2_VOCAB_BPE_KEYED_RESOURCE.into())?;
+    let mut encoder_reader = loader.open_resource(&OA_GPT2_ENCODER_JSON_KEYED_RESOURCE.into())?;
 
     let span_map = read_datagym_vocab(&mut vocab_reader, &mut encoder_reader, false)?;
 
@@ -30,9 +30,15 @@
 //! ```
 
 mod base64_vocab;
+mod sentencepiece_model;
+mod wordpiece_vocab;
 
 #[doc(inline)]
 pub use base64_vocab::*;
+#[doc(inline)]
+pub use sentencepiece_model::*;
+#[doc(inline)]
+pub use wordpiece_vocab::*;
 
 #[cfg(all(feature = "std", feature = "datagym"))]
 mod datagym_vocab;
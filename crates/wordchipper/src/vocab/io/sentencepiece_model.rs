@@ -0,0 +1,424 @@
+//! # Sentencepiece Model IO
+//!
+//! Reads the unigram model of a sentencepiece `.model` file; a
+//! `ModelProto` protobuf message, parsed here without a protobuf dependency.
+
+use std::{fs::File, io::Read, path::Path};
+
+use crate::{
+    TokenType,
+    WCError,
+    WCHashMap,
+    WCResult,
+    alloc::{format, string::String, vec::Vec},
+    spanners::{NormalizerStep, TextSpanningConfig},
+    vocab::{SENTENCEPIECE_PATTERN, SENTENCEPIECE_SPACE, UnigramVocab},
+};
+
+/// A protobuf wire value.
+enum ProtoValue<'a> {
+    Varint(u64),
+    Fixed64,
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// A protobuf wire reader over a message buffer.
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> ProtoReader<'a> {
+    fn read_varint(&mut self) -> WCResult<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.buf.split_first().ok_or_else(truncated)?;
+            self.buf = rest;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(WCError::Parse("protobuf varint overflow".into()))
+    }
+
+    fn read_bytes(
+        &mut self,
+        len: usize,
+    ) -> WCResult<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(truncated());
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    /// Read the next `(field number, value)`; or `None` at the end.
+    fn next_field(&mut self) -> WCResult<Option<(u64, ProtoValue<'a>)>> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        let key = self.read_varint()?;
+        let value = match key & 0x7 {
+            0 => ProtoValue::Varint(self.read_varint()?),
+            1 => {
+                self.read_bytes(8)?;
+                ProtoValue::Fixed64
+            }
+            2 => {
+                let len = self.read_varint()? as usize;
+                ProtoValue::Bytes(self.read_bytes(len)?)
+            }
+            5 => {
+                let bytes = self.read_bytes(4)?;
+                ProtoValue::Fixed32(u32::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            wire => {
+                return Err(WCError::Parse(format!(
+                    "unsupported protobuf wire type {wire}"
+                )));
+            }
+        };
+        Ok(Some((key >> 3, value)))
+    }
+}
+
+fn truncated() -> WCError {
+    WCError::Parse("truncated protobuf message".into())
+}
+
+/// `sentencepiece.Type`.
+const PIECE_NORMAL: u64 = 1;
+const PIECE_UNKNOWN: u64 = 2;
+const PIECE_CONTROL: u64 = 3;
+const PIECE_USER_DEFINED: u64 = 4;
+const PIECE_BYTE: u64 = 6;
+
+/// `TrainerSpec.ModelType`.
+const MODEL_UNIGRAM: u64 = 1;
+
+/// A `ModelProto.sentencepiece`.
+struct SentencePiece {
+    piece: String,
+    score: f32,
+    kind: u64,
+}
+
+fn parse_piece(buf: &[u8]) -> WCResult<SentencePiece> {
+    let mut piece = SentencePiece {
+        piece: String::new(),
+        score: 0.0,
+        kind: PIECE_NORMAL,
+    };
+    let mut reader = ProtoReader { buf };
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(bytes)) => {
+                piece.piece = String::from_utf8(bytes.to_vec())
+                    .map_err(|e| WCError::Parse(format!("invalid piece: {e}")))?;
+            }
+            (2, ProtoValue::Fixed32(bits)) => piece.score = f32::from_bits(bits),
+            (3, ProtoValue::Varint(kind)) => piece.kind = kind,
+            _ => (),
+        }
+    }
+    Ok(piece)
+}
+
+/// Build the normalizer steps of a `NormalizerSpec`.
+///
+/// The precompiled char map is not read; see [`read_sentencepiece_model`].
+fn parse_normalizer_spec(buf: &[u8]) -> WCResult<Vec<NormalizerStep>> {
+    let mut name = String::from("nmt_nfkc");
+    let mut add_dummy_prefix = true;
+    let mut remove_extra_whitespaces = true;
+    let mut escape_whitespaces = true;
+
+    let mut reader = ProtoReader { buf };
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(bytes)) => name = String::from_utf8_lossy(bytes).into(),
+            (2, ProtoValue::Bytes(bytes)) if !bytes.is_empty() => {
+                log::warn!(
+                    "sentencepiece precompiled char map is not supported; \
+                     approximating the {name:?} normalizer by its named rules"
+                );
+            }
+            (3, ProtoValue::Varint(v)) => add_dummy_prefix = v != 0,
+            (4, ProtoValue::Varint(v)) => remove_extra_whitespaces = v != 0,
+            (5, ProtoValue::Varint(v)) => escape_whitespaces = v != 0,
+            _ => (),
+        }
+    }
+
+    let mut steps = Vec::new();
+    // The precompiled char map is approximated by its named rules.
+    if name.contains("nfkc") {
+        steps.push(NormalizerStep::Nfkc);
+    }
+    if name.ends_with("_cf") {
+        steps.push(NormalizerStep::Lowercase);
+    }
+    if remove_extra_whitespaces {
+        steps.extend([NormalizerStep::CollapseWhitespace, NormalizerStep::Trim]);
+    }
+    if add_dummy_prefix {
+        steps.push(NormalizerStep::prepend(" "));
+    }
+    if escape_whitespaces {
+        steps.push(NormalizerStep::replace(" ", SENTENCEPIECE_SPACE));
+    }
+    Ok(steps)
+}
+
+/// Load a [`UnigramVocab`] from a sentencepiece `.model` file.
+///
+/// See [`read_sentencepiece_model`].
+///
+/// ## Arguments
+/// * `path` - the path to the model file.
+pub fn load_sentencepiece_model_path<T: TokenType>(
+    path: impl AsRef<Path>
+) -> WCResult<UnigramVocab<T>> {
+    let mut reader = File::open(path)?;
+    read_sentencepiece_model(&mut reader)
+}
+
+/// Read a [`UnigramVocab`] from a sentencepiece model reader.
+///
+/// Only unigram models are supported. A piece's token is its index;
+/// unknown, control and user-defined pieces become specials; unused and
+/// byte pieces keep their indices but are never emitted.
+///
+/// The normalizer is built from the `NormalizerSpec` name and flags.
+///
+/// This is an approximation of sentencepiece; the tokens can differ:
+/// * The precompiled char map is not read. A `*nfkc*` name is approximated
+///   by NFKC, and `*_cf` by lowercasing; the map's other rules (e.g. the
+///   `nmt` control char removal) are not applied, nor are custom maps.
+/// * Byte fallback is not supported; chars with no piece encode as the
+///   unknown piece, rather than as byte pieces.
+///
+/// Both are logged as warnings, when the model uses them.
+///
+/// ## Arguments
+/// * `reader` - the model reader.
+pub fn read_sentencepiece_model<T: TokenType>(reader: &mut dyn Read) -> WCResult<UnigramVocab<T>> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+
+    let mut pieces = Vec::new();
+    let mut model_type = MODEL_UNIGRAM;
+    let mut steps = None;
+
+    let mut proto = ProtoReader { buf: &buf };
+    while let Some((field, value)) = proto.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(bytes)) => pieces.push(parse_piece(bytes)?),
+            (2, ProtoValue::Bytes(bytes)) => {
+                let mut trainer = ProtoReader { buf: bytes };
+                while let Some((field, value)) = trainer.next_field()? {
+                    if let (3, ProtoValue::Varint(v)) = (field, value) {
+                        model_type = v;
+                    }
+                }
+            }
+            (3, ProtoValue::Bytes(bytes)) => steps = Some(parse_normalizer_spec(bytes)?),
+            _ => (),
+        }
+    }
+    if model_type != MODEL_UNIGRAM {
+        return Err(WCError::Parse(format!(
+            "unsupported sentencepiece model type {model_type}; expected unigram"
+        )));
+    }
+
+    let steps = match steps {
+        Some(steps) => steps,
+        None => parse_normalizer_spec(&[])?,
+    };
+    let mut spanning =
+        TextSpanningConfig::from_pattern(SENTENCEPIECE_PATTERN).with_normalizer(steps);
+    let mut piece_map = WCHashMap::default();
+    let mut unk_token = None;
+    let byte_pieces = pieces.iter().filter(|p| p.kind == PIECE_BYTE).count();
+    if byte_pieces > 0 {
+        log::warn!(
+            "sentencepiece byte fallback is not supported; \
+             ignoring {byte_pieces} byte pieces"
+        );
+    }
+    for (idx, piece) in pieces.into_iter().enumerate() {
+        let token = T::from_usize(idx).ok_or(WCError::TokenOutOfRange)?;
+        match piece.kind {
+            PIECE_NORMAL => {
+                piece_map.insert(piece.piece.into_bytes(), (token, piece.score));
+            }
+            PIECE_UNKNOWN | PIECE_CONTROL | PIECE_USER_DEFINED => {
+                if piece.kind == PIECE_UNKNOWN {
+                    unk_token = Some(token);
+                }
+                spanning.specials_mut().add_str_word(&piece.piece, token);
+            }
+            _ => (),
+        }
+    }
+    let unk_token = unk_token
+        .ok_or_else(|| WCError::VocabConflict("no unknown piece in sentencepiece model".into()))?;
+
+    UnigramVocab::new(spanning, piece_map, unk_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{TokenDecoder, TokenEncoder, TokenizerOptions};
+
+    fn put_varint(
+        buf: &mut Vec<u8>,
+        mut value: u64,
+    ) {
+        while value >= 0x80 {
+            buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        buf.push(value as u8);
+    }
+
+    fn put_bytes(
+        buf: &mut Vec<u8>,
+        field: u64,
+        bytes: &[u8],
+    ) {
+        put_varint(buf, (field << 3) | 2);
+        put_varint(buf, bytes.len() as u64);
+        buf.extend_from_slice(bytes);
+    }
+
+    fn model_proto(
+        pieces: &[(&str, f32, u64)],
+        model_type: u64,
+    ) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for &(piece, score, kind) in pieces {
+            let mut msg = Vec::new();
+            put_bytes(&mut msg, 1, piece.as_bytes());
+            put_varint(&mut msg, (2 << 3) | 5);
+            msg.extend_from_slice(&score.to_le_bytes());
+            put_varint(&mut msg, 3 << 3);
+            put_varint(&mut msg, kind);
+            put_bytes(&mut buf, 1, &msg);
+        }
+
+        let mut trainer = Vec::new();
+        put_varint(&mut trainer, 3 << 3);
+        put_varint(&mut trainer, model_type);
+        // An ignored fixed64 field.
+        put_varint(&mut trainer, (9 << 3) | 1);
+        trainer.extend_from_slice(&[0; 8]);
+        put_bytes(&mut buf, 2, &trainer);
+
+        let mut normalizer = Vec::new();
+        put_bytes(&mut normalizer, 1, b"nmt_nfkc");
+        put_bytes(&mut buf, 3, &normalizer);
+        buf
+    }
+
+    const PIECES: &[(&str, f32, u64)] = &[
+        ("<unk>", 0.0, PIECE_UNKNOWN),
+        ("<s>", 0.0, PIECE_CONTROL),
+        ("</s>", 0.0, PIECE_CONTROL),
+        ("<0x41>", 0.0, PIECE_BYTE),
+        ("\u{2581}", -2.0, PIECE_NORMAL),
+        ("\u{2581}hello", -3.0, PIECE_NORMAL),
+        ("\u{2581}wor", -4.0, PIECE_NORMAL),
+        ("ld", -4.0, PIECE_NORMAL),
+        ("l", -5.0, PIECE_NORMAL),
+        ("d", -5.0, PIECE_NORMAL),
+    ];
+
+    #[test]
+    fn test_read_sentencepiece_model() {
+        let proto = model_proto(PIECES, MODEL_UNIGRAM);
+        let vocab: UnigramVocab<u32> = read_sentencepiece_model(&mut Cursor::new(proto)).unwrap();
+
+        assert_eq!(vocab.pieces().len(), 6);
+        assert_eq!(vocab.spanning().specials().len(), 3);
+        assert_eq!(vocab.unk_token(), 0);
+        assert_eq!(
+            vocab.lookup_piece("\u{2581}wor".as_bytes()),
+            Some((6, -4.0))
+        );
+        assert_eq!(vocab.lookup_piece(b"<0x41>"), None);
+        assert_eq!(vocab.unk_score(), -15.0);
+
+        let proto = model_proto(PIECES, 2);
+        assert!(read_sentencepiece_model::<u32>(&mut Cursor::new(proto)).is_err());
+
+        let mut proto = model_proto(PIECES, MODEL_UNIGRAM);
+        proto.truncate(proto.len() - 3);
+        assert!(read_sentencepiece_model::<u32>(&mut Cursor::new(proto)).is_err());
+    }
+
+    #[test]
+    fn test_load_sentencepiece_model() {
+        let dir = tempdir::TempDir::new("sentencepiece_model_test").unwrap();
+        let path = dir.path().join("spiece.model");
+        std::fs::write(&path, model_proto(PIECES, MODEL_UNIGRAM)).unwrap();
+
+        let vocab: UnigramVocab<u32> = load_sentencepiece_model_path(&path).unwrap();
+        let tokenizer = TokenizerOptions::default()
+            .build_unigram(vocab.into())
+            .unwrap();
+
+        let text = "<s> hello  world \u{3a9}</s>";
+        let tokens = tokenizer.try_encode(text).unwrap();
+        assert_eq!(tokens, vec![1, 5, 6, 7, 4, 0, 2]);
+        assert_eq!(
+            tokenizer.try_decode_to_string(&tokens).unwrap().unwrap(),
+            "<s> hello world <unk></s>"
+        );
+
+        let (_, offsets) = tokenizer.try_encode_with_offsets(text).unwrap();
+        // The dummy prefix maps to the empty range before the word.
+        assert_eq!(offsets[1], 4..9);
+        assert_eq!(offsets[2], 9..14);
+        assert_eq!(offsets[3], 14..16);
+        assert_eq!(offsets[4], 16..17);
+        assert_eq!(offsets[5], 17..19);
+    }
+
+    #[test]
+    fn test_unigram_offsets() {
+        let proto = model_proto(PIECES, MODEL_UNIGRAM);
+        let vocab: UnigramVocab<u32> = read_sentencepiece_model(&mut Cursor::new(proto)).unwrap();
+        let tokenizer = TokenizerOptions::default()
+            .build_unigram(vocab.into())
+            .unwrap();
+
+        // A fused `<unk>` covers its run of unknown chars; and no more.
+        let text = "hello \u{65e5}\u{672c}\u{8a9e}ld \u{65e5}\u{672c} world";
+        let (tokens, offsets) = tokenizer.try_encode_with_offsets(text).unwrap();
+        let pieces: Vec<(u32, &str)> = tokens
+            .iter()
+            .zip(offsets)
+            .map(|(&t, r)| (t, &text[r]))
+            .collect();
+        assert_eq!(
+            pieces,
+            vec![
+                (5, "hello"),
+                (4, " "),
+                (0, "\u{65e5}\u{672c}\u{8a9e}"),
+                (7, "ld"),
+                (4, " "),
+                (0, "\u{65e5}\u{672c}"),
+                (6, " wor"),
+                (7, "ld"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! # Wordpiece Vocabulary IO
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use crate::{
+    TokenType,
+    WCResult,
+    prelude::*,
+    spanners::TextSpanningConfig,
+    vocab::{BERT_SPECIAL_WORDS, DEFAULT_WORDPIECE_UNK_WORD, WordPieceVocab, bert_spanning_config},
+};
+
+/// Load a BERT [`WordPieceVocab`] from a `vocab.txt` file.
+///
+/// Uses [`bert_spanning_config`] and the [`BERT_SPECIAL_WORDS`].
+///
+/// ## Arguments
+/// * `path` - the path to the vocabulary file.
+/// * `uncased` - lowercase and strip accents, as for `*-uncased` models.
+pub fn load_bert_vocab_path<T: TokenType>(
+    path: impl AsRef<Path>,
+    uncased: bool,
+) -> WCResult<WordPieceVocab<T>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_wordpiece_vocab(
+        &mut reader,
+        bert_spanning_config(uncased),
+        &BERT_SPECIAL_WORDS,
+        DEFAULT_WORDPIECE_UNK_WORD,
+    )
+}
+
+/// Read a [`WordPieceVocab`] from a `vocab.txt` line reader.
+///
+/// Lines are one word each; a word's token is its line index:
+/// ```terminaloutput
+/// [PAD]
+/// ...
+/// ##ing
+/// ```
+///
+/// ## Arguments
+/// * `reader` - the line reader.
+/// * `spanning` - the spanners configuration.
+/// * `special_words` - the words to treat as specials.
+/// * `unk_word` - the unknown word.
+pub fn read_wordpiece_vocab<T: TokenType>(
+    reader: &mut dyn BufRead,
+    spanning: TextSpanningConfig<T>,
+    special_words: &[&str],
+    unk_word: &str,
+) -> WCResult<WordPieceVocab<T>> {
+    let words = reader.lines().collect::<Result<Vec<String>, _>>()?;
+    WordPieceVocab::from_words(
+        spanning,
+        words.iter().map(|w| w.trim_end_matches('\r')),
+        special_words,
+        unk_word,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{TokenDecoder, TokenEncoder, TokenizerOptions, VocabIndex};
+
+    const VOCAB_TXT: &str =
+        "[PAD]\n[UNK]\n[CLS]\n[SEP]\n[MASK]\nhello\n##s\nworld\n!\nun\n##aff\n##able\n";
+
+    #[test]
+    fn test_read_wordpiece_vocab() {
+        let vocab: WordPieceVocab<u32> = read_wordpiece_vocab(
+            &mut Cursor::new(VOCAB_TXT.replace('\n', "\r\n")),
+            bert_spanning_config(true),
+            &BERT_SPECIAL_WORDS,
+            DEFAULT_WORDPIECE_UNK_WORD,
+        )
+        .unwrap();
+        assert_eq!(vocab.len(), 12);
+        assert_eq!(vocab.lookup_piece(b"##aff"), Some(10));
+        assert_eq!(vocab.unk_token(), 1);
+    }
+
+    #[test]
+    fn test_load_bert_vocab() {
+        let dir = tempdir::TempDir::new("wordpiece_vocab_test").unwrap();
+        let path = dir.path().join("vocab.txt");
+        std::fs::write(&path, VOCAB_TXT).unwrap();
+
+        let vocab: WordPieceVocab<u32> = load_bert_vocab_path(&path, true).unwrap();
+        let tokenizer = TokenizerOptions::default()
+            .build_wordpiece(vocab.into())
+            .unwrap();
+
+        let text = "[CLS] Hellos, World! Unaffable[SEP]";
+        let tokens = tokenizer.try_encode(text).unwrap();
+        assert_eq!(tokens, vec![2, 5, 6, 1, 7, 8, 9, 10, 11, 3]);
+        assert_eq!(
+            tokenizer.try_decode_to_string(&tokens).unwrap().unwrap(),
+            "[CLS] hellos [UNK] world ! unaffable [SEP]"
+        );
+
+        let (_, offsets) = tokenizer.try_encode_with_offsets(text).unwrap();
+        assert_eq!(offsets[1], 6..11);
+        assert_eq!(offsets[2], 11..12);
+        // `[UNK]` covers the whole word.
+        assert_eq!(offsets[3], 12..13);
+        assert_eq!(offsets[4], 14..19);
+    }
+
+    #[test]
+    fn test_wordpiece_offsets() {
+        let vocab: WordPieceVocab<u32> = read_wordpiece_vocab(
+            &mut Cursor::new(VOCAB_TXT),
+            bert_spanning_config(false),
+            &BERT_SPECIAL_WORDS,
+            DEFAULT_WORDPIECE_UNK_WORD,
+        )
+        .unwrap();
+        let tokenizer = TokenizerOptions::default()
+            .build_wordpiece(vocab.into())
+            .unwrap();
+
+        // `[UNK]` covers its whole word, however long; later offsets are unshifted.
+        let text = "qwertyuiop hellos x world";
+        let (tokens, offsets) = tokenizer.try_encode_with_offsets(text).unwrap();
+        assert_eq!(tokens, vec![1, 5, 6, 1, 7]);
+        let words: Vec<&str> = offsets.into_iter().map(|r| &text[r]).collect();
+        assert_eq!(words, vec!["qwertyuiop", "hello", "s", "x", "world"]);
+    }
+}
@@ -31,23 +31,42 @@
 //! to produce a [`UnifiedTokenVocab<T>`].
 //!
 //! A number of IO helpers are provided in [`io`].
+//!
+//! ## Wordpiece Vocabulary
+//!
+//! [`WordPieceVocab<T>`] holds a BERT-style wordpiece model; it is
+//! encoded by a [`WordPieceSpanEncoder`](crate::encoders::token_span_encoder::span_encoders::WordPieceSpanEncoder),
+//! and builds a [`Tokenizer`](crate::Tokenizer) with
+//! [`TokenizerOptions::build_wordpiece`](crate::TokenizerOptions::build_wordpiece).
+//!
+//! ## Unigram Vocabulary
+//!
+//! [`UnigramVocab<T>`] holds a unigram language model, e.g. a sentencepiece
+//! unigram model; it is encoded by a [`UnigramSpanEncoder`](crate::encoders::token_span_encoder::span_encoders::UnigramSpanEncoder),
+//! and builds a [`Tokenizer`](crate::Tokenizer) with
+//! [`TokenizerOptions::build_unigram`](crate::TokenizerOptions::build_unigram).
 #[cfg(feature = "std")]
 pub mod io;
 pub mod utility;
 
 mod byte_vocab;
 mod pair_vocab;
+mod span_table;
 mod span_vocab;
 mod special_vocab;
 mod token_vocab;
 mod unified_vocab;
+mod unigram_vocab;
 mod vocab_types;
+mod wordpiece_vocab;
 
 #[doc(inline)]
 pub use byte_vocab::*;
 #[doc(inline)]
 pub use pair_vocab::*;
 #[doc(inline)]
+pub use span_table::*;
+#[doc(inline)]
 pub use span_vocab::*;
 #[doc(inline)]
 pub use special_vocab::*;
@@ -56,7 +75,11 @@ pub use token_vocab::*;
 #[doc(inline)]
 pub use unified_vocab::*;
 #[doc(inline)]
+pub use unigram_vocab::*;
+#[doc(inline)]
 pub use vocab_types::*;
+#[doc(inline)]
+pub use wordpiece_vocab::*;
 
 /// Expected bytes/token ratio.
 ///
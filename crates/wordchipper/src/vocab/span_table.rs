@@ -0,0 +1,331 @@
+//! # Compact Span Lookup Table ``{ &[u8] -> T }``
+
+use core::fmt;
+
+use crate::{
+    alloc::{vec, vec::Vec},
+    types::TokenType,
+    vocab::SpanTokenMap,
+};
+
+/// Spans up to this length are keyed inline, by their bytes.
+pub const INLINE_SPAN_BYTES: usize = 8;
+
+/// Multiplicative hashing constant (the 64-bit golden ratio).
+const HASH_MUL: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Read up to 8 bytes as a native-endian word; zero padded.
+///
+/// Native order keeps an inline key's bytes equal to its span's bytes.
+#[inline(always)]
+fn read_word(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_ne_bytes(word)
+}
+
+/// The slot key of a span.
+///
+/// Inline spans are keyed by their bytes; longer spans by a hash.
+/// Keys are only compared between spans of the same length.
+#[inline(always)]
+fn span_key(span: &[u8]) -> u64 {
+    if span.len() <= INLINE_SPAN_BYTES {
+        return read_word(span);
+    }
+    span.chunks(8)
+        .fold((span.len() as u64).wrapping_mul(HASH_MUL), |h, chunk| {
+            (h.rotate_left(23) ^ read_word(chunk)).wrapping_mul(HASH_MUL)
+        })
+}
+
+/// A table slot; `len == 0` marks an empty slot.
+///
+/// The key is kept as native-endian bytes; so an inline span
+/// is the first `len` bytes of its key.
+#[derive(Debug, Clone, Copy)]
+struct Slot<T> {
+    key: [u8; 8],
+    len: u32,
+    offset: u32,
+    token: T,
+}
+
+/// A compact, read-only ``{ &[u8] -> T }`` lookup table.
+///
+/// An open-addressing (linear probing) table over a flat byte arena:
+/// * spans of up to [`INLINE_SPAN_BYTES`] are keyed by their bytes, and
+///   match on the slot alone;
+/// * longer spans are keyed by a hash, and their bytes live in the arena.
+///
+/// Compared to a [`SpanTokenMap`], there is no allocation per span;
+/// and most lookups touch one slot, without hashing a heap key.
+///
+/// Empty spans are never stored. Equality compares the stored spans,
+/// not the slot layout.
+///
+/// ## Style Hints
+///
+/// Instance names should prefer `span_table`.
+#[derive(Clone)]
+pub struct SpanTokenTable<T: TokenType> {
+    /// Power-of-two slots; at most 2/3 full.
+    slots: Vec<Slot<T>>,
+
+    /// The bytes of the non-inline spans.
+    arena: Vec<u8>,
+
+    /// `64 - log2(slots.len())`; shifts a hash to a slot index.
+    shift: u32,
+
+    /// The stored span count.
+    len: usize,
+}
+
+impl<T: TokenType> Default for SpanTokenTable<T> {
+    fn default() -> Self {
+        Self::from_span_pairs(Vec::new())
+    }
+}
+
+impl<T: TokenType> fmt::Debug for SpanTokenTable<T> {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("SpanTokenTable")
+            .field("len", &self.len)
+            .field("slots", &self.slots.len())
+            .field("arena_bytes", &self.arena.len())
+            .finish()
+    }
+}
+
+impl<T: TokenType> PartialEq for SpanTokenTable<T> {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.len == other.len
+            && self
+                .iter()
+                .all(|(span, &token)| other.get(span) == Some(token))
+    }
+}
+
+impl<T: TokenType> From<&SpanTokenMap<T>> for SpanTokenTable<T> {
+    fn from(span_map: &SpanTokenMap<T>) -> Self {
+        Self::from_span_pairs(
+            span_map
+                .iter()
+                .map(|(span, &token)| (span.as_slice(), token))
+                .collect(),
+        )
+    }
+}
+
+impl<T: TokenType> SpanTokenTable<T> {
+    /// Build a table from span/token pairs.
+    ///
+    /// ## Arguments
+    /// * `pairs` - the span/token pairs; spans must be unique,
+    ///   empty spans are skipped.
+    ///
+    /// ## Panics
+    /// If the total non-inline span bytes exceed `u32::MAX`.
+    pub fn from_span_pairs(pairs: Vec<(&[u8], T)>) -> Self {
+        let capacity = (pairs.len() * 3 / 2 + 1).next_power_of_two().max(8);
+        let mut table = Self {
+            slots: vec![
+                Slot {
+                    key: [0; 8],
+                    len: 0,
+                    offset: 0,
+                    token: T::zero(),
+                };
+                capacity
+            ],
+            arena: Vec::new(),
+            shift: 64 - capacity.trailing_zeros(),
+            len: 0,
+        };
+
+        for (span, token) in pairs {
+            if span.is_empty() {
+                continue;
+            }
+            let key = span_key(span);
+            let mut offset = 0;
+            if span.len() > INLINE_SPAN_BYTES {
+                offset = u32::try_from(table.arena.len()).expect("span arena overflow");
+                table.arena.extend_from_slice(span);
+            }
+
+            let mut idx = table.slot_index(key, span.len());
+            while table.slots[idx].len != 0 {
+                idx = (idx + 1) & (capacity - 1);
+            }
+            table.slots[idx] = Slot {
+                key: key.to_ne_bytes(),
+                len: span.len() as u32,
+                offset,
+                token,
+            };
+            table.len += 1;
+        }
+        table.arena.shrink_to_fit();
+        table
+    }
+
+    /// The home slot of a key.
+    #[inline(always)]
+    fn slot_index(
+        &self,
+        key: u64,
+        len: usize,
+    ) -> usize {
+        ((key ^ (len as u64).rotate_right(8)).wrapping_mul(HASH_MUL) >> self.shift) as usize
+    }
+
+    /// Get the number of stored spans.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the table empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the approximate heap size, in bytes.
+    pub fn heap_bytes(&self) -> usize {
+        self.slots.len() * size_of::<Slot<T>>() + self.arena.len()
+    }
+
+    /// Iterate over the stored spans and their tokens; in no particular order.
+    ///
+    /// Inline spans are read from their slot keys, longer spans from the arena.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &T)> {
+        self.slots.iter().filter(|slot| slot.len != 0).map(|slot| {
+            let len = slot.len as usize;
+            let span = match len <= INLINE_SPAN_BYTES {
+                true => &slot.key[..len],
+                false => &self.arena[slot.offset as usize..slot.offset as usize + len],
+            };
+            (span, &slot.token)
+        })
+    }
+
+    /// Return the associated token for the span, if any.
+    ///
+    /// ## Arguments
+    /// * `span` - The byte slice to look up.
+    ///
+    /// ## Returns
+    /// An `Option` containing the token if the span exists in the table.
+    #[inline]
+    pub fn get(
+        &self,
+        span: &[u8],
+    ) -> Option<T> {
+        if span.is_empty() {
+            return None;
+        }
+        let key = span_key(span);
+        let mask = self.slots.len() - 1;
+        let mut idx = self.slot_index(key, span.len());
+        loop {
+            let slot = &self.slots[idx];
+            if slot.len == 0 {
+                return None;
+            }
+            if slot.len as usize == span.len()
+                && u64::from_ne_bytes(slot.key) == key
+                && (span.len() <= INLINE_SPAN_BYTES
+                    || &self.arena[slot.offset as usize..slot.offset as usize + span.len()] == span)
+            {
+                return Some(slot.token);
+            }
+            idx = (idx + 1) & mask;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alloc::format;
+
+    #[test]
+    fn test_span_table() {
+        type T = u32;
+
+        let mut span_map: SpanTokenMap<T> = Default::default();
+        for i in 0..2000_u32 {
+            span_map.insert(format!("w{i}").into_bytes(), i);
+            span_map.insert(
+                format!("a much longer span, number {i}").into_bytes(),
+                10_000 + i,
+            );
+        }
+        span_map.insert(b"12345678".to_vec(), 90_000);
+        span_map.insert(b"123456789".to_vec(), 90_001);
+        span_map.insert(b"\x00".to_vec(), 90_002);
+        span_map.insert(Vec::new(), 90_003);
+
+        let span_table = SpanTokenTable::from(&span_map);
+        assert_eq!(span_table.len(), span_map.len() - 1);
+        assert!(!span_table.is_empty());
+        assert!(span_table.heap_bytes() > 0);
+
+        for (span, &token) in &span_map {
+            match span.is_empty() {
+                true => assert_eq!(span_table.get(span), None),
+                false => assert_eq!(span_table.get(span), Some(token), "{span:?}"),
+            }
+        }
+
+        let mut pairs: Vec<(&[u8], T)> = span_table.iter().map(|(s, &t)| (s, t)).collect();
+        pairs.sort();
+        let mut expected: Vec<(&[u8], T)> = span_map
+            .iter()
+            .filter(|(span, _)| !span.is_empty())
+            .map(|(span, &token)| (span.as_slice(), token))
+            .collect();
+        expected.sort();
+        assert_eq!(pairs, expected);
+
+        // Equality is by content; not by insertion order.
+        pairs.reverse();
+        assert_eq!(SpanTokenTable::from_span_pairs(pairs.clone()), span_table);
+        pairs[0].1 += 1;
+        assert_ne!(SpanTokenTable::from_span_pairs(pairs), span_table);
+
+        // Inline keys are zero padded; the length disambiguates.
+        assert_eq!(span_table.get(b"\x00\x00"), None);
+        assert_eq!(span_table.get(b"1234567"), None);
+        assert_eq!(span_table.get(b"1234567890"), None);
+        assert_eq!(span_table.get(b"a much longer span, number 2000"), None);
+        assert_eq!(span_table.get(b"w2000"), None);
+    }
+
+    #[test]
+    fn test_empty_table() {
+        let span_table = SpanTokenTable::<u16>::default();
+        assert!(span_table.is_empty());
+        assert_eq!(span_table.get(b"abc"), None);
+        assert_eq!(span_table.get(b"a much longer span"), None);
+        assert_eq!(
+            format!("{span_table:?}"),
+            "SpanTokenTable { len: 0, slots: 8, arena_bytes: 0 }"
+        );
+
+        let span_table = SpanTokenTable::from_span_pairs(vec![(b"abc".as_slice(), 7_u16)]);
+        assert_eq!(span_table.get(b"abc"), Some(7));
+        assert_eq!(
+            span_table.iter().collect::<Vec<_>>(),
+            vec![(b"abc".as_slice(), &7)]
+        );
+        assert_ne!(span_table, SpanTokenTable::default());
+    }
+}
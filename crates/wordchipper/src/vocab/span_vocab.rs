@@ -1,5 +1,7 @@
 //! # Word Map ``{ Vec<u8> -> T }`` Token Vocabulary
 
+use once_cell::sync::OnceCell;
+
 use crate::{
     WCResult,
     alloc::vec::Vec,
@@ -10,19 +12,35 @@ use crate::{
         PairMapVocab,
         PairTokenMap,
         SpanTokenMap,
+        SpanTokenTable,
         VocabIndex,
         utility::validators::try_vocab_size,
     },
 };
 
 /// Token vocabulary as a dictionary map of ``{ Vec<u8> -> T }``.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// The spans are held in a [`SpanTokenTable`], built from the map;
+/// [`to_span_map`](Self::to_span_map) rebuilds a map on demand.
+#[derive(Debug, Clone)]
 pub struct SpanMapVocab<T: TokenType> {
     /// The byte/token mapping table.
     byte_vocab: ByteMapVocab<T>,
 
-    /// Map of ``{ Vec<u8> -> T }``.
-    span_map: SpanTokenMap<T>,
+    /// Lookup table of ``{ &[u8] -> T }``.
+    span_table: SpanTokenTable<T>,
+
+    /// Map of ``{ Vec<u8> -> T }``; only built by [`span_map`](Self::span_map).
+    span_map: OnceCell<SpanTokenMap<T>>,
+}
+
+impl<T: TokenType> PartialEq for SpanMapVocab<T> {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.byte_vocab == other.byte_vocab && self.span_table == other.span_table
+    }
 }
 
 impl<T: TokenType> Default for SpanMapVocab<T> {
@@ -128,7 +146,7 @@ impl<T: TokenType> SpanMapVocab<T> {
     /// Initialize a [`SpanMapVocab`].
     ///
     /// The span map will be the union of the span map,
-    /// and all overrides from the `byte_vocab`.
+    /// and all overrides from the `byte_vocab`; empty spans are dropped.
     ///
     /// ## Arguments
     /// * `byte_vocab` - The byte vocabulary mapping.
@@ -144,11 +162,12 @@ impl<T: TokenType> SpanMapVocab<T> {
 
         span_map.extend(byte_vocab.span_pairs());
 
-        span_map.shrink_to_fit();
+        let span_table = SpanTokenTable::from(&span_map);
 
         Ok(Self {
             byte_vocab,
-            span_map,
+            span_table,
+            span_map: OnceCell::new(),
         })
     }
 
@@ -160,9 +179,8 @@ impl<T: TokenType> SpanMapVocab<T> {
 
         SpanMapVocab::<G>::new(
             self.byte_vocab.to_token_type::<G>()?,
-            self.span_map
-                .iter()
-                .map(|(chunk, token)| (chunk.clone(), G::from(*token).unwrap()))
+            self.iter()
+                .map(|(chunk, token)| (chunk.to_vec(), G::from(*token).unwrap()))
                 .collect(),
         )
     }
@@ -173,8 +191,24 @@ impl<T: TokenType> SpanMapVocab<T> {
     }
 
     /// Get the [`SpanTokenMap`].
+    ///
+    /// The map is built from the [`SpanTokenTable`] on the first call,
+    /// and kept for the life of the vocab; a second copy of the spans.
+    #[deprecated(note = "keeps a second copy of the spans; use `to_span_map` or `iter`")]
     pub fn span_map(&self) -> &SpanTokenMap<T> {
-        &self.span_map
+        self.span_map.get_or_init(|| self.to_span_map())
+    }
+
+    /// Build a [`SpanTokenMap`] of the vocabulary.
+    ///
+    /// The map is a copy, built from the [`SpanTokenTable`] on each call.
+    pub fn to_span_map(&self) -> SpanTokenMap<T> {
+        self.span_pairs().collect()
+    }
+
+    /// Get the [`SpanTokenTable`].
+    pub fn span_table(&self) -> &SpanTokenTable<T> {
+        &self.span_table
     }
 
     /// Iterate over the words in the vocabulary.
@@ -182,9 +216,7 @@ impl<T: TokenType> SpanMapVocab<T> {
     /// ## Returns
     /// An iterator over references to spans and their corresponding tokens.
     pub fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a [u8], &'a T)> + 'a {
-        self.span_map
-            .iter()
-            .map(|(chunk, token)| (chunk.as_ref(), token))
+        self.span_table.iter()
     }
 
     /// Return the associated token for the word, if any.
@@ -201,7 +233,7 @@ impl<T: TokenType> SpanMapVocab<T> {
         if chunk.len() == 1 {
             Some(self.byte_vocab.get_token(chunk[0]))
         } else {
-            self.span_map.get(chunk).copied()
+            self.span_table.get(chunk)
         }
     }
 
@@ -217,11 +249,8 @@ impl<T: TokenType> SpanMapVocab<T> {
 
         let mut pairs = PairTokenMap::default();
 
-        let token_to_span: WCHashMap<T, &[u8]> = self
-            .span_map
-            .iter()
-            .map(|(chunk, &token)| (token, chunk.as_ref()))
-            .collect();
+        let token_to_span: WCHashMap<T, &[u8]> =
+            self.iter().map(|(chunk, &token)| (token, chunk)).collect();
 
         for token in self.tokens() {
             let span = token_to_span[&token];
@@ -248,7 +277,7 @@ impl<T: TokenType> VocabIndex<T> for SpanMapVocab<T> {
     type Token = T;
 
     fn len(&self) -> usize {
-        self.span_map.len()
+        self.span_table.len()
     }
 
     fn tokens(&self) -> WCHashSet<T> {
@@ -256,20 +285,18 @@ impl<T: TokenType> VocabIndex<T> for SpanMapVocab<T> {
             .byte_tokens()
             .iter()
             .copied()
-            .chain(self.span_map.values().copied())
+            .chain(self.iter().map(|(_, &token)| token))
             .collect::<WCHashSet<T>>()
     }
 
     fn max_token(&self) -> Option<T> {
         let max_t = self.byte_vocab.max_token();
-        let max_p = self.span_map.values().max().copied();
+        let max_p = self.iter().map(|(_, &token)| token).max();
         [max_t, max_p].into_iter().flatten().max()
     }
 
     fn span_pairs(&self) -> impl Iterator<Item = (Vec<u8>, T)> {
-        self.span_map
-            .iter()
-            .map(|(chunk, &token)| (chunk.clone(), token))
+        self.iter().map(|(chunk, &token)| (chunk.to_vec(), token))
     }
 }
 
@@ -290,12 +317,28 @@ mod tests {
         assert_eq!(vocab.lookup_token(b"apple"), Some(300));
 
         assert_eq!(vocab.byte_vocab(), &vocab.byte_vocab);
-        assert_eq!(vocab.span_map(), &vocab.span_map);
+        assert_eq!(vocab.span_table(), &vocab.span_table);
+        assert_eq!(vocab.span_table().len(), vocab.to_span_map().len());
 
         let mut expected: SpanTokenMap<T> = vocab.byte_vocab().span_pairs().collect();
         expected.extend(span_map.clone());
 
-        assert_eq!(vocab.span_map(), &expected);
+        assert_eq!(vocab.to_span_map(), expected);
+        assert_eq!(vocab.iter().count(), expected.len());
+
+        // The deprecated getter builds the map once, and keeps it.
+        #[allow(deprecated)]
+        {
+            let cached = vocab.clone();
+            assert_eq!(cached.span_map(), &expected);
+            assert!(core::ptr::eq(cached.span_map(), cached.span_map()));
+            assert_eq!(cached, vocab);
+        }
+
+        // Equality compares the spans.
+        assert_eq!(SpanMapVocab::from_span_map(expected.clone()), vocab);
+        expected.insert("apple".as_bytes().to_vec(), 302);
+        assert_ne!(SpanMapVocab::from_span_map(expected), vocab);
     }
 
     #[test]
@@ -309,7 +352,7 @@ mod tests {
         assert_eq!(vocab.max_token(), byte_vocab.max_token());
         assert_eq!(&vocab.tokens(), &byte_vocab.tokens());
 
-        let mut span_map = vocab.span_map.clone();
+        let mut span_map = vocab.to_span_map();
 
         span_map.insert("apple".as_bytes().to_vec(), 300);
         span_map.insert("banana".as_bytes().to_vec(), 301);
@@ -83,6 +83,30 @@ impl<T: TokenType> UnifiedTokenVocab<T> {
         Self::new(span_config, span_vocab, pair_vocab)
     }
 
+    /// Build a byte-only [`UnifiedTokenVocab`] carrying a spanning config.
+    ///
+    /// Non-BPE models (e.g. wordpiece, unigram) use this to thread their
+    /// spanning config and specials through the [`UnifiedTokenVocab`] plumbing.
+    ///
+    /// ## Arguments
+    /// * `span_config` - The spanners configuration.
+    /// * `byte_base` - The first byte token; bytes are `byte_base..byte_base + 256`.
+    ///
+    /// ## Returns
+    /// A `Result<UnifiedTokenVocab>`, with errors on vocab conflict.
+    pub fn from_spanning_config(
+        span_config: TextSpanningConfig<T>,
+        byte_base: usize,
+    ) -> WCResult<Self> {
+        let byte_to_token = (0..256)
+            .map(|b| T::from_usize(byte_base + b).ok_or(WCError::TokenOutOfRange))
+            .collect::<WCResult<Vec<T>>>()?;
+        let span_vocab =
+            SpanMapVocab::from_byte_vocab(ByteMapVocab::from_byte_to_token(&byte_to_token));
+
+        Self::from_span_vocab(span_config, span_vocab)
+    }
+
     /// Build a new [`UnifiedTokenVocab`] from a [`PairMapVocab`].
     ///
     /// ## Arguments
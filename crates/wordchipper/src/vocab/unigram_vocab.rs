@@ -0,0 +1,362 @@
+//! # Unigram Vocabulary
+
+use crate::{
+    TokenType,
+    WCError,
+    WCHashMap,
+    WCHashSet,
+    WCResult,
+    alloc::{format, vec::Vec},
+    spanners::{NormalizerStep, TextSpanningConfig},
+    vocab::{TokenSpanMap, UnifiedTokenVocab, VocabIndex},
+};
+
+/// The sentencepiece whitespace marker, `▁` (U+2581).
+pub const SENTENCEPIECE_SPACE: &str = "\u{2581}";
+
+/// The sentencepiece word pattern.
+///
+/// Each word starts at a whitespace marker; so pieces never cross one.
+pub const SENTENCEPIECE_PATTERN: &str = "\u{2581}[^\u{2581}]*|[^\u{2581}]+";
+
+/// The default unigram unknown token word.
+pub const DEFAULT_UNIGRAM_UNK_WORD: &str = "<unk>";
+
+/// The score penalty of the unknown token, below the minimum piece score.
+pub const DEFAULT_UNIGRAM_UNK_PENALTY: f32 = 10.0;
+
+/// Build the sentencepiece [`TextSpanningConfig`].
+///
+/// Normalizes as sentencepiece's default `nmt_nfkc` rules, approximately:
+/// NFKC, whitespace collapsed and trimmed, a dummy prefix space; and every
+/// space replaced by [`SENTENCEPIECE_SPACE`]. Words are split by
+/// [`SENTENCEPIECE_PATTERN`].
+pub fn sentencepiece_spanning_config<T: TokenType>() -> TextSpanningConfig<T> {
+    TextSpanningConfig::from_pattern(SENTENCEPIECE_PATTERN).with_normalizer([
+        NormalizerStep::Nfkc,
+        NormalizerStep::CollapseWhitespace,
+        NormalizerStep::Trim,
+        NormalizerStep::prepend(" "),
+        NormalizerStep::replace(" ", SENTENCEPIECE_SPACE),
+    ])
+}
+
+/// A unigram language-model vocabulary.
+///
+/// Each piece has a log-probability score; a word is encoded as the
+/// segmentation with the highest total score (the Viterbi path).
+/// Chars covered by no piece encode as the unknown token, scored
+/// [`unk_score`](Self::unk_score).
+///
+/// The pieces are not byte-pair encodable; see
+/// [`to_unified_vocab`](Self::to_unified_vocab) for how this plugs into
+/// the [`UnifiedTokenVocab`] plumbing.
+///
+/// ## Style Hints
+///
+/// Instance names should prefer `unigram_vocab`, or `vocab`
+/// when there is no ambiguity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnigramVocab<T: TokenType> {
+    /// Text Spanning Configuration.
+    spanning: TextSpanningConfig<T>,
+
+    /// `{ Vec<u8> -> (T, score) }` pieces.
+    pieces: WCHashMap<Vec<u8>, (T, f32)>,
+
+    /// The unknown token.
+    unk_token: T,
+
+    /// The score of the unknown token.
+    unk_score: f32,
+
+    /// Merge runs of unknown tokens into one.
+    fuse_unk: bool,
+
+    /// Max piece length, in bytes; bounds the lattice.
+    max_piece_len: usize,
+}
+
+impl<T: TokenType> UnigramVocab<T> {
+    /// Initialize a [`UnigramVocab`].
+    ///
+    /// The unknown score is the minimum piece score, less
+    /// [`DEFAULT_UNIGRAM_UNK_PENALTY`].
+    ///
+    /// ## Arguments
+    /// * `spanning` - The spanners configuration; specials must not be pieces.
+    /// * `pieces` - The `{ piece -> (T, score) }` map.
+    /// * `unk_token` - The unknown token; a piece, or a special.
+    ///
+    /// ## Returns
+    /// A `Result<UnigramVocab>`, with errors on vocab conflict.
+    pub fn new(
+        spanning: TextSpanningConfig<T>,
+        pieces: WCHashMap<Vec<u8>, (T, f32)>,
+        unk_token: T,
+    ) -> WCResult<Self> {
+        let tokens: WCHashSet<T> = pieces.values().map(|&(t, _)| t).collect();
+        if tokens.len() != pieces.len() {
+            return Err(WCError::VocabConflict(
+                "duplicate token found in unigram vocab".into(),
+            ));
+        }
+        for t in spanning.specials().tokens() {
+            if tokens.contains(&t) {
+                return Err(WCError::VocabConflict(format!(
+                    "special token ({t:?}) found in unigram vocab"
+                )));
+            }
+        }
+        if !tokens.contains(&unk_token) && spanning.specials().lookup_span(&unk_token).is_none() {
+            return Err(WCError::VocabConflict(format!(
+                "unknown token ({unk_token:?}) not found in unigram vocab"
+            )));
+        }
+
+        let min_score = pieces.values().map(|&(_, score)| score).fold(0.0, f32::min);
+        let max_piece_len = pieces.keys().map(Vec::len).max().unwrap_or_default();
+        Ok(Self {
+            spanning,
+            pieces,
+            unk_token,
+            unk_score: min_score - DEFAULT_UNIGRAM_UNK_PENALTY,
+            fuse_unk: true,
+            max_piece_len,
+        })
+    }
+
+    /// Build a [`UnigramVocab`] from an ordered, scored word list.
+    ///
+    /// Each word's token is its index; words in `special_words` become
+    /// specials of the spanning config, and their scores are ignored.
+    ///
+    /// ## Arguments
+    /// * `spanning` - The spanners configuration.
+    /// * `words` - The ordered `(word, score)` pairs.
+    /// * `special_words` - The words to treat as specials.
+    /// * `unk_word` - The unknown word.
+    pub fn from_scored_words<I, S>(
+        mut spanning: TextSpanningConfig<T>,
+        words: I,
+        special_words: &[&str],
+        unk_word: &str,
+    ) -> WCResult<Self>
+    where
+        I: IntoIterator<Item = (S, f32)>,
+        S: AsRef<str>,
+    {
+        let mut pieces = WCHashMap::default();
+        let mut unk_token = None;
+        for (idx, (word, score)) in words.into_iter().enumerate() {
+            let word = word.as_ref();
+            let token = T::from_usize(idx).ok_or(WCError::TokenOutOfRange)?;
+            if word == unk_word {
+                unk_token = Some(token);
+            }
+            if special_words.contains(&word) {
+                spanning.specials_mut().add_str_word(word, token);
+            } else if pieces
+                .insert(word.as_bytes().to_vec(), (token, score))
+                .is_some()
+            {
+                return Err(WCError::VocabConflict(format!(
+                    "duplicate piece {word:?} found in words"
+                )));
+            }
+        }
+        let unk_token = unk_token.ok_or_else(|| {
+            WCError::VocabConflict(format!("unknown word {unk_word:?} not found in words"))
+        })?;
+
+        Self::new(spanning, pieces, unk_token)
+    }
+
+    /// Set the score of the unknown token.
+    pub fn with_unk_score(
+        self,
+        unk_score: f32,
+    ) -> Self {
+        Self { unk_score, ..self }
+    }
+
+    /// Set whether runs of unknown tokens are merged into one.
+    pub fn with_fuse_unk(
+        self,
+        fuse_unk: bool,
+    ) -> Self {
+        Self { fuse_unk, ..self }
+    }
+
+    /// Convert to a different token type.
+    pub fn to_token_type<G: TokenType>(&self) -> WCResult<UnigramVocab<G>> {
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|(span, &(t, score))| {
+                Ok((
+                    span.clone(),
+                    (G::from(t).ok_or(WCError::TokenOutOfRange)?, score),
+                ))
+            })
+            .collect::<WCResult<WCHashMap<Vec<u8>, (G, f32)>>>()?;
+        Ok(UnigramVocab::<G> {
+            spanning: self.spanning.to_token_type()?,
+            pieces,
+            unk_token: G::from(self.unk_token).ok_or(WCError::TokenOutOfRange)?,
+            unk_score: self.unk_score,
+            fuse_unk: self.fuse_unk,
+            max_piece_len: self.max_piece_len,
+        })
+    }
+
+    /// Get the [`TextSpanningConfig`].
+    pub fn spanning(&self) -> &TextSpanningConfig<T> {
+        &self.spanning
+    }
+
+    /// Get the `{ Vec<u8> -> (T, score) }` pieces.
+    pub fn pieces(&self) -> &WCHashMap<Vec<u8>, (T, f32)> {
+        &self.pieces
+    }
+
+    /// Get the unknown token.
+    pub fn unk_token(&self) -> T {
+        self.unk_token
+    }
+
+    /// Get the score of the unknown token.
+    pub fn unk_score(&self) -> f32 {
+        self.unk_score
+    }
+
+    /// Are runs of unknown tokens merged into one?
+    pub fn fuse_unk(&self) -> bool {
+        self.fuse_unk
+    }
+
+    /// Get the max piece length, in bytes.
+    pub fn max_piece_len(&self) -> usize {
+        self.max_piece_len
+    }
+
+    /// Look up a piece's token and score.
+    pub fn lookup_piece(
+        &self,
+        piece: &[u8],
+    ) -> Option<(T, f32)> {
+        self.pieces.get(piece).copied()
+    }
+
+    /// Compile a `{ T -> Vec<u8> }` dictionary of pieces and specials.
+    pub fn token_spans(&self) -> TokenSpanMap<T> {
+        self.pieces
+            .iter()
+            .map(|(span, &(t, _))| (t, span.clone()))
+            .chain(self.spanning.specials().span_pairs().map(|(s, t)| (t, s)))
+            .collect()
+    }
+
+    /// Build the [`UnifiedTokenVocab`] carrying this vocab's spanning config.
+    ///
+    /// See [`UnifiedTokenVocab::from_spanning_config`]; its byte tokens are
+    /// placed after the unigram tokens, and the unigram encoder never emits them.
+    pub fn to_unified_vocab(&self) -> WCResult<UnifiedTokenVocab<T>> {
+        let byte_base = self
+            .max_token()
+            .map_or(0, |t| t.to_usize().unwrap_or_default() + 1);
+        UnifiedTokenVocab::from_spanning_config(self.spanning.clone(), byte_base)
+    }
+}
+
+impl<T: TokenType> VocabIndex<T> for UnigramVocab<T> {
+    type Token = T;
+
+    fn len(&self) -> usize {
+        self.pieces.len() + self.spanning.specials().len()
+    }
+
+    fn tokens(&self) -> WCHashSet<T> {
+        self.pieces
+            .values()
+            .map(|&(t, _)| t)
+            .chain(self.spanning.specials().tokens())
+            .collect()
+    }
+
+    fn span_pairs(&self) -> impl Iterator<Item = (Vec<u8>, T)> {
+        self.pieces.iter().map(|(span, &(t, _))| (span.clone(), t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_scored_words() {
+        type T = u32;
+        let words = [
+            ("<unk>", 0.0),
+            ("<s>", 0.0),
+            ("\u{2581}", -2.0),
+            ("a", -3.0),
+            ("ab", -4.0),
+        ];
+        let vocab: UnigramVocab<T> = UnigramVocab::from_scored_words(
+            sentencepiece_spanning_config(),
+            words,
+            &["<unk>", "<s>"],
+            DEFAULT_UNIGRAM_UNK_WORD,
+        )
+        .unwrap();
+
+        assert_eq!(vocab.len(), 5);
+        assert_eq!(vocab.pieces().len(), 3);
+        assert_eq!(vocab.unk_token(), 0);
+        assert_eq!(vocab.unk_score(), -14.0);
+        assert!(vocab.fuse_unk());
+        assert_eq!(vocab.max_piece_len(), 3);
+        assert_eq!(vocab.lookup_piece(b"ab"), Some((4, -4.0)));
+        assert_eq!(vocab.token_spans().get(&1), Some(&b"<s>".to_vec()));
+
+        let vocab = vocab.with_unk_score(-100.0).with_fuse_unk(false);
+        assert_eq!(vocab.unk_score(), -100.0);
+        assert!(!vocab.fuse_unk());
+
+        let unified = vocab.to_unified_vocab().unwrap();
+        assert_eq!(unified.byte_vocab().get_token(0), 5);
+        assert_eq!(unified.special_vocab().lookup_token(b"<s>"), Some(1));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        type T = u32;
+        let spanning = sentencepiece_spanning_config::<T>();
+        assert!(
+            UnigramVocab::<T>::from_scored_words(
+                spanning.clone(),
+                [("a", -1.0), ("b", -1.0)],
+                &[],
+                DEFAULT_UNIGRAM_UNK_WORD,
+            )
+            .is_err()
+        );
+        assert!(
+            UnigramVocab::<T>::from_scored_words(
+                spanning.clone(),
+                [("<unk>", 0.0), ("a", -1.0), ("a", -2.0)],
+                &["<unk>"],
+                DEFAULT_UNIGRAM_UNK_WORD,
+            )
+            .is_err()
+        );
+
+        let spanning = spanning.with_special_words([("<s>", 1)]);
+        let pieces: WCHashMap<Vec<u8>, (T, f32)> =
+            [(b"a".to_vec(), (0, -1.0)), (b"b".to_vec(), (1, -1.0))]
+                .into_iter()
+                .collect();
+        assert!(UnigramVocab::new(spanning, pieces, 0).is_err());
+    }
+}
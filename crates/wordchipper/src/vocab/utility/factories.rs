@@ -4,7 +4,7 @@
 use std::{
     fs::File,
     io::{BufRead, BufReader},
-    path::{Path, PathBuf},
+    path::Path,
 };
 
 #[cfg(feature = "std")]
@@ -57,24 +57,15 @@ impl ConstVocabularyFactory {
         TextSpanningConfig::from_pattern(self.pattern()).with_special_words(self.special_tokens())
     }
 
-    /// Fetch a path to the resource through the loader.
-    #[cfg(feature = "std")]
-    fn fetch_resource(
-        &self,
-        loader: &mut dyn ResourceLoader,
-    ) -> WCResult<PathBuf> {
-        let res: crate::support::resources::KeyedResource = self.resource.clone().into();
-        loader.load_resource_path(&res)
-    }
-
     /// Load the pretrained vocabulary through the loader.
     #[cfg(feature = "std")]
     pub fn load_vocab<T: TokenType>(
         &self,
         loader: &mut dyn ResourceLoader,
     ) -> WCResult<UnifiedTokenVocab<T>> {
-        let path = self.fetch_resource(loader)?;
-        self.load_vocab_path(path)
+        let res: crate::support::resources::KeyedResource = self.resource.clone().into();
+        let mut reader = loader.open_resource(&res)?;
+        self.read_vocab(&mut reader)
     }
 
     /// Load the pretrained vocabulary from disk.
@@ -9,6 +9,6 @@ pub mod testing;
 pub mod validators;
 
 #[doc(inline)]
-pub use specials_tools::{format_carrot, format_reserved_carrot};
+pub use specials_tools::{format_carrot, format_reserved_carrot, format_reserved_special_carrot};
 #[doc(inline)]
 pub use token_list::*;
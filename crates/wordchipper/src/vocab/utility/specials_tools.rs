@@ -28,6 +28,13 @@ pub fn format_reserved_carrot(value: usize) -> String {
     format!("<|reserved_{}|>", value)
 }
 
+/// Generate a "`<|reserved_special_token_{$value}|>`" String.
+///
+/// This is the reserved token naming used by the Llama 3 family.
+pub fn format_reserved_special_carrot(value: usize) -> String {
+    format!("<|reserved_special_token_{}|>", value)
+}
+
 /// Declare a special token constant with [`carrot_str!()`].
 ///
 /// Declare 1:
@@ -76,6 +83,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_reserved_special_carrot() {
+        assert_eq!(
+            format_reserved_special_carrot(7).as_str(),
+            "<|reserved_special_token_7|>"
+        );
+    }
+
     #[test]
     fn test_declare_special() {
         declare_carrot_special!((FOO, "foo"), (BAR, "bar"));
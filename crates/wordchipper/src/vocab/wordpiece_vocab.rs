@@ -0,0 +1,350 @@
+//! # Wordpiece Vocabulary
+
+use crate::{
+    TokenType,
+    WCError,
+    WCHashSet,
+    WCResult,
+    alloc::{
+        format,
+        string::{String, ToString},
+        vec::Vec,
+    },
+    spanners::{NormalizerStep, SpanningStage, TextSpanningConfig},
+    vocab::{SpanTokenMap, TokenSpanMap, UnifiedTokenVocab, VocabIndex},
+};
+
+/// The default wordpiece continuation prefix.
+pub const DEFAULT_WORDPIECE_CONTINUATION_PREFIX: &str = "##";
+
+/// The default wordpiece unknown token word.
+pub const DEFAULT_WORDPIECE_UNK_WORD: &str = "[UNK]";
+
+/// The default max chars per word; longer words encode as the unknown token.
+pub const DEFAULT_WORDPIECE_MAX_INPUT_CHARS_PER_WORD: usize = 100;
+
+/// The special words of BERT vocabularies.
+pub const BERT_SPECIAL_WORDS: [&str; 5] = ["[PAD]", "[UNK]", "[CLS]", "[SEP]", "[MASK]"];
+
+/// The BERT basic pre-tokenizer word pattern.
+///
+/// Splits on whitespace, and isolates each CJK ideograph; punctuation
+/// is split by a [`SpanningStage::Punctuation`] stage.
+pub const BERT_BASIC_PATTERN: &str = r"\p{Han}|[^\s\p{Han}]+";
+
+/// Build the BERT basic pre-tokenizer [`TextSpanningConfig`].
+///
+/// Text is cleaned first (see [`NormalizerStep::CleanText`]).
+///
+/// ## Arguments
+/// * `uncased` - lowercase and strip accents, as for `*-uncased` models.
+pub fn bert_spanning_config<T: TokenType>(uncased: bool) -> TextSpanningConfig<T> {
+    let config =
+        TextSpanningConfig::from_pattern(BERT_BASIC_PATTERN).with_stage(SpanningStage::Punctuation);
+    match uncased {
+        true => config.with_normalizer([
+            NormalizerStep::CleanText,
+            NormalizerStep::Lowercase,
+            NormalizerStep::Nfd,
+            NormalizerStep::StripAccents,
+        ]),
+        false => config.with_normalizer([NormalizerStep::CleanText]),
+    }
+}
+
+/// A wordpiece vocabulary.
+///
+/// Words are encoded greedily, longest-match-first; pieces after the
+/// first are looked up with the continuation prefix (e.g. `##ing`).
+/// A word with no complete segmentation encodes as the unknown token.
+///
+/// The pieces are not byte-pair encodable; see
+/// [`to_unified_vocab`](Self::to_unified_vocab) for how this plugs into
+/// the [`UnifiedTokenVocab`] plumbing.
+///
+/// ## Style Hints
+///
+/// Instance names should prefer `wordpiece_vocab`, or `vocab`
+/// when there is no ambiguity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordPieceVocab<T: TokenType> {
+    /// Text Spanning Configuration.
+    spanning: TextSpanningConfig<T>,
+
+    /// `{ Vec<u8> -> T }` pieces; continuation pieces include the prefix.
+    pieces: SpanTokenMap<T>,
+
+    /// The continuation prefix.
+    continuation_prefix: String,
+
+    /// The unknown token.
+    unk_token: T,
+
+    /// Max chars per word.
+    max_input_chars_per_word: usize,
+
+    /// Max piece length, in bytes; bounds the longest-match search.
+    max_piece_len: usize,
+}
+
+impl<T: TokenType> WordPieceVocab<T> {
+    /// Initialize a [`WordPieceVocab`].
+    ///
+    /// ## Arguments
+    /// * `spanning` - The spanners configuration; specials must not be pieces.
+    /// * `pieces` - The `{ piece -> T }` map.
+    /// * `unk_token` - The unknown token; a piece, or a special.
+    ///
+    /// ## Returns
+    /// A `Result<WordPieceVocab>`, with errors on vocab conflict.
+    pub fn new(
+        spanning: TextSpanningConfig<T>,
+        pieces: SpanTokenMap<T>,
+        unk_token: T,
+    ) -> WCResult<Self> {
+        let tokens: WCHashSet<T> = pieces.values().copied().collect();
+        for t in spanning.specials().tokens() {
+            if tokens.contains(&t) {
+                return Err(WCError::VocabConflict(format!(
+                    "special token ({t:?}) found in wordpiece vocab"
+                )));
+            }
+        }
+        if !tokens.contains(&unk_token) && spanning.specials().lookup_span(&unk_token).is_none() {
+            return Err(WCError::VocabConflict(format!(
+                "unknown token ({unk_token:?}) not found in wordpiece vocab"
+            )));
+        }
+
+        let max_piece_len = pieces.keys().map(Vec::len).max().unwrap_or_default();
+        Ok(Self {
+            spanning,
+            pieces,
+            continuation_prefix: DEFAULT_WORDPIECE_CONTINUATION_PREFIX.to_string(),
+            unk_token,
+            max_input_chars_per_word: DEFAULT_WORDPIECE_MAX_INPUT_CHARS_PER_WORD,
+            max_piece_len,
+        })
+    }
+
+    /// Build a [`WordPieceVocab`] from an ordered word list, as in a BERT `vocab.txt`.
+    ///
+    /// Each word's token is its index; words in `special_words` become
+    /// specials of the spanning config.
+    ///
+    /// ## Arguments
+    /// * `spanning` - The spanners configuration.
+    /// * `words` - The ordered words.
+    /// * `special_words` - The words to treat as specials.
+    /// * `unk_word` - The unknown word.
+    pub fn from_words<I, S>(
+        mut spanning: TextSpanningConfig<T>,
+        words: I,
+        special_words: &[&str],
+        unk_word: &str,
+    ) -> WCResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut pieces = SpanTokenMap::default();
+        let mut unk_token = None;
+        for (idx, word) in words.into_iter().enumerate() {
+            let word = word.as_ref();
+            let token = T::from_usize(idx).ok_or(WCError::TokenOutOfRange)?;
+            if word == unk_word {
+                unk_token = Some(token);
+            }
+            if special_words.contains(&word) {
+                spanning.specials_mut().add_str_word(word, token);
+            } else {
+                pieces.insert(word.as_bytes().to_vec(), token);
+            }
+        }
+        let unk_token = unk_token.ok_or_else(|| {
+            WCError::VocabConflict(format!("unknown word {unk_word:?} not found in words"))
+        })?;
+
+        Self::new(spanning, pieces, unk_token)
+    }
+
+    /// Set the continuation prefix.
+    pub fn with_continuation_prefix<S: Into<String>>(
+        self,
+        continuation_prefix: S,
+    ) -> Self {
+        Self {
+            continuation_prefix: continuation_prefix.into(),
+            ..self
+        }
+    }
+
+    /// Set the max chars per word.
+    pub fn with_max_input_chars_per_word(
+        self,
+        max_input_chars_per_word: usize,
+    ) -> Self {
+        Self {
+            max_input_chars_per_word,
+            ..self
+        }
+    }
+
+    /// Convert to a different token type.
+    pub fn to_token_type<G: TokenType>(&self) -> WCResult<WordPieceVocab<G>> {
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|(span, t)| Ok((span.clone(), G::from(*t).ok_or(WCError::TokenOutOfRange)?)))
+            .collect::<WCResult<SpanTokenMap<G>>>()?;
+        Ok(WordPieceVocab::<G> {
+            spanning: self.spanning.to_token_type()?,
+            pieces,
+            continuation_prefix: self.continuation_prefix.clone(),
+            unk_token: G::from(self.unk_token).ok_or(WCError::TokenOutOfRange)?,
+            max_input_chars_per_word: self.max_input_chars_per_word,
+            max_piece_len: self.max_piece_len,
+        })
+    }
+
+    /// Get the [`TextSpanningConfig`].
+    pub fn spanning(&self) -> &TextSpanningConfig<T> {
+        &self.spanning
+    }
+
+    /// Get the `{ Vec<u8> -> T }` pieces.
+    pub fn pieces(&self) -> &SpanTokenMap<T> {
+        &self.pieces
+    }
+
+    /// Get the continuation prefix.
+    pub fn continuation_prefix(&self) -> &str {
+        &self.continuation_prefix
+    }
+
+    /// Get the unknown token.
+    pub fn unk_token(&self) -> T {
+        self.unk_token
+    }
+
+    /// Get the max chars per word.
+    pub fn max_input_chars_per_word(&self) -> usize {
+        self.max_input_chars_per_word
+    }
+
+    /// Get the max piece length, in bytes.
+    pub fn max_piece_len(&self) -> usize {
+        self.max_piece_len
+    }
+
+    /// Look up a piece; continuation pieces include the prefix.
+    pub fn lookup_piece(
+        &self,
+        piece: &[u8],
+    ) -> Option<T> {
+        self.pieces.get(piece).copied()
+    }
+
+    /// Compile a `{ T -> Vec<u8> }` dictionary of pieces and specials.
+    ///
+    /// Continuation pieces include the prefix.
+    pub fn token_spans(&self) -> TokenSpanMap<T> {
+        self.pieces
+            .iter()
+            .map(|(span, &t)| (t, span.clone()))
+            .chain(self.spanning.specials().span_pairs().map(|(s, t)| (t, s)))
+            .collect()
+    }
+
+    /// Build the [`UnifiedTokenVocab`] carrying this vocab's spanning config.
+    ///
+    /// [`TokenSpanEncoder`](crate::encoders::token_span_encoder::TokenSpanEncoder)
+    /// and [`Tokenizer`](crate::Tokenizer) read the spanning config and specials
+    /// from a [`UnifiedTokenVocab`]. Its byte tokens are placed after the
+    /// wordpiece tokens; the wordpiece encoder never emits them.
+    pub fn to_unified_vocab(&self) -> WCResult<UnifiedTokenVocab<T>> {
+        let byte_base = self
+            .max_token()
+            .map_or(0, |t| t.to_usize().unwrap_or_default() + 1);
+        UnifiedTokenVocab::from_spanning_config(self.spanning.clone(), byte_base)
+    }
+}
+
+impl<T: TokenType> VocabIndex<T> for WordPieceVocab<T> {
+    type Token = T;
+
+    fn len(&self) -> usize {
+        self.pieces.len() + self.spanning.specials().len()
+    }
+
+    fn tokens(&self) -> WCHashSet<T> {
+        self.pieces
+            .values()
+            .copied()
+            .chain(self.spanning.specials().tokens())
+            .collect()
+    }
+
+    fn span_pairs(&self) -> impl Iterator<Item = (Vec<u8>, T)> {
+        self.pieces.iter().map(|(span, &t)| (span.clone(), t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_words() {
+        type T = u32;
+        let words = ["[PAD]", "[UNK]", "[CLS]", "un", "##aff", "##able", "a"];
+        let vocab: WordPieceVocab<T> = WordPieceVocab::from_words(
+            bert_spanning_config(true),
+            words,
+            &BERT_SPECIAL_WORDS,
+            DEFAULT_WORDPIECE_UNK_WORD,
+        )
+        .unwrap();
+
+        assert_eq!(vocab.len(), 7);
+        assert_eq!(vocab.pieces().len(), 4);
+        assert_eq!(vocab.spanning().specials().len(), 3);
+        assert_eq!(vocab.unk_token(), 1);
+        assert_eq!(vocab.continuation_prefix(), "##");
+        assert_eq!(vocab.max_input_chars_per_word(), 100);
+        assert_eq!(vocab.max_piece_len(), 6);
+        assert_eq!(vocab.lookup_piece(b"##aff"), Some(4));
+        assert_eq!(vocab.lookup_piece(b"aff"), None);
+        assert_eq!(vocab.token_spans().get(&2), Some(&b"[CLS]".to_vec()));
+
+        let vocab = vocab
+            .with_continuation_prefix("@@")
+            .with_max_input_chars_per_word(8);
+        assert_eq!(vocab.continuation_prefix(), "@@");
+        assert_eq!(vocab.max_input_chars_per_word(), 8);
+
+        let unified = vocab.to_unified_vocab().unwrap();
+        assert_eq!(unified.byte_vocab().get_token(0), 7);
+        assert_eq!(unified.special_vocab().lookup_token(b"[CLS]"), Some(2));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        type T = u32;
+        assert!(
+            WordPieceVocab::<T>::from_words(
+                bert_spanning_config(false),
+                ["a", "b"],
+                &BERT_SPECIAL_WORDS,
+                DEFAULT_WORDPIECE_UNK_WORD,
+            )
+            .is_err()
+        );
+
+        let spanning = bert_spanning_config(false).with_special_words([("[CLS]", 1)]);
+        let pieces: SpanTokenMap<T> = [(b"a".to_vec(), 0), (b"b".to_vec(), 1)]
+            .into_iter()
+            .collect();
+        assert!(WordPieceVocab::new(spanning, pieces, 0).is_err());
+    }
+}
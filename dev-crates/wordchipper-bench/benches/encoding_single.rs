@@ -849,3 +849,65 @@ mod diverse {
         }
     }
 }
+
+mod span_lookup {
+    use wordchipper::spanners::{SpanRef, TextSpannerBuilder};
+
+    use super::*;
+
+    /// The word spans of the english corpus.
+    fn word_spans(model: &str) -> (Vec<Vec<u8>>, usize) {
+        let vocab = wordchipper_bench::load_unified_vocab::<u32>(model);
+        let text = english_text();
+        let spanner = TextSpannerBuilder::default(&vocab);
+        let mut spans = Vec::new();
+        spanner.for_each_split_span(&text, &mut |span_ref| {
+            if let SpanRef::Word(range) = span_ref {
+                spans.push(text.as_bytes()[range].to_vec());
+            }
+            true
+        });
+        (spans, text.len())
+    }
+
+    fn bench_lookup(
+        bencher: Bencher,
+        model: &str,
+        table: bool,
+    ) {
+        let vocab = wordchipper_bench::load_unified_vocab::<u32>(model);
+        let span_vocab = vocab.span_vocab();
+        let span_map = span_vocab.to_span_map();
+        let (spans, text_bytes) = word_spans(model);
+
+        bencher.counter(BytesCount::new(text_bytes)).bench(|| {
+            spans
+                .iter()
+                .filter_map(|span| match table {
+                    true => span_vocab.lookup_token(black_box(span)),
+                    false => span_map.get(black_box(span)).copied(),
+                })
+                .count()
+        });
+    }
+
+    #[divan::bench]
+    fn cl100k_table(bencher: Bencher) {
+        bench_lookup(bencher, OA_CL100K_BASE, true);
+    }
+
+    #[divan::bench]
+    fn cl100k_hash_map(bencher: Bencher) {
+        bench_lookup(bencher, OA_CL100K_BASE, false);
+    }
+
+    #[divan::bench]
+    fn o200k_table(bencher: Bencher) {
+        bench_lookup(bencher, OA_O200K_BASE, true);
+    }
+
+    #[divan::bench]
+    fn o200k_hash_map(bencher: Bencher) {
+        bench_lookup(bencher, OA_O200K_BASE, false);
+    }
+}
@@ -4,12 +4,16 @@ use std::sync::Arc;
 
 use divan::{Bencher, black_box, counter::BytesCount};
 use wordchipper::{
-    pretrained::openai::{OA_CL100K_BASE_PATTERN, OA_O200K_BASE_PATTERN, OA_R50K_BASE_PATTERN},
+    pretrained::{
+        deepseek::DEEPSEEK_V3_PATTERN,
+        meta::{META_LLAMA3_PATTERN, specials::meta_llama3_special_tokens},
+        openai::{OA_CL100K_BASE_PATTERN, OA_O200K_BASE_PATTERN, OA_R50K_BASE_PATTERN},
+    },
     spanners::{
         TextSpanner,
         TextSpannerBuilder,
         TextSpanningConfig,
-        span_lexers::{LexerTextSpanner, SpanLexer},
+        span_lexers::{LexerTextSpanner, SpanLexer, SpecialWordsLexer, build_regex_lexer},
     },
     support::regex::RegexWrapper,
 };
@@ -32,6 +36,26 @@ fn english_text() -> String {
     ENGLISH_CORPUS.repeat(10)
 }
 
+/// Text where the o200k and `DeepSeek` V3 lexers re-lex their logos tokens:
+/// marks, `Lo` / `Lm` letters before capitals, and unassigned chars.
+static CORRECTION_SAMPLE: &str = "CamelCase \u{2cec0}A HTTPServer \u{3042}L\u{30a2}S \
+    e\u{301}t\u{301}e\u{301} \u{301}x \u{301}! na\u{ef}ve  $l\u{2cec0} \u{10bfd}\u{4f60} ";
+
+fn correction_text() -> String {
+    CORRECTION_SAMPLE.repeat(2000)
+}
+
+/// English text with a Llama 3 special word after every line.
+fn english_text_with_specials() -> String {
+    let specials = meta_llama3_special_tokens::<u32>();
+    ENGLISH_CORPUS
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| format!("{line}{}\n", specials[idx % specials.len()].0))
+        .collect::<String>()
+        .repeat(10)
+}
+
 fn build_regex_only_spanner(
     pattern: impl Into<wordchipper::support::regex::RegexPattern>
 ) -> Arc<dyn TextSpanner> {
@@ -104,6 +128,24 @@ mod english {
             .counter(BytesCount::new(text.len()))
             .bench(|| spanner.split_spans(black_box(&text)));
     }
+
+    #[divan::bench]
+    fn deepseek_v3_regex(bencher: Bencher) {
+        let text = english_text();
+        let spanner = build_regex_only_spanner(DEEPSEEK_V3_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+
+    #[divan::bench]
+    fn deepseek_v3_default(bencher: Bencher) {
+        let text = english_text();
+        let spanner = build_default_spanner(DEEPSEEK_V3_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
 }
 
 mod diverse {
@@ -162,4 +204,99 @@ mod diverse {
             .counter(BytesCount::new(text.len()))
             .bench(|| spanner.split_spans(black_box(&text)));
     }
+
+    #[divan::bench]
+    fn deepseek_v3_regex(bencher: Bencher) {
+        let text = diverse_text();
+        let spanner = build_regex_only_spanner(DEEPSEEK_V3_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+
+    #[divan::bench]
+    fn deepseek_v3_default(bencher: Bencher) {
+        let text = diverse_text();
+        let spanner = build_default_spanner(DEEPSEEK_V3_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+}
+
+/// The worst case for the o200k and `DeepSeek` V3 regex corrections.
+mod corrections {
+    use super::*;
+
+    #[divan::bench]
+    fn o200k_regex(bencher: Bencher) {
+        let text = correction_text();
+        let spanner = build_regex_only_spanner(OA_O200K_BASE_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+
+    #[divan::bench]
+    fn o200k_default(bencher: Bencher) {
+        let text = correction_text();
+        let spanner = build_default_spanner(OA_O200K_BASE_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+
+    #[divan::bench]
+    fn deepseek_v3_regex(bencher: Bencher) {
+        let text = correction_text();
+        let spanner = build_regex_only_spanner(DEEPSEEK_V3_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+
+    #[divan::bench]
+    fn deepseek_v3_default(bencher: Bencher) {
+        let text = correction_text();
+        let spanner = build_default_spanner(DEEPSEEK_V3_PATTERN);
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+}
+
+mod specials {
+    use super::*;
+
+    fn llama3_spanner(special_lexer: Arc<dyn SpanLexer>) -> Arc<dyn TextSpanner> {
+        let word_lexer = build_regex_lexer(META_LLAMA3_PATTERN.into(), true, false, None);
+        Arc::new(LexerTextSpanner::new(word_lexer, Some(special_lexer)))
+    }
+
+    fn llama3_config() -> TextSpanningConfig<u32> {
+        TextSpanningConfig::from_pattern(META_LLAMA3_PATTERN)
+            .with_special_words(meta_llama3_special_tokens::<u32>())
+    }
+
+    #[divan::bench]
+    fn llama3_regex(bencher: Bencher) {
+        let text = english_text_with_specials();
+        let pattern = llama3_config().special_pattern().unwrap();
+        let spanner = llama3_spanner(Arc::new(RegexWrapper::from(pattern)));
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
+
+    #[divan::bench]
+    fn llama3_aho_corasick(bencher: Bencher) {
+        let text = english_text_with_specials();
+        let lexer = SpecialWordsLexer::from_vocab(llama3_config().specials())
+            .unwrap()
+            .unwrap();
+        let spanner = llama3_spanner(Arc::new(lexer));
+        bencher
+            .counter(BytesCount::new(text.len()))
+            .bench(|| spanner.split_spans(black_box(&text)));
+    }
 }
@@ -31,19 +31,24 @@ fn get_disk_cache() -> &'static Mutex<WordchipperDiskCache> {
     DISK_CACHE.get_or_init(|| Mutex::new(WordchipperDiskCache::default()))
 }
 
-/// Builds an `Arc<Tokenizer<T>>` for the target model and options.
+/// Loads the `UnifiedTokenVocab<T>` for the target model.
 ///
 /// Use the default disk cache to load the vocab.
-pub fn load_encoder<T: TokenType>(
-    model: &str,
-    options: TokenEncoderOptions,
-) -> Arc<dyn TokenEncoder<T>> {
+pub fn load_unified_vocab<T: TokenType>(model: &str) -> Arc<UnifiedTokenVocab<T>> {
     let mut guard = get_disk_cache().lock().unwrap();
     let disk_cache = &mut *guard;
 
     let (_desc, vocab) = load_vocab(model, disk_cache).unwrap();
 
-    let vocab: Arc<UnifiedTokenVocab<T>> = vocab.to_token_type::<T>().unwrap().into();
+    vocab.to_token_type::<T>().unwrap().into()
+}
 
-    options.build(vocab)
+/// Builds an `Arc<Tokenizer<T>>` for the target model and options.
+///
+/// Use the default disk cache to load the vocab.
+pub fn load_encoder<T: TokenType>(
+    model: &str,
+    options: TokenEncoderOptions,
+) -> Arc<dyn TokenEncoder<T>> {
+    options.build(load_unified_vocab(model))
 }
@@ -0,0 +1,360 @@
+//! # Pattern Branch Analysis
+//!
+//! Splits a `join_patterns!` pattern into its top-level branches, and
+//! classifies each by how it interacts with whitespace.
+
+use crate::LexgenError;
+
+/// How a pattern branch maps onto a logos token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchKind {
+    /// A contraction; e.g. `'s` or `(?i:'s|'t|'re)`.
+    Contraction,
+
+    /// A word with an optional one-char class prefix; e.g. `[^\r\n\p{L}\p{N}]?\p{L}+`.
+    Word,
+
+    /// A token with an optional leading space; e.g. ` ?[^\s\p{L}\p{N}]+`.
+    SpacePrefixed,
+
+    /// Newlines, with any leading whitespace; `\s*[\r\n]+`.
+    Newline,
+
+    /// Whitespace not followed by non-whitespace; `\s+(?!\S)`.
+    WhitespaceLookahead,
+
+    /// The whitespace fallback; `\s+` or `\s`.
+    Whitespace,
+
+    /// Anything else; matched as-is, never absorbing whitespace.
+    Standalone,
+}
+
+/// A classified top-level pattern branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    /// The branch, as written in the pattern.
+    pub source: String,
+
+    /// The branch, normalized for logos.
+    pub regex: String,
+
+    /// The branch classification.
+    pub kind: BranchKind,
+}
+
+/// Split a pattern on its top-level `|` alternations.
+///
+/// # Arguments
+/// * `pattern` - the regex pattern.
+///
+/// # Returns
+/// The branches, in order.
+pub fn split_branches(pattern: &str) -> Result<Vec<&str>, LexgenError> {
+    let mut branches = Vec::new();
+    let mut scanner = Scanner::new(pattern);
+    let mut start = 0;
+    let mut parens = 0usize;
+
+    while let Some((idx, atom)) = scanner.next_atom()? {
+        match atom {
+            Atom::Char('(') => parens += 1,
+            Atom::Char(')') => {
+                parens = parens.checked_sub(1).ok_or_else(|| {
+                    LexgenError::Parse(format!("unbalanced ')' at {idx} in {pattern:?}"))
+                })?;
+            }
+            Atom::Char('|') if parens == 0 => {
+                branches.push(&pattern[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    if parens != 0 {
+        return Err(LexgenError::Parse(format!("unbalanced '(' in {pattern:?}")));
+    }
+    branches.push(&pattern[start..]);
+
+    Ok(branches)
+}
+
+/// Normalize a branch for logos.
+///
+/// A DFA match is already maximal, so possessive quantifiers (`++`, `?+`)
+/// become greedy ones. Lazy quantifiers, anchors and lookaround have no DFA
+/// equivalent and are rejected; except the whitespace idiom `\s+(?!\S)`,
+/// which the span engine implements.
+///
+/// # Arguments
+/// * `branch` - a single top-level branch.
+///
+/// # Returns
+/// The normalized branch.
+pub fn normalize_branch(branch: &str) -> Result<String, LexgenError> {
+    let mut out = String::with_capacity(branch.len());
+    let mut scanner = Scanner::new(branch);
+    let mut after_quantifier = false;
+    let mut after_open = false;
+
+    while let Some((idx, atom)) = scanner.next_atom()? {
+        let text = scanner.text(idx);
+        match atom {
+            Atom::Char('+') if after_quantifier => {
+                // Possessive; drop.
+                after_quantifier = false;
+                continue;
+            }
+            Atom::Char('?') if after_quantifier => {
+                return Err(LexgenError::Unsupported(format!(
+                    "lazy quantifier in {branch:?}"
+                )));
+            }
+            Atom::Char('?') if after_open => {
+                let rest = &branch[idx + 1..];
+                if rest.starts_with(['=', '!', '<']) {
+                    if out == r"\s+(" && rest == r"!\S)" {
+                        out.push_str(r"?!\S)");
+                        break;
+                    }
+                    return Err(LexgenError::Unsupported(format!(
+                        "lookaround in {branch:?}"
+                    )));
+                }
+                out.push('?');
+                after_quantifier = false;
+            }
+            Atom::Char('^' | '$') => {
+                return Err(LexgenError::Unsupported(format!("anchor in {branch:?}")));
+            }
+            Atom::Char('+' | '*' | '?') | Atom::Counted => {
+                out.push_str(text);
+                after_quantifier = true;
+            }
+            _ => {
+                out.push_str(text);
+                after_quantifier = false;
+            }
+        }
+        after_open = atom == Atom::Char('(');
+    }
+
+    Ok(out)
+}
+
+/// Classify a normalized branch.
+pub fn classify_branch(regex: &str) -> BranchKind {
+    match regex {
+        r"\s+(?!\S)" => BranchKind::WhitespaceLookahead,
+        r"\s+" | r"\s" => BranchKind::Whitespace,
+        r"\s*[\r\n]+" | r"\s*[\r\n]" => BranchKind::Newline,
+        _ if regex.starts_with('\'') || regex.starts_with("(?i:'") => BranchKind::Contraction,
+        _ if regex.starts_with(" ?") => BranchKind::SpacePrefixed,
+        _ if has_optional_class_prefix(regex) => BranchKind::Word,
+        _ => BranchKind::Standalone,
+    }
+}
+
+/// Split, normalize and classify each branch of a pattern.
+pub fn analyze_pattern(pattern: &str) -> Result<Vec<Branch>, LexgenError> {
+    split_branches(pattern)?
+        .into_iter()
+        .map(|source| {
+            let regex = normalize_branch(source)?;
+            let kind = classify_branch(&regex);
+            Ok(Branch {
+                source: source.to_string(),
+                regex,
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Does the branch start with an optional character class, `[...]?`?
+fn has_optional_class_prefix(regex: &str) -> bool {
+    let mut scanner = Scanner::new(regex);
+    matches!(scanner.next_atom(), Ok(Some((0, Atom::Class))))
+        && matches!(scanner.next_atom(), Ok(Some((_, Atom::Char('?')))))
+}
+
+/// A lexical unit of a regex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Atom {
+    /// An unescaped char outside a class.
+    Char(char),
+
+    /// An escape; e.g. `\s` or `\p{L}`.
+    Escape,
+
+    /// A (possibly nested) character class.
+    Class,
+
+    /// A counted repetition; e.g. `{1,3}`.
+    Counted,
+}
+
+/// Walks a regex, one [`Atom`] at a time.
+struct Scanner<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    /// The source text of the atom starting at `start`, ending at the cursor.
+    fn text(
+        &self,
+        start: usize,
+    ) -> &'a str {
+        &self.src[start..self.pos]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn unterminated(
+        &self,
+        what: &str,
+    ) -> LexgenError {
+        LexgenError::Parse(format!("unterminated {what} in {:?}", self.src))
+    }
+
+    /// Consume an escape; the `\` is already consumed.
+    fn escape(&mut self) -> Result<(), LexgenError> {
+        let c = self.bump().ok_or_else(|| self.unterminated("escape"))?;
+        if matches!(c, 'p' | 'P' | 'x' | 'u') && self.peek() == Some('{') {
+            while self.bump().ok_or_else(|| self.unterminated("escape"))? != '}' {}
+        }
+        Ok(())
+    }
+
+    /// Consume a class; the `[` is already consumed.
+    fn class(&mut self) -> Result<(), LexgenError> {
+        if self.peek() == Some('^') {
+            self.bump();
+        }
+        // A leading `]` is a literal.
+        if self.peek() == Some(']') {
+            self.bump();
+        }
+        loop {
+            match self.bump().ok_or_else(|| self.unterminated("class"))? {
+                '\\' => self.escape()?,
+                '[' => self.class()?,
+                ']' => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    fn next_atom(&mut self) -> Result<Option<(usize, Atom)>, LexgenError> {
+        let start = self.pos;
+        let Some(c) = self.bump() else {
+            return Ok(None);
+        };
+        let atom = match c {
+            '\\' => {
+                self.escape()?;
+                Atom::Escape
+            }
+            '[' => {
+                self.class()?;
+                Atom::Class
+            }
+            '{' => {
+                while self.bump().ok_or_else(|| self.unterminated("repetition"))? != '}' {}
+                Atom::Counted
+            }
+            c => Atom::Char(c),
+        };
+        Ok(Some((start, atom)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wordchipper::pretrained::{
+        meta::META_LLAMA3_PATTERN,
+        openai::{OA_CL100K_BASE_PATTERN, OA_R50K_BASE_PATTERN_SLOW},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_split_branches() {
+        assert_eq!(
+            split_branches(r"a|(?i:b|c)|[|\]]|\||d").unwrap(),
+            vec!["a", "(?i:b|c)", r"[|\]]", r"\|", "d"]
+        );
+        assert!(split_branches("(a|b").is_err());
+        assert!(split_branches("[ab").is_err());
+    }
+
+    #[test]
+    fn test_normalize_branch() {
+        assert_eq!(
+            normalize_branch(r"[^\r\n\p{L}\p{N}]?+\p{L}++").unwrap(),
+            r"[^\r\n\p{L}\p{N}]?\p{L}+"
+        );
+        assert_eq!(normalize_branch(r"\p{N}{1,3}+").unwrap(), r"\p{N}{1,3}");
+        assert_eq!(normalize_branch(r"\s+(?!\S)").unwrap(), r"\s+(?!\S)");
+        assert_eq!(normalize_branch(r"[+*]\+").unwrap(), r"[+*]\+");
+
+        assert!(matches!(
+            normalize_branch(r"\s++$"),
+            Err(LexgenError::Unsupported(_))
+        ));
+        assert!(matches!(
+            normalize_branch(r"a+?"),
+            Err(LexgenError::Unsupported(_))
+        ));
+        assert!(matches!(
+            normalize_branch(r"a(?=b)"),
+            Err(LexgenError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_analyze_llama3() {
+        use BranchKind::*;
+
+        let kinds: Vec<BranchKind> = analyze_pattern(META_LLAMA3_PATTERN.as_str())
+            .unwrap()
+            .into_iter()
+            .map(|b| b.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Contraction,
+                Word,
+                Standalone,
+                SpacePrefixed,
+                Newline,
+                WhitespaceLookahead,
+                Whitespace,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_r50k() {
+        let branches = analyze_pattern(OA_R50K_BASE_PATTERN_SLOW.as_str()).unwrap();
+        assert_eq!(branches.len(), 12);
+        assert_eq!(branches[7].kind, BranchKind::SpacePrefixed);
+
+        // The optimized cl100k pattern anchors trailing whitespace.
+        assert!(analyze_pattern(OA_CL100K_BASE_PATTERN.as_str()).is_err());
+    }
+}
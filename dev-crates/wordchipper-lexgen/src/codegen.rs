@@ -0,0 +1,453 @@
+//! # Lexer Code Generation
+//!
+//! Plans the logos tokens for a pattern, and emits the lexer module.
+
+use std::fmt::Write;
+
+use crate::{
+    LexgenError,
+    branch::{Branch, BranchKind, analyze_pattern},
+    specs::LexerSpec,
+};
+
+/// A planned logos token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenPlan {
+    /// The enum variant name.
+    pub variant: String,
+
+    /// The logos regex.
+    pub regex: String,
+
+    /// The `TokenRole` expression.
+    pub role: &'static str,
+
+    /// The pattern branches this token implements.
+    pub sources: Vec<String>,
+}
+
+/// Plan the logos tokens for a pattern.
+///
+/// Non-whitespace branches map 1:1 onto tokens, in pattern order. The
+/// whitespace branches fold into a trailing `Newline` (if the pattern has a
+/// `\s*[\r\n]+` branch) and `Whitespace` token; the span engine implements
+/// the `\s+(?!\S)` lookahead.
+///
+/// # Arguments
+/// * `pattern` - the regex pattern.
+///
+/// # Returns
+/// The tokens; or an error if the pattern has no logos equivalent.
+pub fn plan_tokens(pattern: &str) -> Result<Vec<TokenPlan>, LexgenError> {
+    let branches = analyze_pattern(pattern)?;
+    let has = |kind: BranchKind| branches.iter().any(|b| b.kind == kind);
+    let sources_of = |kinds: &[BranchKind]| -> Vec<String> {
+        branches
+            .iter()
+            .filter(|b| kinds.contains(&b.kind))
+            .map(|b| b.source.clone())
+            .collect()
+    };
+
+    if !has(BranchKind::WhitespaceLookahead) || !has(BranchKind::Whitespace) {
+        return Err(LexgenError::Unsupported(format!(
+            "expected `\\s+(?!\\S)` and `\\s+` branches in {pattern:?}"
+        )));
+    }
+    if let Some(last) = branches.last()
+        && last.kind != BranchKind::Whitespace
+    {
+        return Err(LexgenError::Unsupported(format!(
+            "expected a trailing `\\s+` branch; found {:?}",
+            last.source
+        )));
+    }
+
+    let word_role = if has(BranchKind::Contraction) {
+        "TokenRole::Word {\n                check_contraction: true,\n            }"
+    } else {
+        "TokenRole::Word {\n                check_contraction: false,\n            }"
+    };
+
+    let mut tokens = Vec::new();
+    for branch in &branches {
+        let (base, role) = match branch.kind {
+            BranchKind::Contraction => ("Contraction", "TokenRole::Standalone"),
+            BranchKind::Word => ("Word", word_role),
+            BranchKind::SpacePrefixed => ("Punctuation", "TokenRole::Punctuation"),
+            BranchKind::Standalone => ("Standalone", "TokenRole::Standalone"),
+            BranchKind::Newline | BranchKind::WhitespaceLookahead | BranchKind::Whitespace => {
+                continue;
+            }
+        };
+        tokens.push(TokenPlan {
+            variant: variant_name(&branches, branch, base),
+            regex: branch.regex.clone(),
+            role,
+            sources: vec![branch.source.clone()],
+        });
+    }
+
+    if has(BranchKind::Newline) {
+        tokens.push(TokenPlan {
+            variant: "Newline".to_string(),
+            regex: r"\s*[\r\n]+".to_string(),
+            role: "TokenRole::Standalone",
+            sources: sources_of(&[BranchKind::Newline]),
+        });
+    }
+    tokens.push(TokenPlan {
+        variant: "Whitespace".to_string(),
+        regex: if has(BranchKind::Newline) {
+            r"[^\S\r\n]+"
+        } else {
+            r"\s+"
+        }
+        .to_string(),
+        role: "TokenRole::Whitespace",
+        sources: sources_of(&[BranchKind::WhitespaceLookahead, BranchKind::Whitespace]),
+    });
+
+    Ok(tokens)
+}
+
+/// Name a variant by its kind; numbered if the kind repeats.
+fn variant_name(
+    branches: &[Branch],
+    branch: &Branch,
+    base: &str,
+) -> String {
+    let same: Vec<&Branch> = branches.iter().filter(|b| b.kind == branch.kind).collect();
+    if same.len() == 1 {
+        return base.to_string();
+    }
+    let n = same.iter().position(|b| std::ptr::eq(*b, branch)).unwrap() + 1;
+    format!("{base}{n}")
+}
+
+/// Fixed differential test inputs, as Rust string literals.
+const TEST_CASES: &[&str] = &[
+    r#""Hello world""#,
+    r#""don't I'LL she's 'd 'dog""#,
+    r#""price is 1234567 dollars, \u{0663}\u{0664} \u{00bd}""#,
+    r#""caf\u{00e9} na\u{00ef}ve \u{4f60}\u{597d}\u{4e16}\u{754c}""#,
+    r#""  \nfoo  \n\n bar   ""#,
+    r#""\t 'd\t\t[A \u{2000}\u{09d7}\u{a640}""#,
+    r#""Shakespeare's \"sources,\" then read""#,
+    r#""  $400 dollars\r\n\r\n""#,
+];
+
+/// A proptest strategy biased towards whitespace / contraction interactions.
+const TEST_ALPHABET: &str =
+    r"[ \t\r\na'sStTdDlLvVeE1\u{663}\u{bd}\u{e9}\u{4f60}\u{a640}.,!$\u{2000}\u{301}]{0,40}";
+
+/// Quote a regex as a raw string literal.
+fn raw_string(s: &str) -> String {
+    let mut hashes = String::new();
+    while s.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{s}\"{hashes}")
+}
+
+/// Generate the lexer module for a spec.
+///
+/// # Arguments
+/// * `spec` - the lexer spec.
+///
+/// # Returns
+/// The module source.
+pub fn generate(spec: &LexerSpec) -> Result<String, LexgenError> {
+    let tokens = plan_tokens(spec.pattern.as_str())?;
+
+    let module_path = spec.pattern_path.strip_prefix("crate::").ok_or_else(|| {
+        LexgenError::Parse(format!(
+            "pattern path must start with `crate::`: {}",
+            spec.pattern_path
+        ))
+    })?;
+
+    let name = spec.name;
+    let id = spec.id;
+    let pattern = spec.pattern_name();
+    let token = format!("{name}Token");
+    let lexer = format!("{name}Lexer");
+
+    let mut out = String::new();
+    let w = &mut out;
+
+    // Header.
+    writeln!(w, "//! # {name} Logos Lexer").unwrap();
+    writeln!(w, "//!").unwrap();
+    writeln!(w, "//! Compile-time DFA lexer for {}.", spec.description).unwrap();
+    writeln!(w, "//!").unwrap();
+    writeln!(
+        w,
+        "//! Generated by `wordchipper-lexgen` from [`{pattern}`]; do not edit."
+    )
+    .unwrap();
+    writeln!(
+        w,
+        "//! Regenerate with `cargo run -p wordchipper-lexgen -- --write {id}`."
+    )
+    .unwrap();
+    writeln!(w).unwrap();
+
+    // Imports.
+    let mut crate_imports = [
+        "alloc::sync::Arc".to_string(),
+        module_path.to_string(),
+        "spanners::{\n        SpanRef,\n        span_lexers::{SpanLexer, accelerators::RegexAcceleratorHook},\n    }"
+            .to_string(),
+    ];
+    crate_imports.sort();
+    writeln!(w, "use logos::Logos;").unwrap();
+    writeln!(w).unwrap();
+    writeln!(
+        w,
+        "use super::{{engine::for_each_classified_span, token_role::TokenRole}};"
+    )
+    .unwrap();
+    writeln!(w, "use crate::{{").unwrap();
+    for import in &crate_imports {
+        writeln!(w, "    {import},").unwrap();
+    }
+    writeln!(w, "}};").unwrap();
+    writeln!(w).unwrap();
+
+    // Token enum.
+    writeln!(w, "/// Logos token for the [`{pattern}`] branches.").unwrap();
+    writeln!(w, "///").unwrap();
+    writeln!(w, "/// | Regex branch | Logos variant |").unwrap();
+    writeln!(w, "/// |--------------|---------------|").unwrap();
+    for token in &tokens {
+        for source in &token.sources {
+            writeln!(
+                w,
+                "/// | `{}` | `{}` |",
+                source.replace('|', "\\|"),
+                token.variant
+            )
+            .unwrap();
+        }
+    }
+    writeln!(w, "#[derive(Logos, Debug, PartialEq, Clone)]").unwrap();
+    writeln!(w, "enum {token} {{").unwrap();
+    for (idx, token) in tokens.iter().enumerate() {
+        if idx > 0 {
+            writeln!(w).unwrap();
+        }
+        writeln!(w, "    #[regex({})]", raw_string(&token.regex)).unwrap();
+        writeln!(w, "    {},", token.variant).unwrap();
+    }
+    writeln!(w, "}}").unwrap();
+    writeln!(w).unwrap();
+
+    // Roles.
+    writeln!(w, "impl {token} {{").unwrap();
+    writeln!(w, "    fn role(&self) -> TokenRole {{").unwrap();
+    writeln!(w, "        match self {{").unwrap();
+    for token in &tokens {
+        writeln!(w, "            Self::{} => {},", token.variant, token.role).unwrap();
+    }
+    writeln!(w, "        }}").unwrap();
+    writeln!(w, "    }}").unwrap();
+    writeln!(w, "}}").unwrap();
+    writeln!(w).unwrap();
+
+    // Lexer.
+    write!(
+        w,
+        r#"/// A [`SpanLexer`] for {description}.
+///
+/// Uses a compile-time logos DFA for word scanning.
+///
+/// Only matches the regex spans; does not match the special tokens.
+#[derive(Clone, Debug)]
+pub struct {lexer};
+
+inventory::submit! {{
+    RegexAcceleratorHook::new({pattern}, || Arc::new({lexer}))
+}}
+
+impl SpanLexer for {lexer} {{
+    fn next_span(
+        &self,
+        text: &str,
+        offset: usize,
+    ) -> Option<(usize, usize)> {{
+        let mut next_span: Option<(usize, usize)> = None;
+        self.for_each_word(text, offset, &mut |span_ref| match span_ref {{
+            SpanRef::Word(r) => {{
+                next_span = Some((r.start, r.end));
+                false
+            }}
+            _ => true,
+        }});
+        next_span
+    }}
+
+    fn for_each_word(
+        &self,
+        text: &str,
+        offset: usize,
+        f: &mut dyn FnMut(SpanRef) -> bool,
+    ) -> (bool, usize) {{
+        for_each_classified_span(
+            {token}::lexer(text).spanned().map(|(res, range)| {{
+                let role = match res {{
+                    Ok(tok) => tok.role(),
+                    Err(()) => TokenRole::Gap,
+                }};
+                (role, range)
+            }}),
+            text,
+            offset,
+            f,
+        )
+    }}
+}}
+
+"#,
+        description = spec.description,
+    )
+    .unwrap();
+
+    // Tests.
+    let mut cases = String::new();
+    for case in TEST_CASES {
+        writeln!(cases, "            {case},").unwrap();
+    }
+    write!(
+        w,
+        r#"#[cfg(all(test, feature = "std"))]
+mod tests {{
+    use super::*;
+    use crate::{{
+        alloc::sync::Arc,
+        spanners::{{TextSpanner, span_lexers::LexerTextSpanner}},
+    }};
+
+    fn spanner() -> LexerTextSpanner {{
+        LexerTextSpanner::new(Arc::new({lexer}), None)
+    }}
+
+    /// The reference spanner; the compiled regex, without accelerators.
+    fn regex_spanner() -> LexerTextSpanner {{
+        LexerTextSpanner::new(Arc::new({pattern}.compile().unwrap()), None)
+    }}
+
+    #[test]
+    fn test_logos_{id}_accelerated() {{
+        use crate::spanners::span_lexers::accelerators::get_regex_accelerator;
+
+        assert!(get_regex_accelerator({pattern}.as_str()).is_some());
+    }}
+
+    #[test]
+    fn test_logos_{id}_matches_regex() {{
+        let regex_spanner = regex_spanner();
+        let logos_spanner = spanner();
+
+        let cases = [
+{cases}        ];
+
+        for text in cases {{
+            let regex_spans = regex_spanner.split_spans(text);
+            let logos_spans = logos_spanner.split_spans(text);
+            assert_eq!(
+                regex_spans, logos_spans,
+                "{id} mismatch for {{:?}}:\n  regex: {{:?}}\n  logos: {{:?}}",
+                text, regex_spans, logos_spans
+            );
+        }}
+    }}
+
+    #[test]
+    fn proptest_{id}_logos_matches_regex() {{
+        use proptest::prelude::*;
+
+        let regex_spanner = regex_spanner();
+        let logos_spanner = spanner();
+
+        let config = proptest::test_runner::Config::with_cases(2000);
+        proptest!(config, |(text in "\\PC{{0,200}}|{alphabet}")| {{
+            let regex_spans = regex_spanner.split_spans(&text);
+            let logos_spans = logos_spanner.split_spans(&text);
+            prop_assert_eq!(
+                &regex_spans, &logos_spans,
+                "{id} mismatch for {{:?}}",
+                text
+            );
+        }});
+    }}
+}}
+"#,
+        alphabet = TEST_ALPHABET.replace('\\', "\\\\"),
+    )
+    .unwrap();
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use wordchipper::pretrained::{meta::META_LLAMA3_PATTERN, openai::OA_R50K_BASE_PATTERN_SLOW};
+
+    use super::*;
+
+    fn variants(pattern: &str) -> Vec<(String, &'static str)> {
+        plan_tokens(pattern)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.variant, t.role))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_llama3() {
+        let tokens = plan_tokens(META_LLAMA3_PATTERN.as_str()).unwrap();
+        let names: Vec<&str> = tokens.iter().map(|t| t.variant.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Contraction",
+                "Word",
+                "Standalone",
+                "Punctuation",
+                "Newline",
+                "Whitespace"
+            ]
+        );
+        assert!(tokens[1].role.contains("check_contraction: true"));
+        assert_eq!(tokens[5].regex, r"[^\S\r\n]+");
+        assert_eq!(tokens[5].sources, vec![r"\s+(?!\S)", r"\s+"]);
+    }
+
+    #[test]
+    fn test_plan_r50k() {
+        let tokens = variants(OA_R50K_BASE_PATTERN_SLOW.as_str());
+        assert_eq!(tokens[0].0, "Contraction1");
+        assert_eq!(tokens[7].0, "Punctuation1");
+        assert_eq!(tokens.last().unwrap().0, "Whitespace");
+        assert_eq!(tokens.last().unwrap().1, "TokenRole::Whitespace");
+    }
+
+    #[test]
+    fn test_plan_requires_whitespace_branches() {
+        assert!(matches!(
+            plan_tokens(r"\p{L}+|\s+"),
+            Err(LexgenError::Unsupported(_))
+        ));
+        assert!(matches!(
+            plan_tokens(r"\s+(?!\S)|\s+|\p{L}+"),
+            Err(LexgenError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_raw_string() {
+        assert_eq!(raw_string(r"\s+"), r#"r"\s+""#);
+        assert_eq!(raw_string(r#"["]"#), r##"r#"["]"#"##);
+    }
+}
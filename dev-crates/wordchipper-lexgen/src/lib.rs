@@ -0,0 +1,51 @@
+//! # wordchipper-lexgen
+//!
+//! Generates logos accelerated lexers from `join_patterns!` regex patterns.
+//!
+//! Each top-level branch of the pattern is classified by how it interacts
+//! with whitespace (see [`branch::BranchKind`]); and mapped onto a logos token
+//! and a `TokenRole`. The whitespace idioms (`\s+(?!\S)`, `\s+`, `\s*[\r\n]+`)
+//! are folded into the `Newline` / `Whitespace` tokens the span engine expects.
+//!
+//! The generated module includes a differential test against the compiled
+//! regex (`RegexWrapper`); so a misclassified pattern fails `cargo test`.
+//!
+//! Lexers are registered in [`specs::LEXER_SPECS`]; generate with:
+//!
+//! ```terminaloutput
+//! $ cargo run -p wordchipper-lexgen -- --write <id>
+//! ```
+
+pub mod branch;
+pub mod codegen;
+pub mod specs;
+
+use std::path::PathBuf;
+
+/// Errors from lexer generation.
+#[derive(Debug, thiserror::Error)]
+pub enum LexgenError {
+    /// The pattern could not be parsed.
+    #[error("parse error: {0}")]
+    Parse(String),
+
+    /// The pattern uses a construct with no logos equivalent.
+    #[error("unsupported pattern: {0}")]
+    Unsupported(String),
+
+    /// No spec with the given id.
+    #[error("unknown lexer: {0}")]
+    UnknownLexer(String),
+
+    /// IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The workspace root; spec output paths are relative to this.
+pub fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .canonicalize()
+        .expect("workspace root")
+}
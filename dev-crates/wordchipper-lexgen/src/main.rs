@@ -0,0 +1,66 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use wordchipper_lexgen::{
+    LexgenError,
+    codegen::generate,
+    specs::{LEXER_SPECS, LexerSpec, find_spec},
+    workspace_root,
+};
+
+/// Generate logos accelerated lexers.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Lexer ids to generate; defaults to all.
+    pub lexers: Vec<String>,
+
+    /// List the lexer specs.
+    #[arg(long, default_value_t = false)]
+    pub list: bool,
+
+    /// Write each lexer to its spec output file, rather than stdout.
+    #[arg(long, default_value_t = false)]
+    pub write: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    if args.list {
+        for spec in LEXER_SPECS {
+            println!("{}: {} -> {}", spec.id, spec.pattern_name(), spec.output);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), LexgenError> {
+    let specs: Vec<&LexerSpec> = if args.lexers.is_empty() {
+        LEXER_SPECS.iter().collect()
+    } else {
+        args.lexers
+            .iter()
+            .map(|id| find_spec(id))
+            .collect::<Result<_, _>>()?
+    };
+
+    for spec in specs {
+        let source = generate(spec)?;
+
+        if args.write {
+            std::fs::write(workspace_root().join(spec.output), source)?;
+            println!("wrote: {}", spec.output);
+        } else {
+            print!("{source}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! # Lexer Specs
+//!
+//! The registry of lexer specs.
+//!
+//! Each spec's `output` file is checked in as generated; a test fails
+//! when the generator and the checked-in file disagree.
+
+use wordchipper::{pretrained::qwen::QWEN2_PATTERN, support::regex::ConstRegexPattern};
+
+use crate::LexgenError;
+
+/// A lexer to generate.
+#[derive(Debug, Clone, Copy)]
+pub struct LexerSpec {
+    /// The CLI id; also used in generated test names.
+    pub id: &'static str,
+
+    /// The type prefix; e.g. `Qwen2` for `Qwen2Lexer` / `Qwen2Token`.
+    pub name: &'static str,
+
+    /// What the pattern tokenizes, for the generated docs.
+    pub description: &'static str,
+
+    /// The crate path of the pattern constant, as seen from `wordchipper`.
+    pub pattern_path: &'static str,
+
+    /// The pattern.
+    pub pattern: ConstRegexPattern,
+
+    /// The `--write` output file, relative to the workspace root.
+    pub output: &'static str,
+}
+
+impl LexerSpec {
+    /// The pattern constant name; the last segment of `pattern_path`.
+    pub fn pattern_name(&self) -> &'static str {
+        self.pattern_path
+            .rsplit("::")
+            .next()
+            .unwrap_or(self.pattern_path)
+    }
+}
+
+/// The registered lexer specs.
+pub const LEXER_SPECS: &[LexerSpec] = &[LexerSpec {
+    id: "qwen2",
+    name: "Qwen2",
+    description: "the `Qwen2` pattern (Qwen2, Qwen2.5, Qwen3)",
+    pattern_path: "crate::pretrained::qwen::QWEN2_PATTERN",
+    pattern: QWEN2_PATTERN,
+    output: "crates/wordchipper/src/spanners/span_lexers/logos/qwen2.rs",
+}];
+
+/// Find a spec by id.
+pub fn find_spec(id: &str) -> Result<&'static LexerSpec, LexgenError> {
+    LEXER_SPECS
+        .iter()
+        .find(|spec| spec.id == id)
+        .ok_or_else(|| LexgenError::UnknownLexer(id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{codegen::generate, workspace_root};
+
+    #[test]
+    fn test_find_spec() {
+        assert_eq!(find_spec("qwen2").unwrap().pattern_name(), "QWEN2_PATTERN");
+        assert!(matches!(
+            find_spec("nope"),
+            Err(LexgenError::UnknownLexer(_))
+        ));
+    }
+
+    #[test]
+    fn test_generate_specs() {
+        for spec in LEXER_SPECS {
+            let source = generate(spec).unwrap();
+            assert!(source.contains(&format!("pub struct {}Lexer;", spec.name)));
+            assert!(source.contains(&format!("enum {}Token {{", spec.name)));
+            assert!(source.contains(&format!("fn proptest_{}_logos_matches_regex()", spec.id)));
+
+            let checked_in = std::fs::read_to_string(workspace_root().join(spec.output)).unwrap();
+            assert!(
+                source == checked_in,
+                "{} is stale; regenerate with `cargo run -p wordchipper-lexgen -- --write {}`",
+                spec.output,
+                spec.id
+            );
+        }
+    }
+}